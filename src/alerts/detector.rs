@@ -1,7 +1,14 @@
 use crate::config::AlertsConfig;
+use crate::gpu::{GPUInfo, GPUSensor};
+use crate::metrics::{CpuBreakdown, DiskIo, PressureMetrics, PressureResource, SensorKind, SensorReading};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::collections::HashMap;
 
+/// Critical level of GPU sensors that report no limit of their own
+const GPU_TEMP_CRITICAL_FALLBACK: f32 = 85.0;
+/// Critical level of CPU and board sensors that report no limit of their own
+const SENSOR_TEMP_CRITICAL_FALLBACK: f32 = 95.0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AlertLevel {
     Info,
@@ -14,9 +21,18 @@ pub enum AlertType {
     CpuUsage,
     MemoryUsage,
     SwapUsage,
-    GpuTemperature { gpu_id: usize },
-    GpuUtilization { gpu_id: usize },
-    GpuMemory { gpu_id: usize },
+    CpuSteal,
+    CpuIowait,
+    /// Pressure stall of a resource, system-wide or in one cgroup
+    Pressure { resource: PressureResource, full: bool, cgroup: Option<String> },
+    // GPU alerts are keyed by device key so cooldowns follow the card
+    GpuTemperature { gpu_key: String, sensor: String },
+    GpuUtilization { gpu_key: String },
+    GpuMemory { gpu_key: String },
+    GpuGtt { gpu_key: String },
+    DiskUtilization { device: String },
+    DiskLatency { device: String },
+    SensorTemperature { sensor: String },
 }
 
 #[derive(Debug, Clone)]
@@ -67,9 +83,19 @@ impl AlertDetector {
             AlertType::CpuUsage => "cpu".to_string(),
             AlertType::MemoryUsage => "memory".to_string(),
             AlertType::SwapUsage => "swap".to_string(),
-            AlertType::GpuTemperature { gpu_id } => format!("gpu_{}_temp", gpu_id),
-            AlertType::GpuUtilization { gpu_id } => format!("gpu_{}_util", gpu_id),
-            AlertType::GpuMemory { gpu_id } => format!("gpu_{}_mem", gpu_id),
+            AlertType::CpuSteal => "cpu_steal".to_string(),
+            AlertType::CpuIowait => "cpu_iowait".to_string(),
+            AlertType::Pressure { resource, full, cgroup } => format!("psi_{}_{}_{}",
+                resource.name(),
+                if *full { "full" } else { "some" },
+                cgroup.as_deref().unwrap_or("system")),
+            AlertType::GpuTemperature { gpu_key, sensor } => format!("gpu_{}_temp_{}", gpu_key, sensor),
+            AlertType::GpuUtilization { gpu_key } => format!("gpu_{}_util", gpu_key),
+            AlertType::GpuMemory { gpu_key } => format!("gpu_{}_mem", gpu_key),
+            AlertType::GpuGtt { gpu_key } => format!("gpu_{}_gtt", gpu_key),
+            AlertType::DiskUtilization { device } => format!("disk_{}_util", device),
+            AlertType::DiskLatency { device } => format!("disk_{}_latency", device),
+            AlertType::SensorTemperature { sensor } => format!("sensor_{}", sensor),
         }
     }
 
@@ -95,7 +121,7 @@ impl AlertDetector {
         mem_usage: f32,
         swap_usage: f32,
         swap_total: u64,
-        gpu_infos: &[crate::gpu::GPUInfo],
+        gpu_infos: &[GPUInfo],
     ) -> Vec<Alert> {
         if !self.config.enabled {
             return Vec::new();
@@ -154,23 +180,27 @@ impl AlertDetector {
         }
 
         // Check GPU
-        for (i, gpu_info) in gpu_infos.iter().enumerate() {
+        for gpu_info in gpu_infos {
                 // GPU Temperature
-                if let Some(temp) = gpu_info.temperature {
-                    if temp > self.config.gpu_temp_threshold {
-                        let level = if temp > 85.0 {
+                for sensor in self.temperature_sensors(gpu_info) {
+                    let threshold = self.config.gpu_temp_limit(sensor.critical);
+                    if sensor.temperature > threshold {
+                        let level = if sensor.temperature >= sensor.critical.unwrap_or(GPU_TEMP_CRITICAL_FALLBACK) {
                             AlertLevel::Critical
                         } else {
                             AlertLevel::Warning
                         };
 
                         let alert = Alert::new(
-                            AlertType::GpuTemperature { gpu_id: i },
+                            AlertType::GpuTemperature {
+                                gpu_key: gpu_info.key.clone(),
+                                sensor: sensor.label.clone(),
+                            },
                             level,
-                            temp,
-                            self.config.gpu_temp_threshold,
-                            format!("GPU {} temperature at {:.1}°C (threshold: {:.1}°C)", 
-                                i, temp, self.config.gpu_temp_threshold),
+                            sensor.temperature,
+                            threshold,
+                            format!("{} {} temperature at {:.1}°C (threshold: {:.1}°C)", 
+                                gpu_info.name, sensor.label, sensor.temperature, threshold),
                         );
                         new_alerts.push(alert);
                     }
@@ -180,12 +210,12 @@ impl AlertDetector {
                 if let Some(util) = gpu_info.utilization {
                     if util > self.config.gpu_util_threshold {
                         let alert = Alert::new(
-                            AlertType::GpuUtilization { gpu_id: i },
+                            AlertType::GpuUtilization { gpu_key: gpu_info.key.clone() },
                             AlertLevel::Info,
                             util,
                             self.config.gpu_util_threshold,
-                            format!("GPU {} utilization at {:.1}% (threshold: {:.1}%)", 
-                                i, util, self.config.gpu_util_threshold),
+                            format!("{} utilization at {:.1}% (threshold: {:.1}%)", 
+                                gpu_info.name, util, self.config.gpu_util_threshold),
                         );
                         new_alerts.push(alert);
                     }
@@ -195,12 +225,27 @@ impl AlertDetector {
                 if let Some(mem_percent) = gpu_info.memory_usage_percent() {
                     if mem_percent > self.config.gpu_mem_threshold {
                         let alert = Alert::new(
-                            AlertType::GpuMemory { gpu_id: i },
+                            AlertType::GpuMemory { gpu_key: gpu_info.key.clone() },
                             AlertLevel::Warning,
                             mem_percent,
                             self.config.gpu_mem_threshold,
-                            format!("GPU {} memory at {:.1}% (threshold: {:.1}%)", 
-                                i, mem_percent, self.config.gpu_mem_threshold),
+                            format!("{} memory at {:.1}% (threshold: {:.1}%)", 
+                                gpu_info.name, mem_percent, self.config.gpu_mem_threshold),
+                        );
+                        new_alerts.push(alert);
+                    }
+                }
+
+                // GPU GTT: VRAM overflow or APU memory in system RAM
+                if let Some(gtt_percent) = gpu_info.gtt_usage_percent() {
+                    if gtt_percent > self.config.gpu_gtt_threshold {
+                        let alert = Alert::new(
+                            AlertType::GpuGtt { gpu_key: gpu_info.key.clone() },
+                            AlertLevel::Warning,
+                            gtt_percent,
+                            self.config.gpu_gtt_threshold,
+                            format!("{} GTT at {:.1}% (threshold: {:.1}%)", 
+                                gpu_info.name, gtt_percent, self.config.gpu_gtt_threshold),
                         );
                         new_alerts.push(alert);
                     }
@@ -211,6 +256,185 @@ impl AlertDetector {
         new_alerts
     }
 
+    /// Check steal and iowait shares of all CPUs. The alerts are added to
+    /// the active ones from the latest `check_alerts`.
+    pub fn check_cpu_time_alerts(&mut self, cpu: &CpuBreakdown) -> Vec<Alert> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let mut new_alerts = Vec::new();
+        if cpu.steal > self.config.cpu_steal_threshold {
+            new_alerts.push(Alert::new(
+                AlertType::CpuSteal,
+                AlertLevel::Warning,
+                cpu.steal,
+                self.config.cpu_steal_threshold,
+                format!("CPU steal time at {:.1}% (threshold: {:.1}%)", cpu.steal, self.config.cpu_steal_threshold),
+            ));
+        }
+
+        if cpu.iowait > self.config.cpu_iowait_threshold {
+            new_alerts.push(Alert::new(
+                AlertType::CpuIowait,
+                AlertLevel::Warning,
+                cpu.iowait,
+                self.config.cpu_iowait_threshold,
+                format!("CPU iowait at {:.1}% (threshold: {:.1}%)", cpu.iowait, self.config.cpu_iowait_threshold),
+            ));
+        }
+
+        self.active_alerts.extend(new_alerts.iter().cloned());
+        new_alerts
+    }
+
+    /// Check pressure stall averages. The alerts are added to the active
+    /// ones from the latest `check_alerts`.
+    pub fn check_pressure_alerts(&mut self, psi: &PressureMetrics) -> Vec<Alert> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let checks = [
+            (PressureResource::Cpu, false, self.config.psi_cpu_some_threshold),
+            (PressureResource::Memory, true, self.config.psi_memory_full_threshold),
+            (PressureResource::Io, true, self.config.psi_io_full_threshold),
+        ];
+
+        let mut new_alerts = Vec::new();
+        for (resource, full, threshold) in checks {
+            let Some(pressure) = psi.get(resource) else { continue };
+            let line = if full { pressure.full } else { pressure.some };
+            if let Some(line) = line.filter(|line| line.avg10 > threshold) {
+                new_alerts.push(Self::pressure_alert(resource, full, None, line.avg10, threshold));
+            }
+        }
+
+        // A container or service close to its memory limit stalls long
+        // before the whole system does
+        let threshold = self.config.psi_memory_full_threshold;
+        for cgroup in psi.cgroups() {
+            let full = cgroup.get(PressureResource::Memory).and_then(|p| p.full);
+            if let Some(line) = full.filter(|line| line.avg10 > threshold) {
+                new_alerts.push(Self::pressure_alert(
+                    PressureResource::Memory, true, Some(cgroup.path.clone()), line.avg10, threshold,
+                ));
+            }
+        }
+
+        self.active_alerts.extend(new_alerts.iter().cloned());
+        new_alerts
+    }
+
+    fn pressure_alert(resource: PressureResource, full: bool, cgroup: Option<String>, avg10: f32, threshold: f32) -> Alert {
+        // Memory "full" stalls precede the OOM killer
+        let level = if resource == PressureResource::Memory && full && avg10 > threshold * 2.0 {
+            AlertLevel::Critical
+        } else {
+            AlertLevel::Warning
+        };
+        let scope = cgroup.as_deref().map(|path| format!(" in {}", path)).unwrap_or_default();
+        let message = format!("{} pressure ({}){} at {:.1}% (threshold: {:.1}%)",
+            resource.name(), if full { "full" } else { "some" }, scope, avg10, threshold);
+        Alert::new(AlertType::Pressure { resource, full, cgroup }, level, avg10, threshold, message)
+    }
+
+    /// Check block devices. The alerts are added to the active ones from
+    /// the latest `check_alerts`.
+    pub fn check_disk_alerts(&mut self, disks: &[DiskIo]) -> Vec<Alert> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let mut new_alerts = Vec::new();
+        for disk in disks {
+            if disk.utilization > self.config.disk_util_threshold {
+                new_alerts.push(Alert::new(
+                    AlertType::DiskUtilization { device: disk.name.clone() },
+                    AlertLevel::Warning,
+                    disk.utilization,
+                    self.config.disk_util_threshold,
+                    format!("Disk {} busy {:.1}% of the time (threshold: {:.1}%)",
+                        disk.name, disk.utilization, self.config.disk_util_threshold),
+                ));
+            }
+
+            if let Some(latency) = disk.avg_latency_ms {
+                if latency > self.config.disk_latency_threshold_ms {
+                    // Ten times the threshold means requests are stalling
+                    let level = if latency > self.config.disk_latency_threshold_ms * 10.0 {
+                        AlertLevel::Critical
+                    } else {
+                        AlertLevel::Warning
+                    };
+                    new_alerts.push(Alert::new(
+                        AlertType::DiskLatency { device: disk.name.clone() },
+                        level,
+                        latency,
+                        self.config.disk_latency_threshold_ms,
+                        format!("Disk {} latency {:.1} ms (threshold: {:.1} ms)",
+                            disk.name, latency, self.config.disk_latency_threshold_ms),
+                    ));
+                }
+            }
+        }
+
+        self.active_alerts.extend(new_alerts.iter().cloned());
+        new_alerts
+    }
+
+    /// Check CPU and board temperatures. The alerts are added to the active
+    /// ones from the latest `check_alerts`.
+    pub fn check_sensor_alerts(&mut self, readings: &[SensorReading]) -> Vec<Alert> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let mut new_alerts = Vec::new();
+        for reading in readings.iter().filter(|r| r.kind == SensorKind::Temperature) {
+            let threshold = self.config.sensor_temp_limit(reading.critical);
+            if reading.value > threshold {
+                let level = if reading.value >= reading.critical.unwrap_or(SENSOR_TEMP_CRITICAL_FALLBACK) {
+                    AlertLevel::Critical
+                } else {
+                    AlertLevel::Warning
+                };
+                new_alerts.push(Alert::new(
+                    AlertType::SensorTemperature { sensor: reading.key() },
+                    level,
+                    reading.value,
+                    threshold,
+                    format!("{} {} temperature at {:.1}°C (threshold: {:.1}°C)",
+                        reading.chip, reading.label, reading.value, threshold),
+                ));
+            }
+        }
+
+        self.active_alerts.extend(new_alerts.iter().cloned());
+        new_alerts
+    }
+
+    /// Sensors the temperature alert applies to. The configured sensor is
+    /// used when the GPU has it; otherwise the primary temperature stands in.
+    fn temperature_sensors(&self, gpu_info: &GPUInfo) -> Vec<GPUSensor> {
+        let selected: Vec<GPUSensor> = gpu_info.sensors.iter()
+            .filter(|sensor| self.config.gpu_temp_sensor.as_ref()
+                .is_none_or(|label| sensor.label.eq_ignore_ascii_case(label)))
+            .cloned()
+            .collect();
+        if !selected.is_empty() {
+            return selected;
+        }
+
+        gpu_info.temperature.into_iter()
+            .map(|temperature| GPUSensor {
+                label: "gpu".to_string(),
+                temperature,
+                critical: None,
+            })
+            .collect()
+    }
+
     pub fn active_alerts(&self) -> &[Alert] {
         &self.active_alerts
     }
@@ -231,3 +455,73 @@ impl AlertDetector {
             .count()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::gpu::GPUSample;
+
+    #[test]
+    fn test_gpu_temperature_uses_sensor_critical() {
+        let sensor = |label: &str, temperature, critical| GPUSensor {
+            label: label.to_string(),
+            temperature,
+            critical,
+        };
+        let sample = GPUSample {
+            temperature: Some(70.0),
+            sensors: vec![sensor("edge", 70.0, Some(100.0)), sensor("junction", 111.0, Some(110.0))],
+            ..GPUSample::new("Radeon RX 7800 XT".to_string(), "AMD")
+        };
+        let gpus = [GPUInfo::from_sample(0, "0000:03:00.0".to_string(), sample)];
+
+        let mut config = Config::default().alerts;
+        let alerts = AlertDetector::new(config.clone()).check_alerts(0.0, 0.0, 0.0, 0, &gpus);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].level, AlertLevel::Critical);
+        assert_eq!(alerts[0].threshold, 100.0);
+
+        // Within 10 °C of the critical limit warns before it turns critical
+        let sample = GPUSample {
+            sensors: vec![sensor("edge", 70.0, Some(100.0)), sensor("junction", 104.0, Some(110.0))],
+            ..GPUSample::new("Radeon RX 7800 XT".to_string(), "AMD")
+        };
+        let warm = [GPUInfo::from_sample(0, "0000:03:00.0".to_string(), sample)];
+        let alerts = AlertDetector::new(config.clone()).check_alerts(0.0, 0.0, 0.0, 0, &warm);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].level, AlertLevel::Warning);
+        assert!(matches!(&alerts[0].alert_type, AlertType::GpuTemperature { sensor, .. } if sensor == "junction"));
+
+        config.gpu_temp_threshold = Some(65.0);
+        config.gpu_temp_sensor = Some("edge".to_string());
+        let alerts = AlertDetector::new(config).check_alerts(0.0, 0.0, 0.0, 0, &gpus);
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(&alerts[0].alert_type, AlertType::GpuTemperature { sensor, .. } if sensor == "edge"));
+        assert_eq!(alerts[0].level, AlertLevel::Warning);
+    }
+
+    #[test]
+    fn test_sensor_temperature_warns_below_critical() {
+        let reading = |value| SensorReading {
+            chip: "coretemp".to_string(),
+            label: "Package id 0".to_string(),
+            kind: SensorKind::Temperature,
+            value,
+            critical: Some(100.0),
+        };
+        let config = Config::default().alerts;
+
+        let alerts = AlertDetector::new(config.clone()).check_sensor_alerts(&[reading(89.0)]);
+        assert!(alerts.is_empty());
+
+        // Within 10 °C of TjMax warns, reaching it is critical
+        let alerts = AlertDetector::new(config.clone()).check_sensor_alerts(&[reading(92.0)]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!((alerts[0].level.clone(), alerts[0].threshold), (AlertLevel::Warning, 90.0));
+
+        let alerts = AlertDetector::new(config).check_sensor_alerts(&[reading(100.0)]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].level, AlertLevel::Critical);
+    }
+}
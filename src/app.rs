@@ -1,26 +1,32 @@
 use crate::config::Config;
+use crate::fsroot::FsRoot;
 use crate::error::Result;
-use crate::metrics::SystemMetrics;
-use crate::gpu::GPUManager;
+use crate::metrics::{CgroupMetrics, CgroupNode, CpuTimeMetrics, DiskIoMetrics, NetworkMetrics, PressureMetrics, SensorMetrics, SystemMetrics};
+use crate::gpu::{ControlCapabilities, ControlRequest, FanCurve, GPUEvent, GPUManager, PciIds};
 use crate::alerts::{AlertDetector, Alert, Notifier};
 use crate::history::MetricsHistory;
 use crate::trends::{TrendAnalyzer, MetricTrend};
-use crate::process::{ProcessTree, smart_kill, force_kill};
+use crate::process::{ContainerGroup, ContainerResolver, ProcessTree, SocketInspector, smart_kill, force_kill};
 use crate::metrics::system::ProcessInfo as SystemProcessInfo;
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::collections::HashSet;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ViewMode {
     Dashboard,
     Processes,
     History,
+    Cgroups,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ProcessSortMode {
     Cpu,
     Memory,
+    Pss,
+    Uss,
+    DiskRead,
+    DiskWrite,
     Name,
     Pid,
 }
@@ -31,13 +37,161 @@ pub enum DialogMode {
     ConfirmKill,
     ConfirmTerminate,
     ProcessInfo,
+    GpuControl,
+    GpuDetails,
+}
+
+/// Setting edited in the GPU control dialog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlField {
+    PowerCap,
+    PerformanceLevel,
+    PowerProfile,
+    Fan,
+}
+
+impl ControlField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlField::PowerCap => "Power cap",
+            ControlField::PerformanceLevel => "Performance level",
+            ControlField::PowerProfile => "Power profile",
+            ControlField::Fan => "Fan",
+        }
+    }
+}
+
+/// State of the GPU control dialog for one GPU
+pub struct GpuControlDialog {
+    pub gpu_key: String,
+    pub gpu_name: String,
+    pub capabilities: ControlCapabilities,
+    pub field: ControlField,
+    pub power_cap: f32,
+    pub level_index: usize,
+    pub profile_index: usize,
+    /// Index into `fan_options`; the first option is automatic control
+    pub fan_index: usize,
+    pub fan_options: Vec<(String, Option<FanCurve>)>,
+    /// Outcome of the last dry run; a successful one arms apply
+    pub preview: Option<std::result::Result<Vec<String>, String>>,
+}
+
+impl GpuControlDialog {
+    const POWER_STEP: f32 = 5.0;
+
+    fn new(gpu_key: String, gpu_name: String, capabilities: ControlCapabilities, custom_curve: Option<FanCurve>) -> Self {
+        let mut fan_options = vec![("auto".to_string(), None)];
+        fan_options.extend(custom_curve.map(|curve| ("custom".to_string(), Some(curve))));
+        fan_options.extend(FanCurve::presets().into_iter()
+            .map(|(name, curve)| (name.to_string(), Some(curve))));
+
+        let position = |options: &[String], current: &Option<String>| {
+            current.as_ref()
+                .and_then(|current| options.iter().position(|o| o == current))
+                .unwrap_or(0)
+        };
+
+        let mut dialog = Self {
+            gpu_key,
+            gpu_name,
+            power_cap: capabilities.power_cap.and_then(|cap| cap.current).unwrap_or(0.0),
+            level_index: position(&capabilities.performance_levels, &capabilities.performance_level),
+            profile_index: position(&capabilities.power_profiles, &capabilities.power_profile),
+            fan_index: 0,
+            fan_options,
+            field: ControlField::PowerCap,
+            capabilities,
+            preview: None,
+        };
+        dialog.field = dialog.fields().first().copied().unwrap_or(ControlField::PowerCap);
+        dialog
+    }
+
+    /// Fields the GPU supports, in display order
+    pub fn fields(&self) -> Vec<ControlField> {
+        let caps = &self.capabilities;
+        [
+            (ControlField::PowerCap, caps.power_cap.is_some()),
+            (ControlField::PerformanceLevel, !caps.performance_levels.is_empty()),
+            (ControlField::PowerProfile, !caps.power_profiles.is_empty()),
+            (ControlField::Fan, caps.fan_curve),
+        ]
+        .into_iter()
+        .filter_map(|(field, supported)| supported.then_some(field))
+        .collect()
+    }
+
+    pub fn value_label(&self, field: ControlField) -> String {
+        match field {
+            ControlField::PowerCap => format!("{:.0} W", self.power_cap),
+            ControlField::PerformanceLevel => self.capabilities.performance_levels
+                .get(self.level_index).cloned().unwrap_or_default(),
+            ControlField::PowerProfile => self.capabilities.power_profiles
+                .get(self.profile_index).cloned().unwrap_or_default(),
+            ControlField::Fan => self.fan_options
+                .get(self.fan_index).map(|(name, _)| name.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// The change for the selected field
+    pub fn request(&self) -> Option<ControlRequest> {
+        match self.field {
+            ControlField::PowerCap => Some(ControlRequest::PowerCap(self.power_cap)),
+            ControlField::PerformanceLevel => self.capabilities.performance_levels
+                .get(self.level_index).cloned().map(ControlRequest::PerformanceLevel),
+            ControlField::PowerProfile => self.capabilities.power_profiles
+                .get(self.profile_index).cloned().map(ControlRequest::PowerProfile),
+            ControlField::Fan => match self.fan_options.get(self.fan_index)? {
+                (_, Some(curve)) => Some(ControlRequest::FanCurve(curve.clone())),
+                (_, None) => Some(ControlRequest::FanAuto),
+            },
+        }
+    }
+
+    pub fn move_field(&mut self, delta: isize) {
+        let fields = self.fields();
+        if let Some(pos) = fields.iter().position(|f| *f == self.field) {
+            let next = (pos as isize + delta).rem_euclid(fields.len() as isize) as usize;
+            self.field = fields[next];
+            self.preview = None;
+        }
+    }
+
+    pub fn adjust(&mut self, delta: isize) {
+        let cycle = |index: usize, len: usize| {
+            if len == 0 { 0 } else { (index as isize + delta).rem_euclid(len as isize) as usize }
+        };
+        match self.field {
+            ControlField::PowerCap => {
+                if let Some(range) = self.capabilities.power_cap {
+                    self.power_cap = (self.power_cap + delta as f32 * Self::POWER_STEP)
+                        .clamp(range.min, range.max);
+                }
+            }
+            ControlField::PerformanceLevel => {
+                self.level_index = cycle(self.level_index, self.capabilities.performance_levels.len());
+            }
+            ControlField::PowerProfile => {
+                self.profile_index = cycle(self.profile_index, self.capabilities.power_profiles.len());
+            }
+            ControlField::Fan => self.fan_index = cycle(self.fan_index, self.fan_options.len()),
+        }
+        self.preview = None;
+    }
 }
 
 pub struct App {
     pub config: Config,
     pub metrics: SystemMetrics,
+    pub cpu_times: CpuTimeMetrics,
+    pub disk_io: DiskIoMetrics,
+    pub network: NetworkMetrics,
+    pub sensors: SensorMetrics,
+    pub pressure: PressureMetrics,
     pub gpu: Option<GPUManager>,
     pub gpu_info_cache: Vec<crate::gpu::GPUInfo>, // Cached GPU data
+    last_gpu_scan: Instant,
     pub alert_detector: AlertDetector,
     pub notifier: Notifier,
     pub active_alerts: Vec<Alert>,
@@ -50,12 +204,33 @@ pub struct App {
     pub process_sort: ProcessSortMode,
     pub selected_process_index: usize,
     pub dialog_mode: DialogMode,
+    pub gpu_control: Option<GpuControlDialog>,
+    /// Device key of the GPU shown in the details dialog
+    pub gpu_details: Option<String>,
     pub playback_index: Option<usize>,
     
     // Tree view support
     pub tree_mode: bool,
     pub process_tree: ProcessTree,
     pub collapsed_pids: HashSet<u32>,
+
+    // cgroup view support; cgroups are only read while the view is open
+    pub cgroups: CgroupMetrics,
+    /// Path of the selected cgroup; the row is looked up on every use, so
+    /// the selection follows it when the busiest-first order changes
+    pub selected_cgroup: Option<String>,
+    pub collapsed_cgroups: HashSet<String>,
+
+    // Container support; processes are mapped while the process view is open
+    pub containers: ContainerResolver,
+    pub group_by_container: bool,
+
+    /// Sockets per process, read when the info dialog opens or a port is
+    /// filtered for
+    pub sockets: SocketInspector,
+    /// Port the sockets were last read for; cleared on every update, so a
+    /// port filter reads the socket tables at most once per tick
+    sockets_port: Option<u16>,
     
     // Filter support
     pub filter_mode: bool,
@@ -69,36 +244,44 @@ pub struct App {
 
 impl App {
     pub fn new(config: Config, enable_gpu: bool) -> Result<Self> {
+        let root = FsRoot::from_config(&config.system)?;
+        PciIds::set_global(PciIds::load(&root).with_overrides(&config.gpu.names));
+        FsRoot::set_global(root);
+
+        // The manager is kept even without GPUs so hot-plugged cards show up
         let gpu = if enable_gpu {
             let manager = GPUManager::new();
             if manager.has_gpus() {
                 log::info!("GPU monitoring enabled: {} GPU(s) detected", manager.gpu_count());
-                Some(manager)
             } else {
                 log::warn!("GPU monitoring enabled but no GPUs detected");
-                None
             }
+            Some(manager)
         } else {
             log::info!("GPU monitoring disabled");
             None
         };
 
         let alert_detector = AlertDetector::new(config.alerts.clone());
-        let gpu_count = if let Some(ref gpu_manager) = gpu {
-            gpu_manager.gpu_count()
-        } else {
-            0
-        };
         let notifier = Notifier::new(config.alerts.notifications_enabled);
         let trend_analyzer = TrendAnalyzer::new(config.trends.clone());
-        let history = MetricsHistory::new(config.refresh.history_samples, gpu_count);
+        let history = MetricsHistory::new(config.refresh.history_samples);
+        let mut metrics = SystemMetrics::new();
+        metrics.set_smaps_process_count(config.display.smaps_process_count);
+        metrics.set_count_fds(config.display.process_columns.contains(&crate::config::ProcessColumn::Fds));
         
         Ok(Self {
             alert_detector,
             notifier,
-            metrics: SystemMetrics::new(),
+            metrics,
+            cpu_times: CpuTimeMetrics::new(),
+            disk_io: DiskIoMetrics::new(),
+            network: NetworkMetrics::new(),
+            sensors: SensorMetrics::new(),
+            pressure: PressureMetrics::new(),
             gpu,
             gpu_info_cache: Vec::new(), // Start with empty cache
+            last_gpu_scan: Instant::now(),
             active_alerts: Vec::new(),
             history,
             trend_analyzer,
@@ -109,10 +292,19 @@ impl App {
             process_sort: ProcessSortMode::Cpu,
             selected_process_index: 0,
             dialog_mode: DialogMode::None,
+            gpu_control: None,
+            gpu_details: None,
             playback_index: None,
             tree_mode: false,
             process_tree: ProcessTree::new(),
             collapsed_pids: HashSet::new(),
+            cgroups: CgroupMetrics::new(),
+            selected_cgroup: None,
+            collapsed_cgroups: HashSet::new(),
+            containers: ContainerResolver::new(),
+            group_by_container: false,
+            sockets: SocketInspector::new(),
+            sockets_port: None,
             filter_mode: false,
             filter_input: String::new(),
             filtered_processes: Vec::new(),
@@ -124,7 +316,18 @@ impl App {
 
     pub fn update(&mut self) -> Result<()> {
         if !self.paused {
+            // Per-process I/O is only shown in the process view, and PSS/USS
+            // only where a column or the sort order uses them
+            let process_view = self.view_mode == ViewMode::Processes;
+            self.metrics.set_read_process_io(process_view);
+            self.metrics.set_read_smaps(process_view && self.shows_memory_detail());
             self.metrics.refresh();
+            self.sockets_port = None;
+            self.cpu_times.refresh();
+            self.disk_io.refresh();
+            self.network.refresh();
+            self.sensors.refresh();
+            self.pressure.refresh();
             
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -135,6 +338,8 @@ impl App {
             let mem_usage = self.metrics.memory_usage_percent();
             let swap_usage = self.metrics.swap_usage_percent();
             let swap_total = self.metrics.swap_total();
+            self.rescan_gpus();
+
             // Update GPU cache - only here during update()
             self.gpu_info_cache = if let Some(ref gpu_manager) = self.gpu {
                 gpu_manager.get_info()
@@ -144,15 +349,27 @@ impl App {
             
             // Update history
             self.history.update(timestamp, cpu_usage, mem_usage, swap_usage, &self.gpu_info_cache);
+            if let Some(cpu) = self.cpu_times.total() {
+                self.history.update_cpu_times(timestamp, cpu);
+            }
+            self.history.update_disks(timestamp, self.disk_io.devices());
+            self.history.update_networks(timestamp, self.network.interfaces());
+            self.history.update_sensors(timestamp, self.sensors.readings());
             
             // Check alerts
-            let alerts = self.alert_detector.check_alerts(
+            let mut alerts = self.alert_detector.check_alerts(
                 cpu_usage,
                 mem_usage,
                 swap_usage,
                 swap_total,
                 &self.gpu_info_cache,
             );
+            if let Some(cpu) = self.cpu_times.total() {
+                alerts.extend(self.alert_detector.check_cpu_time_alerts(cpu));
+            }
+            alerts.extend(self.alert_detector.check_disk_alerts(self.disk_io.devices()));
+            alerts.extend(self.alert_detector.check_sensor_alerts(self.sensors.readings()));
+            alerts.extend(self.alert_detector.check_pressure_alerts(&self.pressure));
             
             // Send notifications for critical/warning alerts
             for alert in &alerts {
@@ -164,6 +381,11 @@ impl App {
             self.active_alerts = alerts;
         }
 
+        // Fan curves keep running while paused
+        if let Some(gpu_manager) = &self.gpu {
+            gpu_manager.tick_controls();
+        }
+
         // Analyze trends
         if self.config.trends.enabled && !self.paused {
             self.active_trends = self.trend_analyzer.analyze_all(
@@ -172,13 +394,25 @@ impl App {
             );
         }
         
+        if self.view_mode == ViewMode::Cgroups && !self.paused {
+            self.cgroups.refresh();
+        }
+
+        if self.view_mode == ViewMode::Processes && !self.paused {
+            self.refresh_containers();
+            self.refresh_process_stats();
+            if let Some(pid) = self.get_selected_pid() {
+                self.metrics.refresh_memory_detail(pid);
+            }
+        }
+        
         // Rebuild process tree if in tree mode
         if self.tree_mode {
             self.rebuild_tree();
         }
         
-        // Reapply filter if filter is active
-        if self.filter_mode && !self.filter_input.is_empty() {
+        // Reapply filter if filter is active, also after it was accepted
+        if !self.filter_input.is_empty() {
             self.apply_filter();
         }
         
@@ -188,6 +422,26 @@ impl App {
         Ok(())
     }
 
+    /// Periodically check for hot-plugged or removed cards; the backends
+    /// are only probed again when the DRM listing changed. History, alerts
+    /// and trends follow the device key.
+    fn rescan_gpus(&mut self) {
+        let interval = self.config.refresh.gpu_rescan_secs;
+        if interval == 0 || self.last_gpu_scan.elapsed() < Duration::from_secs(interval) {
+            return;
+        }
+        self.last_gpu_scan = Instant::now();
+
+        let Some(gpu_manager) = self.gpu.as_mut() else { return };
+        for event in gpu_manager.rescan() {
+            let msg = match event {
+                GPUEvent::Added { name, .. } => format!("GPU added: {}", name),
+                GPUEvent::Removed { name, .. } => format!("GPU removed: {}", name),
+            };
+            self.set_status_message(msg);
+        }
+    }
+
     pub fn quit(&mut self) {
         self.running = false;
     }
@@ -197,7 +451,7 @@ impl App {
     }
 
     pub fn has_gpu(&self) -> bool {
-        self.gpu.is_some()
+        self.gpu.as_ref().is_some_and(|gpu| gpu.has_gpus())
     }
 
     pub fn has_alerts(&self) -> bool {
@@ -217,7 +471,161 @@ impl App {
             ViewMode::Dashboard => ViewMode::Processes,
             ViewMode::Processes => ViewMode::Dashboard,
             ViewMode::History => ViewMode::Dashboard,
+            ViewMode::Cgroups => ViewMode::Dashboard,
         };
+        if self.view_mode == ViewMode::Processes {
+            self.refresh_containers();
+            self.refresh_process_stats();
+        }
+    }
+
+    /// Whether the process view shows or sorts by PSS/USS
+    fn shows_memory_detail(&self) -> bool {
+        use crate::config::ProcessColumn;
+        matches!(self.process_sort, ProcessSortMode::Pss | ProcessSortMode::Uss)
+            || self.config.display.process_columns.iter()
+                .any(|column| matches!(column, ProcessColumn::Pss | ProcessColumn::Uss))
+    }
+
+    fn refresh_containers(&mut self) {
+        let pids: Vec<u32> = self.metrics.processes().keys().map(|pid| pid.as_u32()).collect();
+        self.containers.refresh(&pids);
+    }
+
+    /// Read the stat columns for the listed rows only; rows a filter adds
+    /// get theirs on the next update
+    fn refresh_process_stats(&mut self) {
+        let pids: Vec<u32> = self.visible_processes().iter().map(|p| p.pid).collect();
+        self.metrics.refresh_process_stats(pids);
+    }
+
+    /// GPU memory per process in bytes, summed over all GPUs
+    pub fn gpu_memory_by_pid(&self) -> HashMap<u32, u64> {
+        let mut memory = HashMap::new();
+        for process in self.gpu_info_cache.iter().flat_map(|gpu| &gpu.processes) {
+            *memory.entry(process.pid).or_insert(0) += process.memory_used.unwrap_or(0);
+        }
+        memory
+    }
+
+    /// Processes listed in the process view: the filter matches, or the
+    /// top 30 in the current sort order
+    pub fn visible_processes(&self) -> Vec<SystemProcessInfo> {
+        if !self.filter_input.is_empty() {
+            return self.filtered_processes.clone();
+        }
+        match self.process_sort {
+            ProcessSortMode::Cpu => self.metrics.top_processes_by_cpu(30),
+            ProcessSortMode::Memory => self.metrics.top_processes_by_memory(30),
+            ProcessSortMode::Pss => self.metrics.top_processes_by_memory_detail(30, false),
+            ProcessSortMode::Uss => self.metrics.top_processes_by_memory_detail(30, true),
+            ProcessSortMode::DiskRead => self.metrics.top_processes_by_disk(30, false),
+            ProcessSortMode::DiskWrite => self.metrics.top_processes_by_disk(30, true),
+            ProcessSortMode::Name | ProcessSortMode::Pid => self.metrics.top_processes_by_cpu(30),
+        }
+    }
+
+    /// Per-container totals over all processes, host last
+    pub fn container_groups(&self) -> Vec<ContainerGroup> {
+        crate::process::container::group_by_container(
+            &self.metrics.all_processes(),
+            &self.containers,
+            &self.gpu_memory_by_pid(),
+        )
+    }
+
+    /// Rows in the process view, for moving the selection
+    pub fn process_row_count(&self) -> usize {
+        if self.group_by_container {
+            self.container_groups().len()
+        } else {
+            self.visible_processes().len()
+        }
+    }
+
+    pub fn toggle_container_grouping(&mut self) {
+        self.group_by_container = !self.group_by_container;
+        self.selected_process_index = 0;
+        if self.group_by_container {
+            self.refresh_containers();
+            self.set_status_message("Grouped by container".to_string());
+        } else {
+            self.set_status_message("Container grouping disabled".to_string());
+        }
+    }
+
+    /// Leave the grouped view, listing only the processes of the selected
+    /// container
+    pub fn filter_selected_container(&mut self) {
+        let Some(group) = self.container_groups().into_iter().nth(self.selected_process_index) else { return };
+        self.filter_input = format!("@{}", group.label());
+        self.filter_mode = false;
+        self.group_by_container = false;
+        self.selected_process_index = 0;
+        self.apply_filter();
+    }
+
+    /// Open the cgroup view, or go back to the dashboard from it
+    pub fn toggle_cgroup_view(&mut self) {
+        if self.view_mode == ViewMode::Cgroups {
+            self.view_mode = ViewMode::Dashboard;
+            return;
+        }
+
+        self.cgroups.refresh();
+        if !self.cgroups.available() {
+            self.set_status_message("No cgroup v2 hierarchy found".to_string());
+            return;
+        }
+        self.view_mode = ViewMode::Cgroups;
+    }
+
+    /// Row of the selected cgroup in the visible tree
+    pub fn selected_cgroup_index(&self) -> usize {
+        self.selected_cgroup.as_deref()
+            .and_then(|path| self.cgroups.visible_index(&self.collapsed_cgroups, path))
+            .unwrap_or(0)
+    }
+
+    pub fn move_cgroup_selection(&mut self, delta: isize) {
+        let visible = self.cgroups.visible(&self.collapsed_cgroups);
+        let index = self.selected_cgroup_index()
+            .saturating_add_signed(delta)
+            .min(visible.len().saturating_sub(1));
+        self.selected_cgroup = visible.get(index).map(|(node, _)| node.path.clone());
+    }
+
+    pub fn selected_cgroup(&self) -> Option<&CgroupNode> {
+        self.cgroups.visible(&self.collapsed_cgroups)
+            .get(self.selected_cgroup_index())
+            .map(|(node, _)| *node)
+    }
+
+    /// Collapse (`expand == false`) or expand the selected cgroup
+    pub fn set_selected_cgroup_expanded(&mut self, expand: bool) {
+        let Some(node) = self.selected_cgroup() else { return };
+        if node.children.is_empty() {
+            return;
+        }
+        let path = node.path.clone();
+        if expand {
+            self.collapsed_cgroups.remove(&path);
+        } else {
+            self.collapsed_cgroups.insert(path.clone());
+        }
+        self.selected_cgroup = Some(path);
+    }
+
+    /// Processes of the selected cgroup and its descendants, busiest first
+    pub fn selected_cgroup_processes(&self) -> Vec<SystemProcessInfo> {
+        let Some(node) = self.selected_cgroup() else { return Vec::new() };
+        let pids: HashSet<u32> = self.cgroups.subtree_pids(&node.path).into_iter().collect();
+        let mut processes: Vec<_> = self.metrics.all_processes()
+            .into_iter()
+            .filter(|p| pids.contains(&p.pid))
+            .collect();
+        processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        processes
     }
 
     pub fn enter_history_mode(&mut self) {
@@ -253,7 +661,11 @@ impl App {
     pub fn cycle_sort(&mut self) {
         self.process_sort = match self.process_sort {
             ProcessSortMode::Cpu => ProcessSortMode::Memory,
-            ProcessSortMode::Memory => ProcessSortMode::Name,
+            ProcessSortMode::Memory => ProcessSortMode::Pss,
+            ProcessSortMode::Pss => ProcessSortMode::Uss,
+            ProcessSortMode::Uss => ProcessSortMode::DiskRead,
+            ProcessSortMode::DiskRead => ProcessSortMode::DiskWrite,
+            ProcessSortMode::DiskWrite => ProcessSortMode::Name,
             ProcessSortMode::Name => ProcessSortMode::Pid,
             ProcessSortMode::Pid => ProcessSortMode::Cpu,
         };
@@ -272,13 +684,10 @@ impl App {
     }
 
     pub fn get_selected_pid(&self) -> Option<u32> {
-        let processes = match self.process_sort {
-            ProcessSortMode::Cpu => self.metrics.top_processes_by_cpu(30),
-            ProcessSortMode::Memory => self.metrics.top_processes_by_memory(30),
-            ProcessSortMode::Name | ProcessSortMode::Pid => self.metrics.top_processes_by_cpu(30),
-        };
-        
-        processes.get(self.selected_process_index).map(|p| p.pid)
+        if self.group_by_container {
+            return None;
+        }
+        self.visible_processes().get(self.selected_process_index).map(|p| p.pid)
     }
 
     pub fn show_kill_dialog(&mut self) {
@@ -290,11 +699,102 @@ impl App {
     }
 
     pub fn show_info_dialog(&mut self) {
+        self.sockets.refresh();
+        if let Some(pid) = self.get_selected_pid() {
+            self.metrics.refresh_memory_detail(pid);
+        }
         self.dialog_mode = DialogMode::ProcessInfo;
     }
 
     pub fn close_dialog(&mut self) {
         self.dialog_mode = DialogMode::None;
+        self.gpu_control = None;
+        self.gpu_details = None;
+    }
+
+    /// Open the GPU details dialog, or move it to the next GPU
+    pub fn show_gpu_details_dialog(&mut self) {
+        let Some(gpu_manager) = &self.gpu else { return };
+        let keys: Vec<String> = gpu_manager.gpus().iter().map(|gpu| gpu.device_key()).collect();
+        if keys.is_empty() {
+            self.set_status_message("No GPU detected".to_string());
+            return;
+        }
+
+        let current = self.gpu_details.as_ref()
+            .and_then(|key| keys.iter().position(|k| k == key));
+        let next = current.map(|pos| (pos + 1) % keys.len()).unwrap_or(0);
+        self.gpu_details = Some(keys[next].clone());
+        self.dialog_mode = DialogMode::GpuDetails;
+    }
+
+    /// Open the GPU control dialog, or move it to the next tunable GPU
+    pub fn show_gpu_control_dialog(&mut self) {
+        if !self.config.gpu.control.enabled {
+            self.set_status_message("GPU control is disabled (set [gpu.control] enabled = true)".to_string());
+            return;
+        }
+        let Some(gpu_manager) = &self.gpu else { return };
+
+        let tunable: Vec<_> = gpu_manager.gpus().iter()
+            .map(|gpu| (gpu.device_key(), gpu.name(), gpu.control_capabilities()))
+            .filter(|(_, _, caps)| !caps.is_empty())
+            .collect();
+        if tunable.is_empty() {
+            self.set_status_message("No GPU supports tuning".to_string());
+            return;
+        }
+
+        let current = self.gpu_control.as_ref()
+            .and_then(|dialog| tunable.iter().position(|(key, _, _)| *key == dialog.gpu_key));
+        let next = current.map(|pos| (pos + 1) % tunable.len()).unwrap_or(0);
+        let (key, name, caps) = tunable[next].clone();
+
+        let custom_curve = self.config.gpu.control.fan_curve.clone()
+            .and_then(|points| FanCurve::new(points)
+                .map_err(|e| log::warn!("Ignoring custom fan curve: {}", e))
+                .ok());
+        self.gpu_control = Some(GpuControlDialog::new(key, name, caps, custom_curve));
+        self.dialog_mode = DialogMode::GpuControl;
+    }
+
+    /// Dry-run the selected change and keep the result for the dialog
+    pub fn preview_gpu_control(&mut self) {
+        let (Some(gpu_manager), Some(dialog)) = (&self.gpu, self.gpu_control.as_mut()) else { return };
+        let Some(request) = dialog.request() else { return };
+        dialog.preview = Some(gpu_manager.apply_control(&dialog.gpu_key, &request, true)
+            .map_err(|e| e.to_string()));
+    }
+
+    /// Apply the previewed change
+    pub fn apply_gpu_control(&mut self) {
+        let (Some(gpu_manager), Some(dialog)) = (&self.gpu, self.gpu_control.as_mut()) else { return };
+        if !matches!(dialog.preview, Some(Ok(_))) {
+            return;
+        }
+        let Some(request) = dialog.request() else { return };
+
+        let msg = match gpu_manager.apply_control(&dialog.gpu_key, &request, false) {
+            Ok(_) => format!("{}: set {}", dialog.gpu_name, request.describe()),
+            Err(e) => format!("{}: {}", dialog.gpu_name, e),
+        };
+        if let Some(caps) = gpu_manager.control_capabilities(&dialog.gpu_key) {
+            dialog.capabilities = caps;
+        }
+        dialog.preview = None;
+        self.set_status_message(msg);
+    }
+
+    pub fn restore_gpu_controls(&mut self) {
+        let Some(gpu_manager) = &self.gpu else { return };
+        let restored = gpu_manager.restore_controls();
+        if let Some(dialog) = self.gpu_control.as_mut() {
+            if let Some(caps) = gpu_manager.control_capabilities(&dialog.gpu_key) {
+                dialog.capabilities = caps;
+            }
+            dialog.preview = None;
+        }
+        self.set_status_message(format!("Restored {} GPU setting(s)", restored));
     }
 
     pub fn kill_selected_process(&mut self) -> Result<()> {
@@ -413,10 +913,32 @@ impl App {
         self.apply_filter();
     }
     
-    /// Apply current filter to process list
+    /// Apply current filter to process list. `@name` matches the container
+    /// name or ID instead, `@host` processes outside containers, and `:port`
+    /// processes listening on the port.
     pub fn apply_filter(&mut self) {
         if self.filter_input.is_empty() {
             self.filtered_processes = self.metrics.all_processes();
+        } else if let Some(port) = self.filter_input.strip_prefix(':') {
+            let pids = match port.parse::<u16>() {
+                Ok(port) => {
+                    if self.sockets_port != Some(port) {
+                        self.sockets.refresh();
+                        self.sockets_port = Some(port);
+                    }
+                    self.sockets.pids_on_port(port)
+                }
+                Err(_) => HashSet::new(),
+            };
+            self.filtered_processes = self.metrics.all_processes()
+                .into_iter()
+                .filter(|p| pids.contains(&p.pid))
+                .collect();
+        } else if let Some(query) = self.filter_input.strip_prefix('@') {
+            self.filtered_processes = self.metrics.all_processes()
+                .into_iter()
+                .filter(|p| crate::process::container::matches_filter(self.containers.get(p.pid), query))
+                .collect();
         } else {
             let filter_lower = self.filter_input.to_lowercase();
             self.filtered_processes = self.metrics.all_processes()
@@ -424,7 +946,8 @@ impl App {
                 .filter(|p| {
                     p.name.to_lowercase().contains(&filter_lower) ||
                     p.cmd.to_lowercase().contains(&filter_lower) ||
-                    p.pid.to_string().contains(&filter_lower)
+                    p.pid.to_string().contains(&filter_lower) ||
+                    self.containers.get(p.pid).is_some_and(|c| c.matches(&filter_lower))
                 })
                 .collect();
         }
@@ -441,6 +964,18 @@ impl App {
                     b.memory_kb.cmp(&a.memory_kb)
                 );
             }
+            ProcessSortMode::Pss | ProcessSortMode::Uss => {
+                let unique = self.process_sort == ProcessSortMode::Uss;
+                let size = |p: &SystemProcessInfo| p.memory_detail.map(|m| if unique { m.uss } else { m.pss });
+                self.filtered_processes.sort_by_key(|p| std::cmp::Reverse(size(p)));
+            }
+            ProcessSortMode::DiskRead | ProcessSortMode::DiskWrite => {
+                let write = self.process_sort == ProcessSortMode::DiskWrite;
+                let rate = |p: &SystemProcessInfo| p.io
+                    .map(|io| if write { io.write_bytes_per_sec } else { io.read_bytes_per_sec })
+                    .unwrap_or(-1.0);
+                self.filtered_processes.sort_by(|a, b| rate(b).total_cmp(&rate(a)));
+            }
             ProcessSortMode::Name => {
                 self.filtered_processes.sort_by(|a, b| 
                     a.name.cmp(&b.name)
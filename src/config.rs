@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::fs;
 use crate::error::{Error, Result};
@@ -9,6 +10,10 @@ pub struct Config {
     pub alerts: AlertsConfig,
     pub display: DisplayConfig,
     pub trends: TrendConfig,
+    #[serde(default)]
+    pub system: SystemConfig,
+    #[serde(default)]
+    pub gpu: GpuConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +22,9 @@ pub struct RefreshConfig {
     pub interval_ms: u64,
     #[serde(default = "default_history_samples")]
     pub history_samples: usize,
+    /// Seconds between GPU re-detection scans (hot-plug); 0 disables
+    #[serde(default = "default_gpu_rescan_secs")]
+    pub gpu_rescan_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,12 +41,66 @@ pub struct AlertsConfig {
     pub memory_threshold: f32,
     #[serde(default = "default_swap_threshold")]
     pub swap_threshold: f32,
-    #[serde(default = "default_gpu_temp_threshold")]
-    pub gpu_temp_threshold: f32,
+    /// Share of CPU time taken by the hypervisor, in percent
+    #[serde(default = "default_cpu_steal_threshold")]
+    pub cpu_steal_threshold: f32,
+    /// Share of CPU time idle while waiting for I/O, in percent
+    #[serde(default = "default_cpu_iowait_threshold")]
+    pub cpu_iowait_threshold: f32,
+    /// PSI thresholds on the 10 second average, in percent of time stalled.
+    /// Memory "full" also applies to each cgroup.
+    #[serde(default = "default_psi_cpu_some_threshold")]
+    pub psi_cpu_some_threshold: f32,
+    #[serde(default = "default_psi_memory_full_threshold")]
+    pub psi_memory_full_threshold: f32,
+    #[serde(default = "default_psi_io_full_threshold")]
+    pub psi_io_full_threshold: f32,
+    /// Unset means each sensor warns below its critical limit, see
+    /// `gpu_temp_limit`
+    #[serde(default)]
+    pub gpu_temp_threshold: Option<f32>,
+    /// Sensor label to check ("edge", "junction", "mem"); unset checks all
+    #[serde(default)]
+    pub gpu_temp_sensor: Option<String>,
     #[serde(default = "default_gpu_util_threshold")]
     pub gpu_util_threshold: f32,
     #[serde(default = "default_gpu_mem_threshold")]
     pub gpu_mem_threshold: f32,
+    /// GTT (GPU-mapped system memory) usage, AMD only
+    #[serde(default = "default_gpu_gtt_threshold")]
+    pub gpu_gtt_threshold: f32,
+    /// Block device busy time in percent
+    #[serde(default = "default_disk_util_threshold")]
+    pub disk_util_threshold: f32,
+    /// Average block device request latency in milliseconds
+    #[serde(default = "default_disk_latency_threshold")]
+    pub disk_latency_threshold_ms: f32,
+    /// CPU and board temperature sensors; unset means each sensor warns
+    /// below its critical limit, see `sensor_temp_limit`
+    #[serde(default)]
+    pub sensor_temp_threshold: Option<f32>,
+}
+
+/// Distance below a sensor's critical limit at which GPU and board
+/// temperatures warn when no threshold is configured
+const TEMP_WARNING_MARGIN: f32 = 10.0;
+
+impl AlertsConfig {
+    /// Warning limit for a GPU sensor: the configured threshold, else 10 °C
+    /// below the sensor's critical value, else a conservative fallback.
+    /// Reaching the critical value itself raises the alert to Critical.
+    pub fn gpu_temp_limit(&self, critical: Option<f32>) -> f32 {
+        self.gpu_temp_threshold
+            .or(critical.map(|critical| critical - TEMP_WARNING_MARGIN))
+            .unwrap_or_else(default_gpu_temp_fallback)
+    }
+
+    /// Same as `gpu_temp_limit` for CPU and board sensors
+    pub fn sensor_temp_limit(&self, critical: Option<f32>) -> f32 {
+        self.sensor_temp_threshold
+            .or(critical.map(|critical| critical - TEMP_WARNING_MARGIN))
+            .unwrap_or_else(default_sensor_temp_fallback)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +111,66 @@ pub struct DisplayConfig {
     pub show_processes: bool,
     #[serde(default = "default_process_count")]
     pub process_count: usize,
+    /// Largest processes whose PSS/USS is read on every refresh while the
+    /// process view shows or sorts by it; 0 reads only the selected process
+    #[serde(default = "default_smaps_process_count")]
+    pub smaps_process_count: usize,
+    /// Columns of the process view, in order; an empty list uses the default
+    #[serde(default = "default_process_columns")]
+    pub process_columns: Vec<ProcessColumn>,
+}
+
+/// A column of the process view, named in snake_case in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessColumn {
+    Pid,
+    Ppid,
+    User,
+    State,
+    Nice,
+    Priority,
+    Threads,
+    Start,
+    Elapsed,
+    CpuTime,
+    Fds,
+    Container,
+    Name,
+    Command,
+    Cpu,
+    Rss,
+    Pss,
+    Uss,
+    Read,
+    Write,
+}
+
+impl ProcessColumn {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ProcessColumn::Pid => "PID",
+            ProcessColumn::Ppid => "PPID",
+            ProcessColumn::User => "User",
+            ProcessColumn::State => "S",
+            ProcessColumn::Nice => "NI",
+            ProcessColumn::Priority => "PRI",
+            ProcessColumn::Threads => "Thr",
+            ProcessColumn::Start => "Start",
+            ProcessColumn::Elapsed => "Elapsed",
+            ProcessColumn::CpuTime => "Time",
+            ProcessColumn::Fds => "FDs",
+            ProcessColumn::Container => "Container",
+            ProcessColumn::Name => "Name",
+            ProcessColumn::Command => "Command",
+            ProcessColumn::Cpu => "CPU",
+            ProcessColumn::Rss => "RSS",
+            ProcessColumn::Pss => "PSS",
+            ProcessColumn::Uss => "USS",
+            ProcessColumn::Read => "Read/s",
+            ProcessColumn::Write => "Write/s",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,24 +185,81 @@ pub struct TrendConfig {
     pub show_stable_trends: bool,
 }
 
+/// Where sysfs/procfs are read from. Overridden by `GLEAM_ROOT` and
+/// `GLEAM_FIXTURE`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemConfig {
+    #[serde(default = "default_root")]
+    pub root: String,
+    /// Recorded snapshot (file or directory) to run against instead of `root`
+    #[serde(default)]
+    pub fixture: Option<String>,
+}
+
+impl Default for SystemConfig {
+    fn default() -> Self {
+        Self {
+            root: default_root(),
+            fixture: None,
+        }
+    }
+}
+
+/// GPU naming. Keys are PCI IDs in hex: "vendor:device" or
+/// "vendor:device:subvendor:subdevice" (e.g. "1002:747e:1da2:e471").
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuConfig {
+    #[serde(default)]
+    pub names: HashMap<String, String>,
+    #[serde(default)]
+    pub control: GpuControlConfig,
+}
+
+/// Power, performance and fan tuning from the TUI. Off unless enabled;
+/// original values are restored on exit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuControlConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Custom fan curve as [temperature °C, duty %] points
+    #[serde(default)]
+    pub fan_curve: Option<Vec<(f32, u8)>>,
+}
+
 fn default_interval_ms() -> u64 { 1000 }
 fn default_history_samples() -> usize { 60 }
+fn default_gpu_rescan_secs() -> u64 { 5 }
 fn default_enabled() -> bool { true }
 fn default_notifications_enabled() -> bool { true }
 fn default_notification_cooldown() -> u64 { 60 } // 1 minute cooldown
 fn default_cpu_threshold() -> f32 { 85.0 }
 fn default_memory_threshold() -> f32 { 90.0 }
 fn default_swap_threshold() -> f32 { 80.0 }
-fn default_gpu_temp_threshold() -> f32 { 75.0 }
+fn default_cpu_steal_threshold() -> f32 { 10.0 }
+fn default_cpu_iowait_threshold() -> f32 { 20.0 }
+fn default_psi_cpu_some_threshold() -> f32 { 50.0 }
+fn default_psi_memory_full_threshold() -> f32 { 5.0 }
+fn default_psi_io_full_threshold() -> f32 { 20.0 }
+fn default_gpu_temp_fallback() -> f32 { 75.0 }
 fn default_gpu_util_threshold() -> f32 { 95.0 }
 fn default_gpu_mem_threshold() -> f32 { 90.0 }
+fn default_gpu_gtt_threshold() -> f32 { 90.0 }
+fn default_disk_util_threshold() -> f32 { 90.0 }
+fn default_disk_latency_threshold() -> f32 { 100.0 }
+fn default_sensor_temp_fallback() -> f32 { 90.0 }
 fn default_theme() -> String { "dark".to_string() }
 fn default_show_processes() -> bool { true }
 fn default_process_count() -> usize { 10 }
+fn default_smaps_process_count() -> usize { 20 }
+fn default_process_columns() -> Vec<ProcessColumn> {
+    use ProcessColumn::*;
+    vec![Pid, User, Container, Command, Cpu, Rss, Pss, Uss, Read, Write]
+}
 fn default_trend_enabled() -> bool { true }
 fn default_sample_interval() -> u64 { 1 }
 fn default_min_confidence() -> f32 { 0.7 }
 fn default_show_stable() -> bool { true }  // Show all trends including stable ones for testing
+fn default_root() -> String { "/".to_string() }
 
 impl Default for Config {
     fn default() -> Self {
@@ -88,6 +267,7 @@ impl Default for Config {
             refresh: RefreshConfig {
                 interval_ms: default_interval_ms(),
                 history_samples: default_history_samples(),
+                gpu_rescan_secs: default_gpu_rescan_secs(),
             },
             alerts: AlertsConfig {
                 enabled: default_enabled(),
@@ -96,14 +276,26 @@ impl Default for Config {
                 cpu_threshold: default_cpu_threshold(),
                 memory_threshold: default_memory_threshold(),
                 swap_threshold: default_swap_threshold(),
-                gpu_temp_threshold: default_gpu_temp_threshold(),
+                cpu_steal_threshold: default_cpu_steal_threshold(),
+                cpu_iowait_threshold: default_cpu_iowait_threshold(),
+                psi_cpu_some_threshold: default_psi_cpu_some_threshold(),
+                psi_memory_full_threshold: default_psi_memory_full_threshold(),
+                psi_io_full_threshold: default_psi_io_full_threshold(),
+                gpu_temp_threshold: None,
+                gpu_temp_sensor: None,
                 gpu_util_threshold: default_gpu_util_threshold(),
                 gpu_mem_threshold: default_gpu_mem_threshold(),
+                gpu_gtt_threshold: default_gpu_gtt_threshold(),
+                disk_util_threshold: default_disk_util_threshold(),
+                disk_latency_threshold_ms: default_disk_latency_threshold(),
+                sensor_temp_threshold: None,
             },
             display: DisplayConfig {
                 theme: default_theme(),
                 show_processes: default_show_processes(),
                 process_count: default_process_count(),
+                smaps_process_count: default_smaps_process_count(),
+                process_columns: default_process_columns(),
             },
             trends: TrendConfig {
                 enabled: default_trend_enabled(),
@@ -111,6 +303,8 @@ impl Default for Config {
                 min_confidence: default_min_confidence(),
                 show_stable_trends: default_show_stable(),
             },
+            system: SystemConfig::default(),
+            gpu: GpuConfig::default(),
         }
     }
 }
@@ -121,8 +315,11 @@ impl Config {
         
         if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)
+            let mut config: Config = toml::from_str(&content)
                 .map_err(|e| Error::Config(format!("Failed to parse config: {}", e)))?;
+            if config.display.process_columns.is_empty() {
+                config.display.process_columns = default_process_columns();
+            }
             Ok(config)
         } else {
             Ok(Self::default())
@@ -5,12 +5,13 @@ pub use notifications::{send_alert, send_status_update};
 pub use signals::setup_signal_handlers;
 
 use crate::config::Config;
+use crate::fsroot::FsRoot;
 use crate::error::Result;
-use crate::metrics::SystemMetrics;
-use crate::gpu::GPUManager;
+use crate::metrics::{CpuTimeMetrics, DiskIoMetrics, NetworkMetrics, PressureMetrics, SensorMetrics, SystemMetrics};
+use crate::gpu::{GPUManager, PciIds};
 use crate::alerts::{AlertDetector, Notifier};
 use crate::history::MetricsHistory;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 static STOP_FLAG: AtomicBool = AtomicBool::new(false);
@@ -18,7 +19,13 @@ static STOP_FLAG: AtomicBool = AtomicBool::new(false);
 pub struct DaemonContext {
     pub config: Config,
     pub metrics: SystemMetrics,
+    pub cpu_times: CpuTimeMetrics,
+    pub disk_io: DiskIoMetrics,
+    pub network: NetworkMetrics,
+    pub sensors: SensorMetrics,
+    pub pressure: PressureMetrics,
     pub gpu: Option<GPUManager>,
+    last_gpu_scan: Instant,
     pub alert_detector: AlertDetector,
     pub notifier: Notifier,
     pub history: MetricsHistory,
@@ -29,30 +36,32 @@ pub struct DaemonContext {
 
 impl DaemonContext {
     pub fn new(config: Config) -> Result<Self> {
-        let gpu = {
-            let manager = GPUManager::new();
-            if manager.has_gpus() {
-                log::info!("GPU monitoring enabled: {} GPU(s) detected", manager.gpu_count());
-                Some(manager)
-            } else {
-                log::warn!("GPU monitoring enabled but no GPUs detected");
-                None
-            }
-        };
+        let root = FsRoot::from_config(&config.system)?;
+        PciIds::set_global(PciIds::load(&root).with_overrides(&config.gpu.names));
+        FsRoot::set_global(root);
 
-        let alert_detector = AlertDetector::new(config.alerts.clone());
-        let gpu_count = if let Some(ref gpu_manager) = gpu {
-            gpu_manager.gpu_count()
+        // Kept even without GPUs so hot-plugged cards are picked up
+        let gpu = GPUManager::new();
+        if gpu.has_gpus() {
+            log::info!("GPU monitoring enabled: {} GPU(s) detected", gpu.gpu_count());
         } else {
-            0
-        };
+            log::warn!("GPU monitoring enabled but no GPUs detected");
+        }
+
+        let alert_detector = AlertDetector::new(config.alerts.clone());
         let notifier = Notifier::new(config.alerts.notifications_enabled);
-        let history = MetricsHistory::new(config.refresh.history_samples, gpu_count);
+        let history = MetricsHistory::new(config.refresh.history_samples);
         
         Ok(Self {
             config,
             metrics: SystemMetrics::new(),
-            gpu,
+            cpu_times: CpuTimeMetrics::new(),
+            disk_io: DiskIoMetrics::new(),
+            network: NetworkMetrics::new(),
+            sensors: SensorMetrics::new(),
+            pressure: PressureMetrics::new(),
+            gpu: Some(gpu),
+            last_gpu_scan: Instant::now(),
             alert_detector,
             notifier,
             history,
@@ -85,6 +94,11 @@ impl DaemonContext {
     
     fn update_metrics(&mut self) {
         self.metrics.refresh();
+        self.cpu_times.refresh();
+        self.disk_io.refresh();
+        self.network.refresh();
+        self.sensors.refresh();
+        self.pressure.refresh();
         
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -95,9 +109,29 @@ impl DaemonContext {
         self.mem_percent = self.metrics.memory_usage_percent();
         let swap_usage = self.metrics.swap_usage_percent();
         
+        self.rescan_gpus();
         let gpu_info = self.get_gpu_info();
         
         self.history.update(timestamp, self.cpu_percent, self.mem_percent, swap_usage, &gpu_info);
+        if let Some(cpu) = self.cpu_times.total() {
+            self.history.update_cpu_times(timestamp, cpu);
+        }
+        self.history.update_disks(timestamp, self.disk_io.devices());
+        self.history.update_networks(timestamp, self.network.interfaces());
+        self.history.update_sensors(timestamp, self.sensors.readings());
+    }
+    
+    /// Pick up hot-plugged or removed GPUs; events are logged by the manager
+    fn rescan_gpus(&mut self) {
+        let interval = self.config.refresh.gpu_rescan_secs;
+        if interval == 0 || self.last_gpu_scan.elapsed() < Duration::from_secs(interval) {
+            return;
+        }
+        self.last_gpu_scan = Instant::now();
+
+        if let Some(gpu_manager) = self.gpu.as_mut() {
+            gpu_manager.rescan();
+        }
     }
     
     fn get_gpu_info(&self) -> Vec<crate::gpu::GPUInfo> {
@@ -115,13 +149,19 @@ impl DaemonContext {
         let swap_total = self.metrics.swap_total();
         let gpu_info = self.get_gpu_info();
         
-        let alerts = self.alert_detector.check_alerts(
+        let mut alerts = self.alert_detector.check_alerts(
             self.cpu_percent,
             self.mem_percent,
             swap_usage,
             swap_total,
             &gpu_info,
         );
+        if let Some(cpu) = self.cpu_times.total() {
+            alerts.extend(self.alert_detector.check_cpu_time_alerts(cpu));
+        }
+        alerts.extend(self.alert_detector.check_disk_alerts(self.disk_io.devices()));
+        alerts.extend(self.alert_detector.check_sensor_alerts(self.sensors.readings()));
+        alerts.extend(self.alert_detector.check_pressure_alerts(&self.pressure));
         
         for alert in alerts {
             log::warn!("Alert triggered: {:?} - {}", alert.level, alert.message);
@@ -13,6 +13,8 @@ pub fn setup_signal_handlers() -> Result<()> {
                 signal_hook::consts::SIGTERM | signal_hook::consts::SIGINT => {
                     log::info!("Received termination signal, shutting down gracefully");
                     super::set_stop_flag();
+                    crate::gpu::control::restore_all();
+                    crate::fsroot::FsRoot::remove_unpacked_global();
                     std::process::exit(0);
                 }
                 _ => {}
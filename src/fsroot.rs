@@ -0,0 +1,519 @@
+//! Filesystem root for sysfs/procfs readers.
+//!
+//! Every reader that parses `/sys` or `/proc` resolves its paths through an
+//! [`FsRoot`] instead of hard-coding them. On a normal run the root is `/`;
+//! pointing it somewhere else lets the same code run against a copy of
+//! another machine's tree, e.g. a recorded fixture of a user's AMD card.
+//!
+//! Fixtures are single-file snapshots produced by [`record_snapshot`] and
+//! unpacked by [`FsRoot::load_fixture`].
+
+use crate::config::SystemConfig;
+use crate::error::{Error, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Environment variable overriding `system.root`
+pub const ROOT_ENV: &str = "GLEAM_ROOT";
+/// Environment variable overriding `system.fixture`
+pub const FIXTURE_ENV: &str = "GLEAM_FIXTURE";
+/// Prefix of the directories snapshot files are unpacked into
+const UNPACK_PREFIX: &str = "gleam-fixture-";
+
+/// Paths captured by `--record-fixture` when no other list is given
+pub const DEFAULT_RECORD_PATHS: &[&str] = &[
+    "/sys/class/drm",
+    "/sys/class/hwmon",
+    "/sys/class/thermal",
+    "/proc/stat",
+    "/proc/meminfo",
+    "/proc/loadavg",
+    "/proc/pressure",
+    "/sys/fs/cgroup",
+    "/proc/diskstats",
+    "/proc/mounts",
+    "/sys/class/net",
+    "/proc/net/if_inet6",
+];
+
+const SNAPSHOT_HEADER: &str = "# gleam-observer fixture v1";
+const MAX_RECORD_DEPTH: usize = 8;
+const MAX_RECORD_FILE_SIZE: u64 = 64 * 1024;
+
+/// Links that point back up into the device hierarchy or to unrelated
+/// subsystems. They are recorded but never followed.
+const UNFOLLOWED_LINKS: &[&str] = &[
+    "driver", "subsystem", "firmware_node", "iommu", "iommu_group",
+    "of_node", "port", "physfn", "root_port",
+];
+
+static GLOBAL_ROOT: RwLock<Option<FsRoot>> = RwLock::new(None);
+
+#[derive(Debug, Clone)]
+pub struct FsRoot {
+    root: PathBuf,
+    /// Set when the root was unpacked from a snapshot file. The directory
+    /// is removed when the last handle to it is dropped.
+    _unpacked: Option<Arc<UnpackedDir>>,
+}
+
+impl PartialEq for FsRoot {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+#[derive(Debug)]
+struct UnpackedDir(PathBuf);
+
+impl Drop for UnpackedDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.0) {
+            log::warn!("Failed to remove {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+impl FsRoot {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), _unpacked: None }
+    }
+
+    /// The real root of the running system
+    pub fn host() -> Self {
+        Self::new("/")
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.root == Path::new("/")
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve an absolute system path (e.g. "/sys/class/drm") under this root
+    pub fn path(&self, absolute: impl AsRef<Path>) -> PathBuf {
+        let relative = absolute.as_ref()
+            .strip_prefix("/")
+            .unwrap_or(absolute.as_ref());
+        self.root.join(relative)
+    }
+
+    /// Root used by readers that are not handed one explicitly
+    pub fn global() -> FsRoot {
+        GLOBAL_ROOT.read()
+            .ok()
+            .and_then(|root| root.clone())
+            .unwrap_or_else(FsRoot::host)
+    }
+
+    pub fn set_global(root: FsRoot) {
+        if let Ok(mut global) = GLOBAL_ROOT.write() {
+            *global = Some(root);
+        }
+    }
+
+    /// Drop the global root on exit, so an unpacked fixture is removed
+    /// once the readers holding it are gone too
+    pub fn clear_global() {
+        if let Ok(mut global) = GLOBAL_ROOT.write() {
+            *global = None;
+        }
+    }
+
+    /// Remove the directory of an unpacked global fixture right away, for
+    /// exit paths that skip destructors such as the signal handlers
+    pub fn remove_unpacked_global() {
+        let root = GLOBAL_ROOT.try_read().ok().and_then(|root| root.clone());
+        if let Some(unpacked) = root.and_then(|root| root._unpacked) {
+            let _ = fs::remove_dir_all(&unpacked.0);
+        }
+    }
+
+    /// Resolve the root from the environment, falling back to the config.
+    /// A fixture takes precedence over a plain root directory.
+    pub fn from_config(config: &SystemConfig) -> Result<FsRoot> {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+        if let Some(fixture) = env(FIXTURE_ENV).or_else(|| config.fixture.clone()) {
+            return Self::load_fixture(Path::new(&fixture));
+        }
+
+        let root = env(ROOT_ENV).unwrap_or_else(|| config.root.clone());
+        let root = FsRoot::new(root);
+        if !root.root.is_dir() {
+            return Err(Error::Config(format!(
+                "Filesystem root {} is not a directory", root.root.display()
+            )));
+        }
+
+        if !root.is_host() {
+            log::info!("Reading sysfs/procfs from {}", root.root.display());
+        }
+        Ok(root)
+    }
+
+    /// Use a recorded snapshot as root. A directory is used in place; a
+    /// snapshot file is unpacked into a private directory first.
+    pub fn load_fixture(snapshot: &Path) -> Result<FsRoot> {
+        if snapshot.is_dir() {
+            log::info!("Using fixture directory {}", snapshot.display());
+            return Ok(FsRoot::new(snapshot));
+        }
+
+        remove_stale_unpacked();
+        let target = std::env::temp_dir()
+            .join(format!("{}{}", UNPACK_PREFIX, std::process::id()));
+        if target.exists() {
+            fs::remove_dir_all(&target)?;
+        }
+
+        let root = FsRoot {
+            _unpacked: Some(Arc::new(UnpackedDir(target.clone()))),
+            root: target,
+        };
+        let file = fs::File::open(snapshot)
+            .map_err(|e| Error::Config(format!("Failed to open fixture {}: {}", snapshot.display(), e)))?;
+        root.unpack_snapshot(BufReader::new(file))?;
+
+        log::info!("Loaded fixture {} into {}", snapshot.display(), root.root.display());
+        Ok(root)
+    }
+
+    /// Write a file below the root, creating parent directories
+    pub fn write_file(&self, absolute: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+        let path = self.path(absolute);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    /// Create a symlink below the root. `target` is stored verbatim, so
+    /// relative targets resolve inside the root like they do on the host.
+    #[cfg(unix)]
+    pub fn symlink(&self, absolute: impl AsRef<Path>, target: impl AsRef<Path>) -> io::Result<()> {
+        let path = self.path(absolute);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if path.symlink_metadata().is_ok() {
+            fs::remove_file(&path)?;
+        }
+        std::os::unix::fs::symlink(target, path)
+    }
+
+    fn unpack_snapshot<R: BufRead>(&self, mut reader: R) -> Result<()> {
+        let parse_err = |msg: String| Error::Parse(format!("Invalid fixture: {}", msg));
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        if line.trim_end() != SNAPSHOT_HEADER {
+            return Err(parse_err("missing header".to_string()));
+        }
+        fs::create_dir_all(&self.root)?;
+
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            let entry = line.trim_end_matches('\n');
+            if entry.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = entry.split('\t').collect();
+            match fields.as_slice() {
+                ["D", path] => {
+                    fs::create_dir_all(self.contained_path(path)?)?;
+                }
+                ["L", path, target] => {
+                    self.contained_path(path)?;
+                    #[cfg(unix)]
+                    self.symlink(checked_path(path)?, checked_link_target(path, target)?)?;
+                }
+                ["F", len, path] => {
+                    let len = len.parse::<usize>()
+                        .map_err(|_| parse_err(format!("bad length for {}", path)))?;
+                    let mut contents = vec![0u8; len];
+                    reader.read_exact(&mut contents)?;
+                    // Each file body is followed by a separator newline
+                    let mut separator = [0u8; 1];
+                    reader.read_exact(&mut separator)?;
+                    self.contained_path(path)?;
+                    self.write_file(checked_path(path)?, contents)?;
+                }
+                _ => return Err(parse_err(format!("unknown entry '{}'", entry))),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Location of a snapshot entry below the root. Links unpacked earlier
+    /// may lead elsewhere, so the closest existing ancestor (or the entry
+    /// itself) must resolve to a path inside the root.
+    fn contained_path(&self, path: &str) -> Result<PathBuf> {
+        let full = self.path(checked_path(path)?);
+        let root = fs::canonicalize(&self.root)?;
+        let existing = full.ancestors()
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or(&self.root);
+        if !fs::canonicalize(existing)?.starts_with(&root) {
+            return Err(Error::Parse(format!("Fixture path {} leads outside the fixture", path)));
+        }
+        Ok(full)
+    }
+}
+
+/// Reject entries that would escape the fixture directory
+fn checked_path(path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(Error::Parse(format!("Invalid fixture path {}", path.display())));
+    }
+    Ok(Path::new("/").join(path))
+}
+
+/// Reject link targets that are absolute or climb above the root from
+/// the directory of the link at `path`
+fn checked_link_target<'a>(path: &str, target: &'a str) -> Result<&'a str> {
+    let invalid = || Error::Parse(format!("Invalid fixture link {} -> {}", path, target));
+    let mut depth = Path::new(path).components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .count()
+        .saturating_sub(1);
+    for component in Path::new(target).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::ParentDir => depth = depth.checked_sub(1).ok_or_else(invalid)?,
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return Err(invalid()),
+        }
+    }
+    Ok(target)
+}
+
+/// Remove directories left behind by runs that were killed before they
+/// could clean up after themselves
+fn remove_stale_unpacked() {
+    let Ok(entries) = fs::read_dir(std::env::temp_dir()) else { return };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(pid) = name.to_str()
+            .and_then(|name| name.strip_prefix(UNPACK_PREFIX))
+            .and_then(|pid| pid.parse::<u32>().ok()) else { continue };
+        if pid != std::process::id() && !Path::new("/proc").join(pid.to_string()).exists() {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Record `paths` (absolute system paths) under `root` into a snapshot file.
+/// Symlinks are stored as links and, when they point into the device tree,
+/// their targets are recorded too so relative links keep resolving.
+/// Returns the number of entries written.
+pub fn record_snapshot(root: &FsRoot, paths: &[&str], out: &Path) -> Result<usize> {
+    let mut writer = io::BufWriter::new(fs::File::create(out)?);
+    writeln!(writer, "{}", SNAPSHOT_HEADER)?;
+
+    let mut queue: Vec<(PathBuf, usize)> = paths.iter()
+        .map(|p| (PathBuf::from(p), 0))
+        .collect();
+    let mut visited = HashSet::new();
+    let mut entries = 0;
+
+    while let Some((path, depth)) = queue.pop() {
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+
+        let real = root.path(&path);
+        let relative = path.strip_prefix("/").unwrap_or(&path).display().to_string();
+        let Ok(meta) = real.symlink_metadata() else { continue };
+
+        if meta.file_type().is_symlink() {
+            let Ok(target) = fs::read_link(&real) else { continue };
+            writeln!(writer, "L\t{}\t{}", relative, target.display())?;
+            entries += 1;
+
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if depth < MAX_RECORD_DEPTH && !UNFOLLOWED_LINKS.contains(&name) {
+                if let Some(resolved) = resolve_link(root, &real) {
+                    if resolved.starts_with("/sys/devices") || paths.iter().any(|p| resolved.starts_with(p)) {
+                        queue.push((resolved, depth + 1));
+                    }
+                }
+            }
+        } else if meta.is_dir() {
+            writeln!(writer, "D\t{}", relative)?;
+            entries += 1;
+
+            if depth < MAX_RECORD_DEPTH {
+                if let Ok(children) = fs::read_dir(&real) {
+                    for child in children.flatten() {
+                        queue.push((path.join(child.file_name()), depth + 1));
+                    }
+                }
+            }
+        } else if meta.is_file() {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            // PCI BARs and option ROMs are huge or have read side effects
+            if name.starts_with("resource") || name == "rom" {
+                continue;
+            }
+
+            let mut contents = Vec::new();
+            let read = fs::File::open(&real)
+                .and_then(|f| f.take(MAX_RECORD_FILE_SIZE).read_to_end(&mut contents));
+            if read.is_err() {
+                continue; // write-only or unreadable attribute
+            }
+
+            writeln!(writer, "F\t{}\t{}", contents.len(), relative)?;
+            writer.write_all(&contents)?;
+            writeln!(writer)?;
+            entries += 1;
+        }
+    }
+
+    writer.flush()?;
+    Ok(entries)
+}
+
+/// Absolute system path a link under `root` points to
+fn resolve_link(root: &FsRoot, real: &Path) -> Option<PathBuf> {
+    let canonical = fs::canonicalize(real).ok()?;
+    let canonical_root = fs::canonicalize(root.root()).ok()?;
+    let relative = canonical.strip_prefix(canonical_root).ok()?;
+    Some(Path::new("/").join(relative))
+}
+
+/// Scratch root for tests. Every instance gets its own directory, which is
+/// removed on drop, also when the test fails. `clone()` derefs to a plain
+/// [`FsRoot`] handle for a reader's `with_root`.
+#[cfg(test)]
+pub(crate) struct TestRoot {
+    root: FsRoot,
+}
+
+#[cfg(test)]
+impl TestRoot {
+    pub fn new(name: &str) -> Self {
+        static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let id = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let dir = std::env::temp_dir()
+            .join(format!("gleam-test-{}-{}-{}", name, std::process::id(), id));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create test root");
+        Self { root: FsRoot::new(dir) }
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestRoot {
+    type Target = FsRoot;
+
+    fn deref(&self) -> &FsRoot {
+        &self.root
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestRoot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root.root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_resolution() {
+        let root = FsRoot::new("/tmp/fixture");
+        assert_eq!(root.path("/proc/stat"), PathBuf::from("/tmp/fixture/proc/stat"));
+        assert!(FsRoot::host().is_host());
+        assert_eq!(FsRoot::host().path("/proc/stat"), PathBuf::from("/proc/stat"));
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let source = TestRoot::new("record");
+        source.write_file("/sys/devices/pci0000:00/0000:03:00.0/vendor", "0x1002\n").unwrap();
+        source.symlink("/sys/class/drm/card0/device", "../../../devices/pci0000:00/0000:03:00.0").unwrap();
+        source.write_file("/proc/stat", "cpu  1 2 3 4\n").unwrap();
+
+        let snapshot = source.root().join("snapshot.fixture");
+        let entries = record_snapshot(&source, &["/sys/class/drm", "/proc/stat"], &snapshot).unwrap();
+        assert!(entries >= 4);
+
+        let loaded = TestRoot::new("load");
+        loaded.unpack_snapshot(BufReader::new(fs::File::open(&snapshot).unwrap())).unwrap();
+
+        let vendor = fs::read_to_string(loaded.path("/sys/class/drm/card0/device/vendor")).unwrap();
+        assert_eq!(vendor, "0x1002\n");
+        let link = fs::read_link(loaded.path("/sys/class/drm/card0/device")).unwrap();
+        assert_eq!(link.file_name().unwrap(), "0000:03:00.0");
+        assert_eq!(fs::read_to_string(loaded.path("/proc/stat")).unwrap(), "cpu  1 2 3 4\n");
+    }
+
+    #[test]
+    fn test_snapshot_rejects_escaping_paths() {
+        let root = TestRoot::new("escape");
+        let snapshot = format!("{}\nF\t2\t../evil\nhi\n", SNAPSHOT_HEADER);
+        assert!(root.unpack_snapshot(snapshot.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_absolute_link_targets() {
+        let root = TestRoot::new("absolute-link");
+        let snapshot = format!("{}\nL\tsys/x\t/etc\nF\t2\tsys/x/passwd\nhi\n", SNAPSHOT_HEADER);
+        assert!(root.unpack_snapshot(snapshot.as_bytes()).is_err());
+        assert!(root.path("/sys/x").symlink_metadata().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_escaping_link_targets() {
+        let root = TestRoot::new("parent-link");
+        let snapshot = format!("{}\nL\tsys/x\t../../outside\n", SNAPSHOT_HEADER);
+        assert!(root.unpack_snapshot(snapshot.as_bytes()).is_err());
+
+        // Climbing back to the root itself is fine
+        let snapshot = format!("{}\nL\tsys/class/drm/card0\t../../../sys\n", SNAPSHOT_HEADER);
+        root.unpack_snapshot(snapshot.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_refuses_writes_through_links() {
+        let root = TestRoot::new("write-through");
+        // Each target stays inside lexically, but "up" resolves to the
+        // root, so "escape" resolves to its parent
+        let snapshot = format!("{}\nL\tsys/up\t..\nL\tsys/escape\tup/..\nF\t2\tsys/escape/evil\nhi\n",
+            SNAPSHOT_HEADER);
+        assert!(root.unpack_snapshot(snapshot.as_bytes()).is_err());
+        assert!(!root.root().parent().unwrap().join("evil").exists());
+    }
+
+    #[test]
+    fn test_unpacked_fixture_removed_on_drop() {
+        let source = TestRoot::new("unpack");
+        source.write_file("/proc/stat", "cpu  1 2 3 4\n").unwrap();
+        let snapshot = source.root().join("snapshot.fixture");
+        record_snapshot(&source, &["/proc/stat"], &snapshot).unwrap();
+
+        let loaded = FsRoot::load_fixture(&snapshot).unwrap();
+        let dir = loaded.root().to_path_buf();
+        let copy = loaded.clone();
+        drop(loaded);
+        assert!(copy.path("/proc/stat").exists());
+        drop(copy);
+        assert!(!dir.exists());
+    }
+}
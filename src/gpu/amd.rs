@@ -1,21 +1,59 @@
 use crate::error::{Error, Result};
-use crate::gpu::backend::GPUBackend;
+use crate::gpu::backend::{GPUBackend, GPUSample, SampleCache};
+#[cfg(target_os = "linux")]
+use crate::gpu::backend::{DeviceProperties, PcieLink, ThrottleReason};
+#[cfg(target_os = "linux")]
+use crate::fsroot::FsRoot;
+#[cfg(target_os = "linux")]
+use crate::gpu::fdinfo::DrmClientTracker;
+#[cfg(target_os = "linux")]
+use crate::gpu::hwmon;
+#[cfg(target_os = "linux")]
+use crate::gpu::pciids::{PciId, PciIds};
+#[cfg(target_os = "linux")]
+use crate::gpu::control::{
+    ControlCapabilities, ControlRequest, FanCurve, PowerCapRange, SysfsControl, SysfsWrite,
+};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
 
 #[cfg(target_os = "linux")]
 pub struct AmdBackend {
     hwmon_path: PathBuf,
     device_path: PathBuf,
+    key: String,
+    /// PCI address; `None` when the device link could not be read, in
+    /// which case the key is the device path
+    pci_slot: Option<String>,
     name: String,
+    memory_total: Option<u64>,
+    properties: DeviceProperties,
+    clients: Option<Mutex<DrmClientTracker>>,
+    controls: SysfsControl,
+    fan_curve: Mutex<Option<FanCurve>>,
+    samples: SampleCache,
 }
 
+/// Values accepted by `power_dpm_force_performance_level`
+#[cfg(target_os = "linux")]
+const PERFORMANCE_LEVELS: &[&str] = &[
+    "auto", "low", "high", "manual",
+    "profile_standard", "profile_min_sclk", "profile_min_mclk", "profile_peak",
+];
+
 #[cfg(target_os = "linux")]
 impl AmdBackend {
     pub fn detect_all() -> Result<Vec<Self>> {
+        Self::detect_all_in(&FsRoot::global())
+    }
+
+    /// Detect cards under the sysfs tree of `root`
+    pub fn detect_all_in(root: &FsRoot) -> Result<Vec<Self>> {
         let mut gpus = Vec::new();
 
-        let cards = match fs::read_dir("/sys/class/drm") {
+        let cards = match fs::read_dir(root.path("/sys/class/drm")) {
             Ok(entries) => entries,
             Err(_) => return Err(Error::Gpu("Failed to read /sys/class/drm".to_string())),
         };
@@ -42,10 +80,24 @@ impl AmdBackend {
                 if vendor.trim() == "0x1002" {
                     if let Some(hwmon_path) = Self::find_hwmon(&device_path) {
                         let name = Self::read_gpu_name(&device_path);
+                        let pci_slot = Self::read_pci_slot(&device_path);
+                        let clients = pci_slot.clone()
+                            .map(|slot| Mutex::new(DrmClientTracker::new(root.clone(), slot)));
+                        let key = pci_slot.clone().unwrap_or_else(|| device_path.display().to_string());
+                        let memory_total = Self::read_memory_total(&device_path);
+                        let properties = Self::read_properties(root, &device_path);
                         gpus.push(Self {
                             hwmon_path,
                             device_path,
+                            key,
+                            pci_slot,
                             name,
+                            memory_total,
+                            properties,
+                            clients,
+                            controls: SysfsControl::new(),
+                            fan_curve: Mutex::new(None),
+                            samples: SampleCache::new(),
                         });
                     }
                 }
@@ -74,189 +126,415 @@ impl AmdBackend {
         None
     }
 
-    fn read_gpu_name(device_path: &PathBuf) -> String {
-        // Try sysfs first
+    fn read_gpu_name(device_path: &Path) -> String {
+        let id = PciId::read(device_path);
+        let ids = PciIds::global();
+
+        if let Some(name) = id.as_ref().and_then(|id| ids.override_name(id)) {
+            return name.to_string();
+        }
+
+        // Newer cards report their marketing name directly
         if let Ok(name) = fs::read_to_string(device_path.join("product_name")) {
             let trimmed = name.trim();
             if !trimmed.is_empty() {
                 return trimmed.to_string();
             }
         }
-        
-        // Get device ID for known GPU mapping
-        let device_id = Self::read_device_id(device_path);
-        
-        // Fallback to lspci with machine-readable format
-        if let Ok(pci_slot) = fs::read_link(device_path) {
-            if let Some(slot) = pci_slot.file_name().and_then(|s| s.to_str()) {
-                if let Ok(output) = std::process::Command::new("lspci")
-                    .args(&["-vmm", "-s", slot])
-                    .output()
-                {
-                    if let Ok(text) = String::from_utf8(output.stdout) {
-                        let mut device_name = None;
-                        let mut sdevice_name = None;
-                        let mut svendor = None;
-                        
-                        for line in text.lines() {
-                            if line.starts_with("Device:") {
-                                device_name = Some(line.trim_start_matches("Device:").trim());
-                            } else if line.starts_with("SDevice:") {
-                                sdevice_name = Some(line.trim_start_matches("SDevice:").trim());
-                            } else if line.starts_with("SVendor:") {
-                                svendor = Some(line.trim_start_matches("SVendor:").trim());
-                            }
-                        }
-                        
-                        // Apply known GPU mappings for proper naming
-                        if let (Some(dev_id), Some(vendor)) = (&device_id, svendor) {
-                            if let Some(proper_name) = Self::get_known_gpu_name(dev_id, vendor) {
-                                return proper_name;
-                            }
-                        }
-                        
-                        // Prefer subsystem device name (more specific)
-                        if let Some(name) = sdevice_name {
-                            if !name.is_empty() {
-                                return name.to_string();
-                            }
-                        }
-                        
-                        // Fallback to device name, extract from brackets
-                        if let Some(name) = device_name {
-                            if let Some(bracketed) = name.split('[').nth(1) {
-                                if let Some(extracted) = bracketed.split(']').next() {
-                                    return extracted.trim().to_string();
-                                }
-                            }
-                            return name.to_string();
-                        }
-                    }
-                }
-            }
-        }
-        
-        "Unknown AMD GPU".to_string()
+
+        id.and_then(|id| ids.lookup_name(&id))
+            .unwrap_or_else(|| "Unknown AMD GPU".to_string())
     }
     
-    fn read_device_id(device_path: &PathBuf) -> Option<String> {
-        if let Ok(pci_slot) = fs::read_link(device_path) {
-            if let Some(slot) = pci_slot.file_name().and_then(|s| s.to_str()) {
-                if let Ok(output) = std::process::Command::new("lspci")
-                    .args(&["-n", "-s", slot])
-                    .output()
-                {
-                    if let Ok(text) = String::from_utf8(output.stdout) {
-                        // Parse "08:00.0 0300: 1002:73df (rev c1)"
-                        if let Some(ids) = text.split_whitespace().nth(2) {
-                            if let Some(device) = ids.split(':').nth(1) {
-                                return Some(device.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
+    /// PCI address of the card (e.g. "0000:03:00.0"), as used by `drm-pdev`
+    fn read_pci_slot(device_path: &PathBuf) -> Option<String> {
+        fs::read_link(device_path)
+            .ok()?
+            .file_name()?
+            .to_str()
+            .map(|s| s.to_string())
     }
-    
-    fn get_known_gpu_name(device_id: &str, vendor: &str) -> Option<String> {
-        // Sapphire GPUs
-        if vendor.contains("Sapphire") {
-            match device_id {
-                "73df" => return Some("Sapphire RX 6700 XT Nitro".to_string()),
-                "73ef" => return Some("Sapphire RX 6650 XT Nitro".to_string()),
-                "73ff" => return Some("Sapphire RX 6600 XT Nitro".to_string()),
-                "7340" => return Some("Sapphire NITRO+ Radeon RX 7900 XTX Vapor-X".to_string()),
-                "744c" => return Some("Sapphire NITRO+ Radeon RX 7900 XT".to_string()),
-                "747e" => return Some("Sapphire NITRO+ Radeon RX 7800 XT".to_string()),
-                "7480" => return Some("Sapphire NITRO+ Radeon RX 7700 XT".to_string()),
-                _ => {}
-            }
+
+    fn read_sysfs_value(&self, filename: &str) -> Option<String> {
+        fs::read_to_string(self.hwmon_path.join(filename))
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+
+    fn read_device_value(&self, filename: &str) -> Option<String> {
+        fs::read_to_string(self.device_path.join(filename))
+            .ok()
+            .map(|s| s.trim().to_string())
+    }
+
+    fn read_gpu_metrics(&self) -> Option<GpuMetrics> {
+        let data = fs::read(self.device_path.join("gpu_metrics")).ok()?;
+        GpuMetrics::parse(&data)
+    }
+
+    /// Current link from the active `pp_dpm_pcie` level (the link is
+    /// downtrained at idle), falling back to gpu_metrics and the PCI core.
+    fn read_pcie_link(&self, metrics: Option<&GpuMetrics>) -> Option<PcieLink> {
+        let dpm = self.read_device_value("pp_dpm_pcie");
+        let (dpm_speed, dpm_width) = dpm.as_deref()
+            .and_then(|content| parse_dpm_pcie(content, true))
+            .unwrap_or((None, None));
+
+        let current_speed = dpm_speed
+            .or_else(|| metrics.and_then(|m| m.pcie_speed))
+            .or_else(|| self.read_device_value("current_link_speed").and_then(|s| parse_link_speed(&s)));
+        let current_width = dpm_width
+            .or_else(|| metrics.and_then(|m| m.pcie_width))
+            .or_else(|| self.read_device_value("current_link_width").and_then(|s| s.parse().ok()));
+
+        let (top_speed, top_width) = dpm.as_deref()
+            .and_then(|content| parse_dpm_pcie(content, false))
+            .unwrap_or((None, None));
+        let max_speed = self.read_device_value("max_link_speed")
+            .and_then(|s| parse_link_speed(&s))
+            .or(top_speed);
+        let max_width = self.read_device_value("max_link_width")
+            .and_then(|s| s.parse().ok())
+            .or(top_width);
+
+        let link = PcieLink {
+            current_gen: current_speed.and_then(PcieLink::gen_from_speed),
+            current_width,
+            max_gen: max_speed.and_then(PcieLink::gen_from_speed),
+            max_width,
+        };
+        (link != PcieLink::default()).then_some(link)
+    }
+}
+
+/// Fields of the amdgpu `gpu_metrics` table used by the monitor. The table
+/// is a versioned C struct; only layouts known to match are decoded.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, PartialEq)]
+struct GpuMetrics {
+    /// Average VCN (video encode/decode) activity in percent
+    mm_activity: Option<f32>,
+    pcie_width: Option<u32>,
+    /// Per-lane link speed in GT/s
+    pcie_speed: Option<f32>,
+    /// ASIC-independent throttler bits (SMU_THROTTLER_*), v1.3+
+    indep_throttle_status: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+impl GpuMetrics {
+    fn parse(data: &[u8]) -> Option<Self> {
+        // 0xFFFF / all ones mark fields the firmware does not fill
+        let u16_at = |offset: usize| {
+            data.get(offset..offset + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .filter(|&v| v != u16::MAX)
+        };
+        let u64_at = |offset: usize| {
+            data.get(offset..offset + 8)
+                .and_then(|b| b.try_into().ok())
+                .map(u64::from_le_bytes)
+                .filter(|&v| v != u64::MAX)
+        };
+
+        let format_revision = *data.get(2)?;
+        let content_revision = *data.get(3)?;
+
+        let metrics = match (format_revision, content_revision) {
+            // Discrete GPUs, gpu_metrics_v1_1 .. v1_3 share this prefix
+            (1, 1..=3) => GpuMetrics {
+                mm_activity: u16_at(20).map(|v| v as f32),
+                pcie_width: u16_at(74).map(|v| v as u32).filter(|&w| w > 0),
+                pcie_speed: u16_at(76).map(|v| v as f32 / 10.0).filter(|&s| s > 0.0),
+                indep_throttle_status: if content_revision >= 3 { u64_at(112) } else { None },
+            },
+            // APUs, gpu_metrics_v2_1 and later
+            (2, 1..) => GpuMetrics {
+                mm_activity: u16_at(30).map(|v| v as f32),
+                ..GpuMetrics::default()
+            },
+            _ => return None,
+        };
+
+        Some(metrics)
+    }
+
+    fn throttle_reasons(&self) -> Option<Vec<ThrottleReason>> {
+        let status = self.indep_throttle_status?;
+        // Bit groups from the kernel's SMU_THROTTLER_* definitions
+        let groups = [
+            (0x0000_0000_0000_00ff_u64, ThrottleReason::PowerCap),   // PPT/SPL
+            (0x0000_0000_00ff_0000_u64, ThrottleReason::HwSlowdown), // TDC/EDC current limits
+            (0x0000_0fff_0000_0000_u64, ThrottleReason::Thermal),    // temperature limits
+            (0x0000_f000_0000_0000_u64, ThrottleReason::HwSlowdown), // VRHOT/PROCHOT
+            (0xff00_0000_0000_0000_u64, ThrottleReason::Other),      // PPM/FIT
+        ];
+
+        let mut reasons: Vec<ThrottleReason> = groups.into_iter()
+            .filter(|(mask, _)| status & mask != 0)
+            .map(|(_, reason)| reason)
+            .collect();
+        reasons.sort();
+        reasons.dedup();
+        Some(reasons)
+    }
+}
+
+/// Parse `pp_dpm_pcie` ("1: 16.0GT/s, x16 1000Mhz *") into link speed and
+/// width of the active level, or of the highest level when `active` is false
+#[cfg(target_os = "linux")]
+fn parse_dpm_pcie(content: &str, active: bool) -> Option<(Option<f32>, Option<u32>)> {
+    let line = if active {
+        content.lines().find(|line| line.trim_end().ends_with('*'))?
+    } else {
+        content.lines().rfind(|line| !line.trim().is_empty())?
+    };
+    let (_, level) = line.split_once(':')?;
+
+    let speed = level.split("GT/s").next()
+        .and_then(|s| s.trim().parse::<f32>().ok());
+    let width = level.split_whitespace()
+        .find_map(|token| token.strip_prefix('x'))
+        .and_then(|w| w.trim_end_matches(',').parse::<u32>().ok());
+
+    Some((speed, width))
+}
+
+/// Parse the highest clock of `pp_od_clk_voltage` for the `OD_SCLK` and
+/// `OD_MCLK` sections ("1: 2800Mhz", or "7: 1366MHz 1150mV" on older cards)
+#[cfg(target_os = "linux")]
+fn parse_od_clocks(content: &str) -> (Option<u32>, Option<u32>) {
+    let (mut sclk, mut mclk): (Option<u32>, Option<u32>) = (None, None);
+    let mut section = "";
+
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_suffix(':') {
+            section = header;
+            continue;
         }
-        
-        // ASUS GPUs
-        if vendor.contains("ASUSTeK") || vendor.contains("ASUS") {
-            match device_id {
-                "73df" => return Some("ASUS ROG Strix RX 6700 XT OC".to_string()),
-                "73ef" => return Some("ASUS TUF Gaming RX 6650 XT OC".to_string()),
-                "73ff" => return Some("ASUS ROG Strix RX 6600 XT OC".to_string()),
-                "7340" => return Some("ASUS ROG Strix RX 7900 XTX OC".to_string()),
-                "744c" => return Some("ASUS TUF Gaming RX 7900 XT OC".to_string()),
-                "747e" => return Some("ASUS TUF Gaming RX 7800 XT OC".to_string()),
-                "7480" => return Some("ASUS Dual RX 7700 XT OC".to_string()),
-                _ => {}
-            }
+        let target = match section {
+            "OD_SCLK" => &mut sclk,
+            "OD_MCLK" => &mut mclk,
+            _ => continue,
+        };
+        if let Some(mhz) = line.split_once(':').and_then(|(_, level)| parse_mhz(level)) {
+            *target = Some(target.map_or(mhz, |max| max.max(mhz)));
         }
-        
-        // MSI GPUs
-        if vendor.contains("Micro-Star") || vendor.contains("MSI") {
-            match device_id {
-                "73df" => return Some("MSI Radeon RX 6700 XT Gaming X".to_string()),
-                "73ef" => return Some("MSI Radeon RX 6650 XT Mech 2X OC".to_string()),
-                "73ff" => return Some("MSI Radeon RX 6600 XT Gaming X".to_string()),
-                "7340" => return Some("MSI Radeon RX 7900 XTX Gaming Trio".to_string()),
-                "744c" => return Some("MSI Radeon RX 7900 XT Gaming Trio".to_string()),
-                "747e" => return Some("MSI Radeon RX 7800 XT Gaming Trio".to_string()),
-                "7480" => return Some("MSI Radeon RX 7700 XT Gaming X".to_string()),
-                _ => {}
+    }
+
+    (sclk, mclk)
+}
+
+/// Highest level of a `pp_dpm_*` clock table ("1: 2400Mhz *")
+#[cfg(target_os = "linux")]
+fn parse_dpm_max(content: &str) -> Option<u32> {
+    content.lines()
+        .filter_map(|line| parse_mhz(line.split_once(':')?.1))
+        .max()
+}
+
+#[cfg(target_os = "linux")]
+fn parse_mhz(level: &str) -> Option<u32> {
+    let token = level.split_whitespace().next()?.to_lowercase();
+    token.strip_suffix("mhz")?.parse::<u32>().ok()
+}
+
+/// Parse a PCI core link speed such as "16.0 GT/s PCIe"
+#[cfg(target_os = "linux")]
+fn parse_link_speed(value: &str) -> Option<f32> {
+    value.split_whitespace().next()?.parse::<f32>().ok()
+}
+
+#[cfg(target_os = "linux")]
+impl AmdBackend {
+    fn read_watts(&self, filename: &str) -> Option<f32> {
+        self.read_sysfs_value(filename)
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(|v| v as f32 / 1_000_000.0) // µW to W
+    }
+
+    fn power_cap_range(&self) -> Option<PowerCapRange> {
+        Some(PowerCapRange {
+            min: self.read_watts("power1_cap_min")?,
+            max: self.read_watts("power1_cap_max")?,
+            default: self.read_watts("power1_cap_default"),
+            current: self.read_watts("power1_cap"),
+        })
+    }
+
+    fn power_profiles(&self) -> Vec<PowerProfile> {
+        self.read_device_value("pp_power_profile_mode")
+            .map(|content| parse_power_profiles(&content))
+            .unwrap_or_default()
+    }
+
+    fn has_manual_fan(&self) -> bool {
+        self.hwmon_path.join("pwm1").exists() && self.hwmon_path.join("pwm1_enable").exists()
+    }
+
+    /// pwm1 value for a duty cycle, scaled to the card's pwm range
+    fn pwm_value(&self, duty: u8) -> String {
+        let max = self.read_sysfs_value("pwm1_max")
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(255);
+        (max * duty.min(100) as u32 / 100).to_string()
+    }
+
+    fn hottest_temperature(&self) -> Option<f32> {
+        hwmon::read_temperatures(&self.hwmon_path)
+            .into_iter()
+            .map(|sensor| sensor.temperature)
+            .reduce(f32::max)
+    }
+
+    /// The sysfs writes a request translates to, after validating it
+    fn plan_control(&self, request: &ControlRequest) -> Result<Vec<SysfsWrite>> {
+        let level_path = self.device_path.join("power_dpm_force_performance_level");
+
+        match request {
+            ControlRequest::PowerCap(watts) => {
+                let range = self.power_cap_range()
+                    .ok_or_else(|| Error::Gpu("Power cap is not adjustable".to_string()))?;
+                range.validate(*watts)?;
+                let microwatts = (*watts as f64 * 1_000_000.0).round() as u64;
+                Ok(vec![SysfsWrite::new(self.hwmon_path.join("power1_cap"), microwatts.to_string())])
             }
-        }
-        
-        // Gigabyte GPUs
-        if vendor.contains("Gigabyte") {
-            match device_id {
-                "73df" => return Some("Gigabyte Radeon RX 6700 XT Gaming OC".to_string()),
-                "73ef" => return Some("Gigabyte Radeon RX 6650 XT Eagle".to_string()),
-                "73ff" => return Some("Gigabyte Radeon RX 6600 XT Gaming OC Pro".to_string()),
-                "7340" => return Some("Gigabyte Radeon RX 7900 XTX Gaming OC".to_string()),
-                "744c" => return Some("Gigabyte Radeon RX 7900 XT Gaming OC".to_string()),
-                "747e" => return Some("Gigabyte Radeon RX 7800 XT Gaming OC".to_string()),
-                "7480" => return Some("Gigabyte Radeon RX 7700 XT Gaming OC".to_string()),
-                _ => {}
+            ControlRequest::PerformanceLevel(level) => {
+                if !PERFORMANCE_LEVELS.contains(&level.as_str()) {
+                    return Err(Error::Gpu(format!("Unknown performance level: {}", level)));
+                }
+                if !level_path.exists() {
+                    return Err(Error::Gpu("Performance level is not adjustable".to_string()));
+                }
+                Ok(vec![SysfsWrite::new(level_path, level.clone())])
             }
-        }
-        
-        // XFX GPUs
-        if vendor.contains("XFX") || vendor.contains("Pine Technology") {
-            match device_id {
-                "73df" => return Some("XFX Speedster MERC 319 RX 6700 XT".to_string()),
-                "73ef" => return Some("XFX Speedster SWFT 210 RX 6650 XT".to_string()),
-                "73ff" => return Some("XFX Speedster QICK 308 RX 6600 XT".to_string()),
-                "7340" => return Some("XFX Speedster MERC 310 RX 7900 XTX".to_string()),
-                "744c" => return Some("XFX Speedster MERC 310 RX 7900 XT".to_string()),
-                "747e" => return Some("XFX Speedster MERC 319 RX 7800 XT".to_string()),
-                "7480" => return Some("XFX Speedster QICK 319 RX 7700 XT".to_string()),
-                _ => {}
+            ControlRequest::PowerProfile(name) => {
+                let profiles = self.power_profiles();
+                let profile = profiles.iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| Error::Gpu(format!("Unknown power profile: {}", name)))?;
+                // Restore writes back only the index; without a marked
+                // profile there is nothing safe to put back
+                let active = profiles.iter().find(|p| p.active)
+                    .map(|p| p.index.to_string())
+                    .ok_or_else(|| Error::Gpu("Cannot tell the active power profile".to_string()))?;
+
+                // Profiles only take effect in the manual performance level
+                let mut writes = Vec::new();
+                if self.read_device_value("power_dpm_force_performance_level").as_deref() != Some("manual") {
+                    writes.push(SysfsWrite::new(level_path, "manual"));
+                }
+                writes.push(SysfsWrite {
+                    path: self.device_path.join("pp_power_profile_mode"),
+                    value: profile.index.to_string(),
+                    restore: Some(active),
+                });
+                Ok(writes)
             }
-        }
-        
-        // PowerColor GPUs
-        if vendor.contains("PowerColor") || vendor.contains("TUL Corporation") {
-            match device_id {
-                "73df" => return Some("PowerColor Red Devil RX 6700 XT".to_string()),
-                "73ef" => return Some("PowerColor Fighter RX 6650 XT".to_string()),
-                "73ff" => return Some("PowerColor Red Devil RX 6600 XT".to_string()),
-                "7340" => return Some("PowerColor Red Devil RX 7900 XTX".to_string()),
-                "744c" => return Some("PowerColor Red Devil RX 7900 XT".to_string()),
-                "747e" => return Some("PowerColor Hellhound RX 7800 XT".to_string()),
-                "7480" => return Some("PowerColor Hellhound RX 7700 XT".to_string()),
-                _ => {}
+            ControlRequest::FanCurve(curve) => {
+                if !self.has_manual_fan() {
+                    return Err(Error::Gpu("Manual fan control is not supported".to_string()));
+                }
+                let duty = curve.duty_at(self.hottest_temperature().unwrap_or(f32::MAX));
+                Ok(vec![
+                    SysfsWrite::new(self.hwmon_path.join("pwm1_enable"), "1"),
+                    SysfsWrite::new(self.hwmon_path.join("pwm1"), self.pwm_value(duty)),
+                ])
+            }
+            ControlRequest::FanAuto => {
+                if !self.has_manual_fan() {
+                    return Err(Error::Gpu("Manual fan control is not supported".to_string()));
+                }
+                Ok(vec![SysfsWrite::new(self.hwmon_path.join("pwm1_enable"), "2")])
             }
         }
-        
-        None
     }
+}
 
-    fn read_sysfs_value(&self, filename: &str) -> Option<String> {
-        fs::read_to_string(self.hwmon_path.join(filename))
-            .ok()
-            .map(|s| s.trim().to_string())
-    }
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, PartialEq)]
+struct PowerProfile {
+    index: usize,
+    name: String,
+    active: bool,
+}
+
+/// Parse `pp_power_profile_mode`. Profile rows start with their index and
+/// end the name with ':'; the active one is marked with '*'. Per-clock
+/// detail rows on newer cards ("0(GFXCLK) ...") are skipped.
+#[cfg(target_os = "linux")]
+fn parse_power_profiles(content: &str) -> Vec<PowerProfile> {
+    content.lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let (index, rest) = line.split_once(char::is_whitespace)?;
+            let index = index.parse().ok()?;
+            let (name, _) = rest.split_once(':')?;
+            let active = name.contains('*');
+            let name = name.replace('*', "").trim().to_string();
+            (!name.is_empty()).then_some(PowerProfile { index, name, active })
+        })
+        .collect()
 }
 
 #[cfg(target_os = "linux")]
 impl GPUBackend for AmdBackend {
+    fn collect(&self) -> GPUSample {
+        GPUSample::timed(|| {
+            let metrics = self.read_gpu_metrics();
+
+            GPUSample {
+                temperature: self.read_temperature(),
+                utilization: self.read_utilization(),
+                memory_used: self.read_memory_used(),
+                memory_total: self.memory_total,
+                // APUs and cards that spill over keep much of their memory in GTT
+                gtt_used: Self::read_mem_info(&self.device_path, "gtt_used"),
+                gtt_total: Self::read_mem_info(&self.device_path, "gtt_total"),
+                vis_vram_used: Self::read_mem_info(&self.device_path, "vis_vram_used"),
+                vis_vram_total: Self::read_mem_info(&self.device_path, "vis_vram_total"),
+                power_draw: self.read_power_draw(),
+                power_limit: self.read_power_limit(),
+                clock_speed: self.read_clock_speed(),
+                memory_clock: self.read_memory_clock(),
+                fan_rpm: self.read_fan_rpm(),
+                processes: self.read_processes(),
+                media_utilization: metrics.as_ref().and_then(|m| m.mm_activity),
+                pcie_link: self.read_pcie_link(metrics.as_ref()),
+                // PCIe throughput is unsupported: reading `pcie_bw` sleeps
+                // for a second in the driver while it counts packets, which
+                // would stall every refresh, and it reports packet counts
+                // rather than bytes
+                throttle_reasons: metrics.as_ref().and_then(|m| m.throttle_reasons()),
+                sensors: hwmon::read_temperatures(&self.hwmon_path),
+                ..GPUSample::new(self.name.clone(), "AMD")
+            }
+        })
+    }
+
+    fn sample_cache(&self) -> &SampleCache {
+        &self.samples
+    }
+
+    fn device_key(&self) -> String {
+        self.key.clone()
+    }
+
+    fn pci_slot(&self) -> Option<String> {
+        self.pci_slot.clone()
+    }
+
+    fn properties(&self) -> DeviceProperties {
+        self.properties.clone()
+    }
+
+    fn is_stale(&self) -> bool {
+        // The card may have been renumbered and its hwmon node re-created
+        !self.hwmon_path.exists()
+            || Self::read_pci_slot(&self.device_path) != self.pci_slot
+    }
+
     fn name(&self) -> String {
         self.name.clone()
     }
@@ -265,50 +543,131 @@ impl GPUBackend for AmdBackend {
         "AMD".to_string()
     }
 
-    fn temperature(&self) -> Option<f32> {
+    fn control_capabilities(&self) -> ControlCapabilities {
+        let profiles = self.power_profiles();
+        let level_path = self.device_path.join("power_dpm_force_performance_level");
+        ControlCapabilities {
+            power_cap: self.power_cap_range(),
+            performance_levels: if level_path.exists() {
+                PERFORMANCE_LEVELS.iter().map(|level| level.to_string()).collect()
+            } else {
+                Vec::new()
+            },
+            performance_level: self.read_device_value("power_dpm_force_performance_level"),
+            power_profile: profiles.iter().find(|p| p.active).map(|p| p.name.clone()),
+            power_profiles: profiles.into_iter().map(|p| p.name).collect(),
+            fan_curve: self.has_manual_fan(),
+        }
+    }
+
+    fn apply_control(&self, request: &ControlRequest, dry_run: bool) -> Result<Vec<String>> {
+        let writes = self.plan_control(request)?;
+        let applied = self.controls.apply(&writes, dry_run)?;
+
+        if !dry_run {
+            if let Ok(mut fan_curve) = self.fan_curve.lock() {
+                match request {
+                    ControlRequest::FanCurve(curve) => *fan_curve = Some(curve.clone()),
+                    ControlRequest::FanAuto => *fan_curve = None,
+                    _ => {}
+                }
+            }
+        }
+        Ok(applied)
+    }
+
+    fn tick_controls(&self) {
+        let Some(curve) = self.fan_curve.lock().ok().and_then(|curve| curve.clone()) else {
+            return;
+        };
+        // Without a reading, run the fan at the top of the curve to be safe
+        let duty = curve.duty_at(self.hottest_temperature().unwrap_or(f32::MAX));
+        if let Err(e) = self.controls.write(&self.hwmon_path.join("pwm1"), &self.pwm_value(duty), None) {
+            log::warn!("{}: {}", self.name, e);
+        }
+    }
+
+    fn restore_controls(&self) -> Result<usize> {
+        if let Ok(mut fan_curve) = self.fan_curve.lock() {
+            *fan_curve = None;
+        }
+        self.controls.restore()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl AmdBackend {
+    fn read_temperature(&self) -> Option<f32> {
         self.read_sysfs_value("temp1_input")
             .and_then(|s| s.parse::<i32>().ok())
             .map(|t| t as f32 / 1000.0)
     }
 
-    fn utilization(&self) -> Option<f32> {
+    fn read_utilization(&self) -> Option<f32> {
         fs::read_to_string(self.device_path.join("gpu_busy_percent"))
             .ok()
             .and_then(|s| s.trim().parse::<f32>().ok())
     }
 
-    fn memory_used(&self) -> Option<u64> {
-        fs::read_to_string(self.device_path.join("mem_info_vram_used"))
-            .ok()
-            .and_then(|s| s.trim().parse::<u64>().ok())
+    fn read_memory_used(&self) -> Option<u64> {
+        Self::read_mem_info(&self.device_path, "vram_used")
     }
 
-    fn memory_total(&self) -> Option<u64> {
-        fs::read_to_string(self.device_path.join("mem_info_vram_total"))
+    /// Inventory from sysfs. Max clocks come from the overdrive table when
+    /// it is enabled, else from the highest DPM level.
+    fn read_properties(root: &FsRoot, device_path: &Path) -> DeviceProperties {
+        let read = |name: &str| {
+            fs::read_to_string(device_path.join(name))
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let (od_sclk, od_mclk) = read("pp_od_clk_voltage")
+            .map(|content| parse_od_clocks(&content))
+            .unwrap_or_default();
+
+        DeviceProperties {
+            vbios_version: read("vbios_version"),
+            uuid: read("unique_id"),
+            max_clock: od_sclk.or_else(|| read("pp_dpm_sclk").and_then(|c| parse_dpm_max(&c))),
+            max_memory_clock: od_mclk.or_else(|| read("pp_dpm_mclk").and_then(|c| parse_dpm_max(&c))),
+            ..DeviceProperties::from_sysfs(root, device_path)
+        }
+    }
+
+    /// VRAM size is fixed, so it is read once at detection
+    fn read_memory_total(device_path: &Path) -> Option<u64> {
+        Self::read_mem_info(device_path, "vram_total")
+    }
+
+    /// `mem_info_*` counters in bytes
+    fn read_mem_info(device_path: &Path, counter: &str) -> Option<u64> {
+        fs::read_to_string(device_path.join(format!("mem_info_{}", counter)))
             .ok()
             .and_then(|s| s.trim().parse::<u64>().ok())
     }
 
-    fn power_draw(&self) -> Option<f32> {
+    fn read_power_draw(&self) -> Option<f32> {
         self.read_sysfs_value("power1_average")
             .and_then(|s| s.parse::<u64>().ok())
             .map(|p| p as f32 / 1_000_000.0)
     }
 
-    fn power_limit(&self) -> Option<f32> {
+    fn read_power_limit(&self) -> Option<f32> {
         self.read_sysfs_value("power1_cap")
             .and_then(|s| s.parse::<u64>().ok())
             .map(|v| v as f32 / 1_000_000.0) // µW to W
     }
 
-    fn clock_speed(&self) -> Option<u32> {
+    fn read_clock_speed(&self) -> Option<u32> {
         self.read_sysfs_value("freq1_input")
             .and_then(|s| s.parse::<u64>().ok())
             .map(|f| (f / 1_000_000) as u32)
     }
 
-    fn memory_clock(&self) -> Option<u32> {
-        self.read_sysfs_value("pp_dpm_mclk")
+    /// Active `pp_dpm_mclk` level of the device, else the hwmon memory clock
+    fn read_memory_clock(&self) -> Option<u32> {
+        self.read_device_value("pp_dpm_mclk")
             .and_then(|content| {
                 content.lines()
                     .find(|line| line.contains('*'))
@@ -323,15 +682,20 @@ impl GPUBackend for AmdBackend {
                             .ok()
                     })
             })
+            .or_else(|| self.read_sysfs_value("freq2_input")
+                .and_then(|s| s.parse::<u64>().ok())
+                .map(|f| (f / 1_000_000) as u32))
     }
 
-    fn fan_speed(&self) -> Option<u32> {
+    fn read_fan_rpm(&self) -> Option<u32> {
         self.read_sysfs_value("fan1_input")
             .and_then(|s| s.parse::<u32>().ok())
     }
 
-    fn processes(&self) -> Vec<crate::gpu::backend::GPUProcess> {
-        Vec::new()
+    fn read_processes(&self) -> Vec<crate::gpu::backend::GPUProcess> {
+        self.clients.as_ref()
+            .and_then(|clients| clients.lock().ok().map(|mut tracker| tracker.processes()))
+            .unwrap_or_default()
     }
 }
 
@@ -341,22 +705,184 @@ pub struct AmdBackend;
 #[cfg(not(target_os = "linux"))]
 impl AmdBackend {
     pub fn detect_all() -> Result<Vec<Self>> {
+        Self::detect_all_in(&crate::fsroot::FsRoot::global())
+    }
+
+    pub fn detect_all_in(_root: &crate::fsroot::FsRoot) -> Result<Vec<Self>> {
         Err(Error::Gpu("AMD GPU monitoring not supported on this platform".to_string()))
     }
 }
 
 #[cfg(not(target_os = "linux"))]
 impl GPUBackend for AmdBackend {
-    fn name(&self) -> String { "Unsupported".to_string() }
-    fn vendor(&self) -> String { "AMD".to_string() }
-    fn temperature(&self) -> Option<f32> { None }
-    fn utilization(&self) -> Option<f32> { None }
-    fn memory_used(&self) -> Option<u64> { None }
-    fn memory_total(&self) -> Option<u64> { None }
-    fn power_draw(&self) -> Option<f32> { None }
-    fn power_limit(&self) -> Option<f32> { None }
-    fn clock_speed(&self) -> Option<u32> { None }
-    fn memory_clock(&self) -> Option<u32> { None }
-    fn fan_speed(&self) -> Option<u32> { None }
-    fn processes(&self) -> Vec<crate::gpu::backend::GPUProcess> { Vec::new() }
+    fn collect(&self) -> GPUSample {
+        GPUSample::new("Unsupported".to_string(), "AMD")
+    }
+
+    fn sample_cache(&self) -> &SampleCache {
+        static SAMPLES: SampleCache = SampleCache::new();
+        &SAMPLES
+    }
+
+    fn device_key(&self) -> String {
+        "unsupported".to_string()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_detect_from_fixture_root() {
+        let root = TestRoot::new("amd");
+        let device = "/sys/devices/pci0000:00/0000:00:01.0/0000:03:00.0";
+        root.write_file(format!("{}/vendor", device), "0x1002\n").unwrap();
+        root.write_file(format!("{}/product_name", device), "Radeon RX 7800 XT\n").unwrap();
+        root.write_file(format!("{}/gpu_busy_percent", device), "42\n").unwrap();
+        root.write_file(format!("{}/mem_info_vram_total", device), "17163091968\n").unwrap();
+        root.write_file(format!("{}/mem_info_gtt_used", device), "3221225472\n").unwrap();
+        root.write_file(format!("{}/mem_info_gtt_total", device), "4294967296\n").unwrap();
+        root.write_file(format!("{}/pp_dpm_mclk", device), "0: 96Mhz\n1: 456Mhz\n2: 1249Mhz *\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/name", device), "amdgpu\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp1_input", device), "51000\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp1_label", device), "edge\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp1_crit", device), "100000\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp2_input", device), "63000\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp2_label", device), "junction\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp2_crit", device), "110000\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/temp10_input", device), "60000\n").unwrap();
+        root.symlink("/sys/class/drm/card1/device", "../../../devices/pci0000:00/0000:00:01.0/0000:03:00.0").unwrap();
+        // Connectors must not be mistaken for cards
+        root.write_file("/sys/class/drm/card1-DP-1/status", "connected\n").unwrap();
+
+        let gpus = AmdBackend::detect_all_in(&root).unwrap();
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].name(), "Radeon RX 7800 XT");
+        assert_eq!(gpus[0].utilization(), Some(42.0));
+        assert_eq!(gpus[0].temperature(), Some(51.0));
+        assert_eq!(gpus[0].memory_total(), Some(17163091968));
+        let sensors = gpus[0].sensors();
+        let labels: Vec<&str> = sensors.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, ["edge", "junction", "temp10"]);
+        assert_eq!(sensors[1].temperature, 63.0);
+        assert_eq!(sensors[1].critical, Some(110.0));
+        assert_eq!(sensors[2].critical, None);
+        assert_eq!(gpus[0].clients.as_ref().unwrap().lock().unwrap().pdev(), "0000:03:00.0");
+
+        let info = crate::gpu::backend::GPUInfo::from_sample(0, gpus[0].device_key(), gpus[0].sample());
+        assert_eq!(info.gtt_usage_percent(), Some(75.0));
+        assert_eq!(info.vis_vram_usage_percent(), None);
+        assert_eq!(info.memory_clock, Some(1249));
+
+        // Without a DPM table the hwmon memory clock is used
+        std::fs::remove_file(root.path(format!("{}/pp_dpm_mclk", device))).unwrap();
+        root.write_file(format!("{}/hwmon/hwmon3/freq2_input", device), "1000000000\n").unwrap();
+        assert_eq!(gpus[0].collect().memory_clock, Some(1000));
+    }
+
+    #[test]
+    fn test_controls_on_writable_fixture() {
+        let root = TestRoot::new("amd-control");
+        let device = "/sys/devices/pci0000:00/0000:00:01.0/0000:03:00.0";
+        let hwmon = format!("{}/hwmon/hwmon3", device);
+        root.write_file(format!("{}/vendor", device), "0x1002\n").unwrap();
+        root.write_file(format!("{}/power_dpm_force_performance_level", device), "auto\n").unwrap();
+        root.write_file(format!("{}/pp_power_profile_mode", device),
+            "NUM        MODE_NAME     SCLK_UP_HYST\n  0   BOOTUP_DEFAULT:        -\n  1 3D_FULL_SCREEN *:        0\n  5          COMPUTE:        0\n").unwrap();
+        root.write_file(format!("{}/name", hwmon), "amdgpu\n").unwrap();
+        root.write_file(format!("{}/temp1_input", hwmon), "60000\n").unwrap();
+        root.write_file(format!("{}/power1_cap", hwmon), "200000000\n").unwrap();
+        root.write_file(format!("{}/power1_cap_min", hwmon), "100000000\n").unwrap();
+        root.write_file(format!("{}/power1_cap_max", hwmon), "250000000\n").unwrap();
+        root.write_file(format!("{}/pwm1", hwmon), "80\n").unwrap();
+        root.write_file(format!("{}/pwm1_enable", hwmon), "2\n").unwrap();
+        root.symlink("/sys/class/drm/card0/device", "../../../devices/pci0000:00/0000:00:01.0/0000:03:00.0").unwrap();
+        let read = |path: &str| fs::read_to_string(root.path(path)).unwrap().trim().to_string();
+
+        let gpus = AmdBackend::detect_all_in(&root).unwrap();
+        let gpu = &gpus[0];
+        let caps = gpu.control_capabilities();
+        assert_eq!(caps.power_cap.map(|cap| (cap.min, cap.max)), Some((100.0, 250.0)));
+        assert_eq!(caps.power_profiles, ["BOOTUP_DEFAULT", "3D_FULL_SCREEN", "COMPUTE"]);
+        assert_eq!(caps.power_profile.as_deref(), Some("3D_FULL_SCREEN"));
+        assert!(caps.fan_curve);
+
+        assert!(gpu.apply_control(&ControlRequest::PowerCap(300.0), true).is_err());
+        assert!(gpu.apply_control(&ControlRequest::PerformanceLevel("turbo".into()), true).is_err());
+        gpu.apply_control(&ControlRequest::PowerCap(180.0), true).unwrap();
+        assert_eq!(read(&format!("{}/power1_cap", hwmon)), "200000000");
+
+        gpu.apply_control(&ControlRequest::PowerCap(180.0), false).unwrap();
+        gpu.apply_control(&ControlRequest::PowerProfile("compute".into()), false).unwrap();
+        let curve = FanCurve::new(vec![(40.0, 20), (80.0, 100)]).unwrap();
+        gpu.apply_control(&ControlRequest::FanCurve(curve), false).unwrap();
+        assert_eq!(read(&format!("{}/power1_cap", hwmon)), "180000000");
+        assert_eq!(read(&format!("{}/power_dpm_force_performance_level", device)), "manual");
+        assert_eq!(read(&format!("{}/pp_power_profile_mode", device)), "5");
+        assert_eq!(read(&format!("{}/pwm1_enable", hwmon)), "1");
+        assert_eq!(read(&format!("{}/pwm1", hwmon)), "153"); // 60% of 255
+
+        assert_eq!(gpu.restore_controls().unwrap(), 5);
+        assert_eq!(read(&format!("{}/power1_cap", hwmon)), "200000000");
+        assert_eq!(read(&format!("{}/power_dpm_force_performance_level", device)), "auto");
+        assert_eq!(read(&format!("{}/pp_power_profile_mode", device)), "1");
+        assert_eq!(read(&format!("{}/pwm1_enable", hwmon)), "2");
+        assert_eq!(read(&format!("{}/pwm1", hwmon)), "80");
+
+        // Without a marked profile the original cannot be restored, so the
+        // table is left alone rather than saved and written back whole
+        let table = "NUM        MODE_NAME     SCLK_UP_HYST\n  0   BOOTUP_DEFAULT:        -\n  5          COMPUTE:        0\n";
+        root.write_file(format!("{}/pp_power_profile_mode", device), table).unwrap();
+        assert!(gpu.apply_control(&ControlRequest::PowerProfile("compute".into()), false).is_err());
+        assert_eq!(fs::read_to_string(root.path(format!("{}/pp_power_profile_mode", device))).unwrap(), table);
+        assert_eq!(gpu.restore_controls().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_dpm_pcie() {
+        let content = "0: 2.5GT/s, x8 619Mhz\n1: 16.0GT/s, x16 1138Mhz *\n";
+        assert_eq!(parse_dpm_pcie(content, true), Some((Some(16.0), Some(16))));
+        assert_eq!(parse_dpm_pcie("0: 2.5GT/s, x8 *\n1: 8.0GT/s, x16\n", false), Some((Some(8.0), Some(16))));
+        assert_eq!(parse_link_speed("16.0 GT/s PCIe"), Some(16.0));
+        assert_eq!(PcieLink::gen_from_speed(16.0), Some(4));
+    }
+
+    #[test]
+    fn test_parse_od_clocks() {
+        let rdna3 = "OD_SCLK:\n0: 500Mhz\n1: 2800Mhz\nOD_MCLK:\n0: 97Mhz\n1: 1250MHz\n\
+            OD_VDDGFX_OFFSET:\n0mV\nOD_RANGE:\nSCLK:     500Mhz       3150Mhz\n";
+        assert_eq!(parse_od_clocks(rdna3), (Some(2800), Some(1250)));
+        let polaris = "OD_SCLK:\n0:        300MHz        750mV\n7:       1366MHz       1150mV\n";
+        assert_eq!(parse_od_clocks(polaris), (Some(1366), None));
+        assert_eq!(parse_dpm_max("0: 500Mhz\n1: 2400Mhz *\n2: 2600Mhz\n"), Some(2600));
+    }
+
+    #[test]
+    fn test_parse_gpu_metrics_v1_3() {
+        let mut data = vec![0u8; 128];
+        data[0..2].copy_from_slice(&128u16.to_le_bytes());
+        data[2] = 1;
+        data[3] = 3;
+        data[20..22].copy_from_slice(&37u16.to_le_bytes());  // average_mm_activity
+        data[74..76].copy_from_slice(&16u16.to_le_bytes());  // pcie_link_width
+        data[76..78].copy_from_slice(&160u16.to_le_bytes()); // pcie_link_speed, 0.1 GT/s
+        let status: u64 = (1 << 0) | (1 << 35) | (1 << 44) | (1 << 16);
+        data[112..120].copy_from_slice(&status.to_le_bytes());
+
+        let metrics = GpuMetrics::parse(&data).unwrap();
+        assert_eq!(metrics.mm_activity, Some(37.0));
+        assert_eq!(metrics.pcie_width, Some(16));
+        assert_eq!(metrics.pcie_speed, Some(16.0));
+        assert_eq!(metrics.throttle_reasons(), Some(vec![
+            ThrottleReason::Thermal,
+            ThrottleReason::PowerCap,
+            ThrottleReason::HwSlowdown,
+        ]));
+
+        // Unknown layouts are not guessed at
+        data[2] = 3;
+        assert!(GpuMetrics::parse(&data).is_none());
+    }
 }
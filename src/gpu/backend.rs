@@ -1,39 +1,498 @@
+use crate::error::{Error, Result};
+use crate::fsroot::FsRoot;
+use crate::gpu::control::{self, ControlCapabilities, ControlRequest};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A GPU backend. `collect` gathers every metric in one pass so backends
+/// can batch reads and reuse static data. Rates (power from energy
+/// counters, engine busy time) are measured since the previous pass, so
+/// callers use `sample`, which also keeps the result for the single-metric
+/// getters; those read the kept sample and never advance the rates.
 pub trait GPUBackend: Send + Sync {
-    fn name(&self) -> String;
-    fn vendor(&self) -> String;
-    fn temperature(&self) -> Option<f32>;
-    fn utilization(&self) -> Option<f32>;
-    fn memory_used(&self) -> Option<u64>;
-    fn memory_total(&self) -> Option<u64>;
-    fn power_draw(&self) -> Option<f32>;
-    fn power_limit(&self) -> Option<f32>;
-    fn clock_speed(&self) -> Option<u32>;
-    fn memory_clock(&self) -> Option<u32>;
-    fn fan_speed(&self) -> Option<u32>;
-    fn processes(&self) -> Vec<GPUProcess>;
+    fn collect(&self) -> GPUSample;
+
+    /// Storage for the last sample, see `SampleCache`
+    fn sample_cache(&self) -> &SampleCache;
+
+    /// Collect a new sample and keep it for the getters
+    fn sample(&self) -> GPUSample {
+        let sample = self.collect();
+        self.sample_cache().store(&sample);
+        sample
+    }
+
+    /// The last sample, or a first one when none was taken yet
+    fn latest(&self) -> GPUSample {
+        self.sample_cache().get().unwrap_or_else(|| self.sample())
+    }
+
+    /// Stable identity of the physical device (UUID or PCI bus ID). It does
+    /// not change when cards are re-enumerated, so per-GPU state is keyed by it.
+    fn device_key(&self) -> String;
+
+    /// True once the handle no longer reaches a live device, e.g. after a
+    /// driver reload moved its sysfs nodes. Stale backends are re-created.
+    fn is_stale(&self) -> bool { false }
+
+    /// PCI address in sysfs form ("0000:03:00.0"), when known. Used to
+    /// keep the generic DRM backend off cards a vendor backend handles.
+    fn pci_slot(&self) -> Option<String> { None }
+
+    /// Firmware, driver and capability inventory. It does not change while
+    /// the device is attached, so backends read it once at detection.
+    fn properties(&self) -> DeviceProperties { DeviceProperties::default() }
+
+    fn name(&self) -> String { self.latest().name }
+    fn vendor(&self) -> String { self.latest().vendor }
+    fn temperature(&self) -> Option<f32> { self.latest().temperature }
+    fn utilization(&self) -> Option<f32> { self.latest().utilization }
+    fn memory_used(&self) -> Option<u64> { self.latest().memory_used }
+    fn memory_total(&self) -> Option<u64> { self.latest().memory_total }
+    fn power_draw(&self) -> Option<f32> { self.latest().power_draw }
+    fn power_limit(&self) -> Option<f32> { self.latest().power_limit }
+    fn clock_speed(&self) -> Option<u32> { self.latest().clock_speed }
+    fn memory_clock(&self) -> Option<u32> { self.latest().memory_clock }
+    fn fan_speed(&self) -> Option<u32> { self.latest().fan_speed }
+    fn fan_rpm(&self) -> Option<u32> { self.latest().fan_rpm }
+    fn processes(&self) -> Vec<GPUProcess> { self.latest().processes }
+
+    // Extended telemetry; `None` when the backend or device lacks it
+    fn encoder_utilization(&self) -> Option<f32> { self.latest().encoder_utilization }
+    fn decoder_utilization(&self) -> Option<f32> { self.latest().decoder_utilization }
+    fn media_utilization(&self) -> Option<f32> { self.latest().media_utilization }
+    fn pcie_link(&self) -> Option<PcieLink> { self.latest().pcie_link }
+    fn pcie_throughput(&self) -> Option<PcieThroughput> { self.latest().pcie_throughput }
+    fn throttle_reasons(&self) -> Option<Vec<ThrottleReason>> { self.latest().throttle_reasons }
+    fn sensors(&self) -> Vec<GPUSensor> { self.latest().sensors }
+
+    // Opt-in tuning, see `gpu::control`. Unsupported unless overridden.
+    fn control_capabilities(&self) -> ControlCapabilities { ControlCapabilities::default() }
+
+    /// Validate a change and, unless `dry_run`, apply it. Returns the
+    /// individual settings that were (or would be) written.
+    fn apply_control(&self, _request: &ControlRequest, _dry_run: bool) -> Result<Vec<String>> {
+        Err(Error::Gpu(format!("{} does not support tuning", self.name())))
+    }
+
+    /// Keep active controls in step with the device, e.g. a fan curve
+    fn tick_controls(&self) {}
+
+    /// Put back every setting changed by `apply_control`; returns how many
+    fn restore_controls(&self) -> Result<usize> { Ok(0) }
+}
+
+/// Static facts about a device. Fields are `None` when the backend or
+/// driver does not expose them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceProperties {
+    /// Kernel module bound to the device ("amdgpu", "nvidia", "nouveau", ...)
+    pub driver: Option<String>,
+    pub driver_version: Option<String>,
+    pub vbios_version: Option<String>,
+    /// CUDA compute capability, e.g. "8.9"
+    pub compute_capability: Option<String>,
+    pub pci_bus_id: Option<String>,
+    /// NVML UUID or amdgpu `unique_id`
+    pub uuid: Option<String>,
+    /// Highest core and memory clocks in MHz
+    pub max_clock: Option<u32>,
+    pub max_memory_clock: Option<u32>,
+}
+
+impl DeviceProperties {
+    /// Driver name and version and PCI address of a sysfs device.
+    /// In-tree drivers carry no version of their own; the kernel release
+    /// stands in for it and is labelled as such.
+    pub fn from_sysfs(root: &FsRoot, device_path: &Path) -> Self {
+        let link_name = |path: &Path| {
+            std::fs::read_link(path).ok()?
+                .file_name()?
+                .to_str()
+                .map(|s| s.to_string())
+        };
+        let read_trimmed = |path: PathBuf| {
+            std::fs::read_to_string(path).ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let driver = link_name(&device_path.join("driver"));
+        let driver_version = driver.as_ref()
+            .and_then(|driver| read_trimmed(root.path(format!("/sys/module/{}/version", driver))))
+            .or_else(|| read_trimmed(root.path("/proc/sys/kernel/osrelease"))
+                .map(|release| format!("kernel {}", release)));
+
+        Self {
+            driver,
+            driver_version,
+            pci_bus_id: link_name(device_path),
+            ..Self::default()
+        }
+    }
+
+    /// (label, value) pairs of the fields that are set, for display
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mhz = |clock: Option<u32>| clock.map(|c| format!("{} MHz", c));
+        [
+            ("Driver", self.driver.clone()),
+            ("Driver version", self.driver_version.clone()),
+            ("VBIOS", self.vbios_version.clone()),
+            ("Compute capability", self.compute_capability.clone()),
+            ("PCI bus ID", self.pci_bus_id.clone()),
+            ("UUID", self.uuid.clone()),
+            ("Max core clock", mhz(self.max_clock)),
+            ("Max memory clock", mhz(self.max_memory_clock)),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
+    }
+}
+
+/// One temperature sensor of a GPU (edge, junction, memory, ...)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GPUSensor {
+    pub label: String,
+    pub temperature: f32,
+    /// Limit at which the hardware starts protecting itself
+    pub critical: Option<f32>,
+}
+
+/// PCIe link of a GPU. Fields are `None` when the driver does not say.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PcieLink {
+    pub current_gen: Option<u32>,
+    pub current_width: Option<u32>,
+    pub max_gen: Option<u32>,
+    pub max_width: Option<u32>,
+}
+
+impl PcieLink {
+    /// PCIe generation for a per-lane transfer rate in GT/s
+    pub fn gen_from_speed(gts: f32) -> Option<u32> {
+        match gts {
+            s if s >= 60.0 => Some(6),
+            s if s >= 30.0 => Some(5),
+            s if s >= 15.0 => Some(4),
+            s if s >= 7.5 => Some(3),
+            s if s >= 4.5 => Some(2),
+            s if s >= 2.0 => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Whether the link runs on fewer lanes than it supports. Dropping to a
+    /// lower generation at idle is normal power saving; losing lanes is not.
+    pub fn is_degraded(&self) -> bool {
+        matches!((self.current_width, self.max_width), (Some(cur), Some(max)) if cur < max)
+    }
+
+    /// "Gen4 x16", with "?" for unknown parts
+    pub fn format(gen: Option<u32>, width: Option<u32>) -> String {
+        let gen = gen.map(|g| g.to_string()).unwrap_or_else(|| "?".to_string());
+        let width = width.map(|w| w.to_string()).unwrap_or_else(|| "?".to_string());
+        format!("Gen{} x{}", gen, width)
+    }
+}
+
+/// PCIe traffic in KB/s
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PcieThroughput {
+    pub tx_kbps: u32,
+    pub rx_kbps: u32,
+}
+
+/// Why the GPU is currently running below its requested clocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ThrottleReason {
+    Thermal,
+    PowerCap,
+    HwSlowdown,
+    Other,
+}
+
+impl ThrottleReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThrottleReason::Thermal => "thermal",
+            ThrottleReason::PowerCap => "power cap",
+            ThrottleReason::HwSlowdown => "HW slowdown",
+            ThrottleReason::Other => "other",
+        }
+    }
+}
+
+/// Snapshot of one GPU taken by a single `GPUBackend::sample` call
+#[derive(Debug, Clone)]
+pub struct GPUSample {
+    pub name: String,
+    pub vendor: String,
+    pub temperature: Option<f32>,
+    pub utilization: Option<f32>,
+    pub memory_used: Option<u64>,
+    pub memory_total: Option<u64>,
+    /// System memory mapped for the GPU (AMD GTT), bytes
+    pub gtt_used: Option<u64>,
+    pub gtt_total: Option<u64>,
+    /// VRAM the CPU can address directly (AMD BAR), bytes
+    pub vis_vram_used: Option<u64>,
+    pub vis_vram_total: Option<u64>,
+    pub power_draw: Option<f32>,
+    pub power_limit: Option<f32>,
+    pub clock_speed: Option<u32>,
+    pub memory_clock: Option<u32>,
+    /// Fan duty in percent (NVIDIA)
+    pub fan_speed: Option<u32>,
+    /// Fan speed in RPM (hwmon `fan1_input` on AMD and Intel)
+    pub fan_rpm: Option<u32>,
+    pub processes: Vec<GPUProcess>,
+    pub encoder_utilization: Option<f32>,
+    pub decoder_utilization: Option<f32>,
+    /// Combined video engine load where encode and decode share one
+    /// engine and are not reported separately (AMD VCN)
+    pub media_utilization: Option<f32>,
+    pub pcie_link: Option<PcieLink>,
+    /// NVIDIA only; amdgpu's `pcie_bw` is not read, see `AmdBackend::collect`
+    pub pcie_throughput: Option<PcieThroughput>,
+    /// Empty when the GPU is not throttled
+    pub throttle_reasons: Option<Vec<ThrottleReason>>,
+    /// All temperature sensors; `temperature` is the primary one
+    pub sensors: Vec<GPUSensor>,
+    /// When collection started
+    pub collected_at: Instant,
+    /// How long the backend took to collect the sample
+    pub collection_time: Duration,
+}
+
+/// The last sample a backend took. Backends own one and hand it out from
+/// `GPUBackend::sample_cache`.
+#[derive(Debug, Default)]
+pub struct SampleCache {
+    last: Mutex<Option<GPUSample>>,
+}
+
+impl SampleCache {
+    pub const fn new() -> Self {
+        Self { last: Mutex::new(None) }
+    }
+
+    pub fn store(&self, sample: &GPUSample) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(sample.clone());
+        }
+    }
+
+    pub fn get(&self) -> Option<GPUSample> {
+        self.last.lock().ok().and_then(|last| last.clone())
+    }
+}
+
+impl GPUSample {
+    pub fn new(name: String, vendor: &str) -> Self {
+        Self {
+            name,
+            vendor: vendor.to_string(),
+            temperature: None,
+            utilization: None,
+            memory_used: None,
+            memory_total: None,
+            gtt_used: None,
+            gtt_total: None,
+            vis_vram_used: None,
+            vis_vram_total: None,
+            power_draw: None,
+            power_limit: None,
+            clock_speed: None,
+            memory_clock: None,
+            fan_speed: None,
+            fan_rpm: None,
+            processes: Vec::new(),
+            encoder_utilization: None,
+            decoder_utilization: None,
+            media_utilization: None,
+            pcie_link: None,
+            pcie_throughput: None,
+            throttle_reasons: None,
+            sensors: Vec::new(),
+            collected_at: Instant::now(),
+            collection_time: Duration::ZERO,
+        }
+    }
+
+    /// Run `collect` and stamp the result with its start time and duration
+    pub fn timed(collect: impl FnOnce() -> GPUSample) -> GPUSample {
+        let start = Instant::now();
+        let mut sample = collect();
+        sample.collected_at = start;
+        sample.collection_time = start.elapsed();
+        sample
+    }
+}
+
+/// How a process uses the GPU, as reported by the driver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GPUClientType {
+    Graphics,
+    Compute,
+    Both,
+}
+
+impl GPUClientType {
+    pub fn merge(self, other: GPUClientType) -> GPUClientType {
+        if self == other {
+            self
+        } else {
+            GPUClientType::Both
+        }
+    }
+
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            GPUClientType::Graphics => "G",
+            GPUClientType::Compute => "C",
+            GPUClientType::Both => "C+G",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct GPUProcess {
     pub pid: u32,
     pub name: String,
-    pub memory_used: u64, // bytes
+    pub memory_used: Option<u64>, // bytes, None when the driver withholds it
+    pub utilization: Option<f32>, // percent of the busiest engine (SM on NVIDIA)
+    pub encoder_utilization: Option<f32>,
+    pub decoder_utilization: Option<f32>,
+    pub client_type: Option<GPUClientType>,
+}
+
+/// Change in the set of GPUs found by `GPUManager::redetect`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GPUEvent {
+    Added { key: String, name: String },
+    Removed { key: String, name: String },
 }
 
 pub struct GPUManager {
-    backends: Vec<Box<dyn GPUBackend>>,
+    backends: Vec<Arc<dyn GPUBackend>>,
+    root: FsRoot,
+    /// DRM cards and their device links at the last detection
+    drm_listing: Vec<(String, Option<PathBuf>)>,
 }
 
 impl GPUManager {
     pub fn new() -> Self {
-        let mut backends: Vec<Box<dyn GPUBackend>> = Vec::new();
+        Self::with_root(&FsRoot::global())
+    }
+
+    pub fn with_root(root: &FsRoot) -> Self {
+        Self {
+            backends: Self::detect_backends(root),
+            root: root.clone(),
+            drm_listing: Self::read_drm_listing(root),
+        }
+    }
+
+    /// Re-detect GPUs if a card appeared, went away or was renumbered since
+    /// the last detection, or a backend lost its device. Listing
+    /// /sys/class/drm is cheap; probing the backends is not, so this is
+    /// what the periodic rescan calls.
+    pub fn rescan(&mut self) -> Vec<GPUEvent> {
+        let listing = Self::read_drm_listing(&self.root);
+        if listing == self.drm_listing && !self.backends.iter().any(|gpu| gpu.is_stale()) {
+            return Vec::new();
+        }
+        self.redetect()
+    }
+
+    /// `cardN` entries of /sys/class/drm with their device link targets.
+    /// Connectors ("card0-DP-1") and render nodes are left out.
+    fn read_drm_listing(root: &FsRoot) -> Vec<(String, Option<PathBuf>)> {
+        let Ok(entries) = std::fs::read_dir(root.path("/sys/class/drm")) else {
+            return Vec::new();
+        };
+        let mut listing: Vec<(String, Option<PathBuf>)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                if !name.starts_with("card") || name.contains('-') {
+                    return None;
+                }
+                let device = std::fs::read_link(entry.path().join("device")).ok();
+                Some((name, device))
+            })
+            .collect();
+        listing.sort();
+        listing
+    }
+
+    /// Probe all backends again and reconcile with the known GPUs by device
+    /// key. Backends of GPUs that are still present are kept, so their rate
+    /// counters and process trackers survive the rescan.
+    pub fn redetect(&mut self) -> Vec<GPUEvent> {
+        self.drm_listing = Self::read_drm_listing(&self.root);
+        let mut detected = Self::detect_backends(&self.root);
+        let mut events = Vec::new();
+
+        let detected_keys: HashSet<String> = detected.iter().map(|gpu| gpu.device_key()).collect();
+        self.backends.retain(|gpu| {
+            let key = gpu.device_key();
+            if detected_keys.contains(&key) {
+                return true;
+            }
+            // Put back what was changed in case the device returns
+            if let Err(e) = gpu.restore_controls() {
+                log::warn!("GPU {}: {}", key, e);
+            }
+            events.push(GPUEvent::Removed { key, name: gpu.name() });
+            false
+        });
+
+        for gpu in self.backends.iter_mut() {
+            if gpu.is_stale() {
+                let key = gpu.device_key();
+                if let Some(pos) = detected.iter().position(|fresh| fresh.device_key() == key) {
+                    log::debug!("Re-created stale backend for GPU {}", key);
+                    if let Err(e) = gpu.restore_controls() {
+                        log::warn!("GPU {}: {}", key, e);
+                    }
+                    *gpu = detected.swap_remove(pos);
+                }
+            }
+        }
+
+        let known: HashSet<String> = self.backends.iter().map(|gpu| gpu.device_key()).collect();
+        for gpu in detected {
+            if !known.contains(&gpu.device_key()) {
+                events.push(GPUEvent::Added { key: gpu.device_key(), name: gpu.name() });
+                self.backends.push(gpu);
+            }
+        }
+
+        for event in &events {
+            match event {
+                GPUEvent::Added { key, name } => log::info!("GPU added: {} ({})", name, key),
+                GPUEvent::Removed { key, name } => log::info!("GPU removed: {} ({})", name, key),
+            }
+        }
+
+        events
+    }
+
+    /// Detect GPUs from the sysfs tree under `root`. NVML talks to the
+    /// driver directly, so NVIDIA cards are only probed on the host root.
+    fn detect_backends(root: &FsRoot) -> Vec<Arc<dyn GPUBackend>> {
+        let mut backends: Vec<Arc<dyn GPUBackend>> = Vec::new();
 
         #[cfg(feature = "nvidia")]
-        {
+        if root.is_host() {
             use super::nvidia::NvidiaBackend;
             if let Ok(nvidia_gpus) = NvidiaBackend::detect_all() {
                 for gpu in nvidia_gpus {
-                    backends.push(Box::new(gpu));
+                    backends.push(Arc::new(gpu));
                 }
             }
         }
@@ -41,9 +500,9 @@ impl GPUManager {
         #[cfg(all(target_os = "linux", feature = "amd"))]
         {
             use super::amd::AmdBackend;
-            if let Ok(amd_gpus) = AmdBackend::detect_all() {
+            if let Ok(amd_gpus) = AmdBackend::detect_all_in(root) {
                 for gpu in amd_gpus {
-                    backends.push(Box::new(gpu));
+                    backends.push(Arc::new(gpu));
                 }
             }
         }
@@ -51,17 +510,32 @@ impl GPUManager {
         #[cfg(feature = "intel")]
         {
             use super::intel::IntelBackend;
-            if let Ok(intel_gpus) = IntelBackend::detect_all() {
+            if let Ok(intel_gpus) = IntelBackend::detect_all_in(root) {
                 for gpu in intel_gpus {
-                    backends.push(Box::new(gpu));
+                    backends.push(Arc::new(gpu));
+                }
+            }
+        }
+
+        // Anything left over gets basic data from the generic DRM backend
+        #[cfg(all(target_os = "linux", feature = "drm"))]
+        {
+            use super::drm::DrmBackend;
+            let claimed: HashSet<String> = backends.iter().filter_map(|gpu| gpu.pci_slot()).collect();
+            if let Ok(drm_gpus) = DrmBackend::detect_all_in(root, &claimed) {
+                for gpu in drm_gpus {
+                    backends.push(Arc::new(gpu));
                 }
             }
         }
 
-        Self { backends }
+        for gpu in &backends {
+            control::register(gpu);
+        }
+        backends
     }
 
-    pub fn gpus(&self) -> &[Box<dyn GPUBackend>] {
+    pub fn gpus(&self) -> &[Arc<dyn GPUBackend>] {
         &self.backends
     }
 
@@ -77,40 +551,69 @@ impl GPUManager {
         self.backends.iter()
             .enumerate()
             .map(|(i, gpu)| {
-                let power_draw = gpu.power_draw();
-                let power_limit = gpu.power_limit();
-                let utilization = gpu.utilization();
-                
-                // Calculate power efficiency (utilization per watt)
-                let power_efficiency = if let (Some(util), Some(power)) = (utilization, power_draw) {
-                    if power > 0.0 {
-                        Some(util / power)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
-                
-                GPUInfo {
-                    id: i,
-                    name: gpu.name(),
-                    vendor: gpu.vendor(),
-                    temperature: gpu.temperature(),
-                    utilization,
-                    memory_used: gpu.memory_used(),
-                    memory_total: gpu.memory_total(),
-                    power_draw,
-                    power_limit,
-                    power_efficiency,
-                    clock_speed: gpu.clock_speed(),
-                    memory_clock: gpu.memory_clock(),
-                    fan_speed: gpu.fan_speed(),
-                    processes: gpu.processes(),
+                let sample = gpu.sample();
+                if sample.collection_time > Duration::from_millis(100) {
+                    log::debug!("Sampling {} took {:?}", sample.name, sample.collection_time);
                 }
+                GPUInfo::from_sample(i, gpu.device_key(), sample)
             })
             .collect()
     }
+
+    fn find(&self, key: &str) -> Result<&dyn GPUBackend> {
+        self.backends.iter()
+            .find(|gpu| gpu.device_key() == key)
+            .map(|gpu| gpu.as_ref())
+            .ok_or_else(|| Error::Gpu(format!("GPU {} not found", key)))
+    }
+
+    pub fn properties(&self, key: &str) -> Option<DeviceProperties> {
+        self.find(key).ok().map(|gpu| gpu.properties())
+    }
+
+    pub fn control_capabilities(&self, key: &str) -> Option<ControlCapabilities> {
+        self.find(key).ok().map(|gpu| gpu.control_capabilities())
+    }
+
+    pub fn apply_control(&self, key: &str, request: &ControlRequest, dry_run: bool) -> Result<Vec<String>> {
+        if control::is_exiting() {
+            return Err(Error::Gpu("GPU settings were restored for shutdown".to_string()));
+        }
+        let gpu = self.find(key)?;
+        let changes = gpu.apply_control(request, dry_run)?;
+        if !dry_run {
+            log::info!("{}: set {}", gpu.name(), request.describe());
+        }
+        Ok(changes)
+    }
+
+    pub fn tick_controls(&self) {
+        if control::is_exiting() {
+            return;
+        }
+        for gpu in &self.backends {
+            gpu.tick_controls();
+        }
+    }
+
+    /// Restore original settings on every GPU; returns how many were put back
+    pub fn restore_controls(&self) -> usize {
+        self.backends.iter()
+            .map(|gpu| gpu.restore_controls().unwrap_or_else(|e| {
+                log::warn!("{}: {}", gpu.name(), e);
+                0
+            }))
+            .sum()
+    }
+}
+
+impl Drop for GPUManager {
+    fn drop(&mut self) {
+        let restored = self.restore_controls();
+        if restored > 0 {
+            log::info!("Restored {} GPU setting(s)", restored);
+        }
+    }
 }
 
 impl Default for GPUManager {
@@ -121,29 +624,177 @@ impl Default for GPUManager {
 
 #[derive(Debug, Clone)]
 pub struct GPUInfo {
+    /// Position in the current GPU list; changes when GPUs come and go
     pub id: usize,
+    /// Stable device identity, see `GPUBackend::device_key`
+    pub key: String,
     pub name: String,
     pub vendor: String,
     pub temperature: Option<f32>,
     pub utilization: Option<f32>,
     pub memory_used: Option<u64>,
     pub memory_total: Option<u64>,
+    pub gtt_used: Option<u64>,
+    pub gtt_total: Option<u64>,
+    pub vis_vram_used: Option<u64>,
+    pub vis_vram_total: Option<u64>,
     pub power_draw: Option<f32>,
     pub power_limit: Option<f32>,
     pub power_efficiency: Option<f32>,
     pub clock_speed: Option<u32>,
     pub memory_clock: Option<u32>,
     pub fan_speed: Option<u32>,
+    pub fan_rpm: Option<u32>,
     pub processes: Vec<GPUProcess>,
+    pub encoder_utilization: Option<f32>,
+    pub decoder_utilization: Option<f32>,
+    pub media_utilization: Option<f32>,
+    pub pcie_link: Option<PcieLink>,
+    pub pcie_throughput: Option<PcieThroughput>,
+    pub throttle_reasons: Option<Vec<ThrottleReason>>,
+    pub sensors: Vec<GPUSensor>,
+    pub collection_time: Duration,
 }
 
 impl GPUInfo {
+    pub fn from_sample(id: usize, key: String, sample: GPUSample) -> Self {
+        // Calculate power efficiency (utilization per watt)
+        let power_efficiency = match (sample.utilization, sample.power_draw) {
+            (Some(util), Some(power)) if power > 0.0 => Some(util / power),
+            _ => None,
+        };
+
+        Self {
+            id,
+            key,
+            name: sample.name,
+            vendor: sample.vendor,
+            temperature: sample.temperature,
+            utilization: sample.utilization,
+            memory_used: sample.memory_used,
+            memory_total: sample.memory_total,
+            gtt_used: sample.gtt_used,
+            gtt_total: sample.gtt_total,
+            vis_vram_used: sample.vis_vram_used,
+            vis_vram_total: sample.vis_vram_total,
+            power_draw: sample.power_draw,
+            power_limit: sample.power_limit,
+            power_efficiency,
+            clock_speed: sample.clock_speed,
+            memory_clock: sample.memory_clock,
+            fan_speed: sample.fan_speed,
+            fan_rpm: sample.fan_rpm,
+            processes: sample.processes,
+            encoder_utilization: sample.encoder_utilization,
+            decoder_utilization: sample.decoder_utilization,
+            media_utilization: sample.media_utilization,
+            pcie_link: sample.pcie_link,
+            pcie_throughput: sample.pcie_throughput,
+            throttle_reasons: sample.throttle_reasons,
+            sensors: sample.sensors,
+            collection_time: sample.collection_time,
+        }
+    }
+
     pub fn memory_usage_percent(&self) -> Option<f32> {
-        match (self.memory_used, self.memory_total) {
-            (Some(used), Some(total)) if total > 0 => {
-                Some((used as f32 / total as f32) * 100.0)
+        usage_percent(self.memory_used, self.memory_total)
+    }
+
+    pub fn gtt_usage_percent(&self) -> Option<f32> {
+        usage_percent(self.gtt_used, self.gtt_total)
+    }
+
+    pub fn vis_vram_usage_percent(&self) -> Option<f32> {
+        usage_percent(self.vis_vram_used, self.vis_vram_total)
+    }
+}
+
+fn usage_percent(used: Option<u64>, total: Option<u64>) -> Option<f32> {
+    match (used, total) {
+        (Some(used), Some(total)) if total > 0 => {
+            Some((used as f32 / total as f32) * 100.0)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(target_os = "linux", feature = "amd"))]
+    use crate::fsroot::TestRoot;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Reports how many times it was collected as its utilisation
+    #[derive(Default)]
+    struct CountingBackend {
+        collected: AtomicU32,
+        samples: SampleCache,
+    }
+
+    impl GPUBackend for CountingBackend {
+        fn collect(&self) -> GPUSample {
+            let count = self.collected.fetch_add(1, Ordering::Relaxed) + 1;
+            GPUSample {
+                utilization: Some(count as f32),
+                ..GPUSample::new("Counting".to_string(), "Test")
             }
-            _ => None,
         }
+
+        fn sample_cache(&self) -> &SampleCache {
+            &self.samples
+        }
+
+        fn device_key(&self) -> String {
+            "counting".to_string()
+        }
+    }
+
+    #[test]
+    fn test_getters_read_last_sample() {
+        let gpu = CountingBackend::default();
+        // Without a sample yet, the first getter takes one
+        assert_eq!(gpu.utilization(), Some(1.0));
+        assert_eq!(gpu.power_draw(), None);
+        assert_eq!(gpu.name(), "Counting");
+        assert_eq!(gpu.utilization(), Some(1.0));
+
+        assert_eq!(gpu.sample().utilization, Some(2.0));
+        assert_eq!(gpu.utilization(), Some(2.0));
+        assert_eq!(gpu.collected.load(Ordering::Relaxed), 2);
+    }
+
+    #[cfg(all(target_os = "linux", feature = "amd"))]
+    fn add_amd_card(root: &FsRoot, card: &str, slot: &str) {
+        let device = format!("/sys/devices/pci0000:00/{}", slot);
+        root.write_file(format!("{}/vendor", device), "0x1002\n").unwrap();
+        root.write_file(format!("{}/product_name", device), "Radeon Test\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon0/name", device), "amdgpu\n").unwrap();
+        root.symlink(format!("/sys/class/drm/{}/device", card), format!("../../../devices/pci0000:00/{}", slot)).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "amd"))]
+    fn test_redetect_tracks_devices_by_key() {
+        let root = TestRoot::new("hotplug");
+        add_amd_card(&root, "card0", "0000:03:00.0");
+
+        let mut manager = GPUManager::with_root(&root);
+        assert_eq!(manager.get_info()[0].key, "0000:03:00.0");
+        assert!(manager.rescan().is_empty());
+
+        // An eGPU appears and takes the card0 slot, pushing the old card to card1
+        std::fs::remove_dir_all(root.path("/sys/class/drm")).unwrap();
+        add_amd_card(&root, "card0", "0000:0a:00.0");
+        add_amd_card(&root, "card1", "0000:03:00.0");
+
+        let events = manager.rescan();
+        assert_eq!(events, vec![GPUEvent::Added { key: "0000:0a:00.0".to_string(), name: "Radeon Test".to_string() }]);
+        let keys: Vec<String> = manager.get_info().into_iter().map(|gpu| gpu.key).collect();
+        assert_eq!(keys, vec!["0000:03:00.0", "0000:0a:00.0"]);
+
+        std::fs::remove_dir_all(root.path("/sys/class/drm/card0")).unwrap();
+        let events = manager.rescan();
+        assert_eq!(events, vec![GPUEvent::Removed { key: "0000:0a:00.0".to_string(), name: "Radeon Test".to_string() }]);
     }
 }
@@ -0,0 +1,335 @@
+//! Opt-in GPU tuning: power cap, performance level, power profile and
+//! manual fan curve.
+//!
+//! Changes go through `GPUBackend::apply_control`. A dry run validates the
+//! request and reports the writes it would make without touching the
+//! device. Backends remember the original value of everything they change
+//! and put it back in `restore_controls`, which `GPUManager` calls when it
+//! is dropped, before it drops a removed GPU, and `restore_all` calls on a
+//! panic or a termination signal, where nothing is dropped.
+
+use crate::error::{Error, Result};
+use crate::gpu::GPUBackend;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Every backend that was detected, for `restore_all`
+static BACKENDS: Mutex<Vec<Weak<dyn GPUBackend>>> = Mutex::new(Vec::new());
+
+/// Set once `restore_all` ran; no further changes are made after it
+static EXITING: AtomicBool = AtomicBool::new(false);
+
+/// Make a backend reachable from `restore_all`
+pub fn register(backend: &Arc<dyn GPUBackend>) {
+    if let Ok(mut backends) = BACKENDS.lock() {
+        backends.retain(|known| known.strong_count() > 0);
+        backends.push(Arc::downgrade(backend));
+    }
+}
+
+/// Whether `restore_all` ran and changes must no longer be applied
+pub fn is_exiting() -> bool {
+    EXITING.load(Ordering::SeqCst)
+}
+
+/// Restore original settings on every live backend and stop applying
+/// changes, including fan curve ticks. Returns how many were put back.
+pub fn restore_all() -> usize {
+    EXITING.store(true, Ordering::SeqCst);
+    // A panic may happen while the list is locked; skip rather than deadlock
+    let backends: Vec<Arc<dyn GPUBackend>> = match BACKENDS.try_lock() {
+        Ok(backends) => backends.iter().filter_map(Weak::upgrade).collect(),
+        Err(_) => return 0,
+    };
+    backends.iter()
+        .map(|gpu| gpu.restore_controls().unwrap_or_else(|e| {
+            log::warn!("{}: {}", gpu.name(), e);
+            0
+        }))
+        .sum()
+}
+
+/// Restore GPU settings before a panic is reported. A panic with
+/// `panic = "abort"` or on another thread does not drop `GPUManager`.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_all();
+        previous(info);
+    }));
+}
+
+/// Restore GPU settings and remove an unpacked fixture on SIGINT, SIGTERM
+/// and SIGHUP, then exit. The
+/// signals are blocked on the calling thread, so call this from `main`
+/// before any other thread starts; the threads inherit the mask and only
+/// the waiting thread receives them.
+#[cfg(unix)]
+pub fn install_signal_handler() -> Result<()> {
+    use nix::sys::signal::{SigSet, Signal};
+
+    let mut signals = SigSet::empty();
+    for signal in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGHUP] {
+        signals.add(signal);
+    }
+    signals.thread_block()
+        .map_err(|e| Error::System(format!("Cannot block signals: {}", e)))?;
+
+    std::thread::Builder::new()
+        .name("exit-cleanup".to_string())
+        .spawn(move || {
+            if let Ok(signal) = signals.wait() {
+                let restored = restore_all();
+                crate::fsroot::FsRoot::remove_unpacked_global();
+                log::info!("Received {}, restored {} GPU setting(s)", signal, restored);
+                std::process::exit(128 + signal as i32);
+            }
+        })
+        .map_err(|e| Error::System(format!("Cannot start signal thread: {}", e)))?;
+    Ok(())
+}
+
+/// A single change to a GPU's tuning
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlRequest {
+    /// Board power limit in watts
+    PowerCap(f32),
+    /// Driver performance level, e.g. "auto", "high" or "manual"
+    PerformanceLevel(String),
+    /// Workload profile by name, e.g. "COMPUTE" or "3D_FULL_SCREEN"
+    PowerProfile(String),
+    /// Drive the fan from the hottest sensor using this curve
+    FanCurve(FanCurve),
+    /// Hand the fan back to the firmware
+    FanAuto,
+}
+
+impl ControlRequest {
+    pub fn describe(&self) -> String {
+        match self {
+            ControlRequest::PowerCap(watts) => format!("power cap {:.0} W", watts),
+            ControlRequest::PerformanceLevel(level) => format!("performance level {}", level),
+            ControlRequest::PowerProfile(profile) => format!("power profile {}", profile),
+            ControlRequest::FanCurve(curve) => format!("fan curve {}", curve),
+            ControlRequest::FanAuto => "automatic fan control".to_string(),
+        }
+    }
+}
+
+/// Fan duty by temperature, linearly interpolated between points
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanCurve {
+    /// (°C, duty %) sorted by temperature
+    points: Vec<(f32, u8)>,
+}
+
+impl FanCurve {
+    pub fn new(points: Vec<(f32, u8)>) -> Result<Self> {
+        if points.len() < 2 {
+            return Err(Error::Config("Fan curve needs at least two points".to_string()));
+        }
+        if points.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err(Error::Config("Fan curve temperatures must be increasing".to_string()));
+        }
+        if let Some(&(temp, duty)) = points.iter().find(|&&(_, duty)| duty > 100) {
+            return Err(Error::Config(format!("Fan duty {}% at {}°C is above 100%", duty, temp)));
+        }
+        Ok(Self { points })
+    }
+
+    /// Built-in curves offered by the control dialog
+    pub fn presets() -> Vec<(&'static str, FanCurve)> {
+        [
+            ("quiet", vec![(50.0, 20), (70.0, 40), (85.0, 70), (95.0, 100)]),
+            ("balanced", vec![(40.0, 25), (60.0, 45), (75.0, 70), (90.0, 100)]),
+            ("performance", vec![(30.0, 40), (55.0, 60), (70.0, 85), (80.0, 100)]),
+        ]
+        .into_iter()
+        .filter_map(|(name, points)| Some((name, Self::new(points).ok()?)))
+        .collect()
+    }
+
+    pub fn duty_at(&self, temperature: f32) -> u8 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if temperature <= first.0 {
+            return first.1;
+        }
+        if temperature >= last.0 {
+            return last.1;
+        }
+
+        self.points.windows(2)
+            .find(|pair| temperature <= pair[1].0)
+            .map(|pair| {
+                let ((t0, d0), (t1, d1)) = (pair[0], pair[1]);
+                let ratio = (temperature - t0) / (t1 - t0);
+                (d0 as f32 + ratio * (d1 as f32 - d0 as f32)).round() as u8
+            })
+            .unwrap_or(last.1)
+    }
+}
+
+impl std::fmt::Display for FanCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let points: Vec<String> = self.points.iter()
+            .map(|(temp, duty)| format!("{:.0}°C:{}%", temp, duty))
+            .collect();
+        write!(f, "{}", points.join(" "))
+    }
+}
+
+/// Allowed power cap in watts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PowerCapRange {
+    pub min: f32,
+    pub max: f32,
+    pub default: Option<f32>,
+    pub current: Option<f32>,
+}
+
+impl PowerCapRange {
+    pub fn validate(&self, watts: f32) -> Result<()> {
+        if watts < self.min || watts > self.max {
+            return Err(Error::Gpu(format!(
+                "Power cap {:.0} W is outside the allowed {:.0}-{:.0} W", watts, self.min, self.max)));
+        }
+        Ok(())
+    }
+}
+
+/// What a backend can change on a device, with the current settings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ControlCapabilities {
+    pub power_cap: Option<PowerCapRange>,
+    pub performance_levels: Vec<String>,
+    pub performance_level: Option<String>,
+    pub power_profiles: Vec<String>,
+    pub power_profile: Option<String>,
+    pub fan_curve: bool,
+}
+
+impl ControlCapabilities {
+    pub fn is_empty(&self) -> bool {
+        self.power_cap.is_none()
+            && self.performance_levels.is_empty()
+            && self.power_profiles.is_empty()
+            && !self.fan_curve
+    }
+}
+
+/// One planned sysfs write
+#[derive(Debug, Clone, PartialEq)]
+pub struct SysfsWrite {
+    pub path: PathBuf,
+    pub value: String,
+    /// Value to write back on restore, for files whose contents cannot
+    /// simply be written back (tables). `None` uses the current contents.
+    pub restore: Option<String>,
+}
+
+impl SysfsWrite {
+    pub fn new(path: PathBuf, value: impl Into<String>) -> Self {
+        Self { path, value: value.into(), restore: None }
+    }
+}
+
+/// Writes sysfs attributes and remembers what they held before the first
+/// change. Paths are canonicalised so a restore after card renumbering
+/// still reaches the same PCI device.
+#[derive(Debug, Default)]
+pub struct SysfsControl {
+    originals: Mutex<Vec<(PathBuf, String)>>,
+}
+
+impl SysfsControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate (`dry_run`) or perform the writes in order. Returns a
+    /// "file = value" line per write.
+    pub fn apply(&self, writes: &[SysfsWrite], dry_run: bool) -> Result<Vec<String>> {
+        let mut applied = Vec::new();
+        for write in writes {
+            if dry_run {
+                // Opening for write checks permissions without changing anything
+                fs::OpenOptions::new().write(true).open(&write.path)
+                    .map_err(|e| Error::Gpu(format!("Cannot write {}: {}", write.path.display(), e)))?;
+            } else {
+                self.write(&write.path, &write.value, write.restore.clone())?;
+            }
+            applied.push(format!("{} = {}", display_name(&write.path), write.value));
+        }
+        Ok(applied)
+    }
+
+    pub fn write(&self, path: &Path, value: &str, restore_value: Option<String>) -> Result<()> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let original = match restore_value {
+            Some(value) => value,
+            None => fs::read_to_string(&path)
+                .map(|s| s.trim().to_string())
+                .map_err(|e| Error::Gpu(format!("Cannot read {}: {}", path.display(), e)))?,
+        };
+
+        fs::write(&path, value)
+            .map_err(|e| Error::Gpu(format!("Cannot write {}: {}", path.display(), e)))?;
+
+        if let Ok(mut originals) = self.originals.lock() {
+            if !originals.iter().any(|(known, _)| *known == path) {
+                originals.push((path, original));
+            }
+        }
+        Ok(())
+    }
+
+    /// Write back every original value, newest first. Returns how many
+    /// were restored; failures are reported after trying all of them.
+    pub fn restore(&self) -> Result<usize> {
+        let originals = match self.originals.lock() {
+            Ok(mut originals) => std::mem::take(&mut *originals),
+            Err(_) => return Ok(0),
+        };
+
+        let mut restored = 0;
+        let mut failed = Vec::new();
+        for (path, value) in originals.into_iter().rev() {
+            match fs::write(&path, &value) {
+                Ok(()) => restored += 1,
+                Err(e) => failed.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(restored)
+        } else {
+            Err(Error::Gpu(format!("Failed to restore {}", failed.join(", "))))
+        }
+    }
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fan_curve_interpolation() {
+        let curve = FanCurve::new(vec![(40.0, 20), (60.0, 40), (80.0, 100)]).unwrap();
+        assert_eq!(curve.duty_at(20.0), 20);
+        assert_eq!(curve.duty_at(50.0), 30);
+        assert_eq!(curve.duty_at(70.0), 70);
+        assert_eq!(curve.duty_at(95.0), 100);
+
+        assert!(FanCurve::new(vec![(60.0, 40), (40.0, 20)]).is_err());
+        assert!(FanCurve::new(vec![(40.0, 20), (60.0, 140)]).is_err());
+        assert_eq!(FanCurve::presets().len(), 3);
+    }
+}
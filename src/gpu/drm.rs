@@ -0,0 +1,228 @@
+//! Fallback backend for any DRM card (nouveau, panfrost, msm, v3d, ...).
+//!
+//! Cards already handled by a vendor backend are skipped. Utilisation and
+//! memory are summed from the fdinfo counters of every client of the card,
+//! and temperatures come from whatever hwmon device the card registers.
+
+use crate::error::{Error, Result};
+use crate::fsroot::FsRoot;
+use crate::gpu::backend::{DeviceProperties, GPUBackend, GPUProcess, GPUSample, SampleCache};
+use crate::gpu::fdinfo::DrmClientTracker;
+use crate::gpu::hwmon;
+use crate::gpu::pciids::{PciId, PciIds};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Firmware framebuffers register a card but have no GPU behind them
+const FRAMEBUFFER_DRIVERS: &[&str] = &["simpledrm", "simple-framebuffer", "efifb", "vesafb", "ofdrm"];
+
+pub struct DrmBackend {
+    card_path: PathBuf,
+    device_path: PathBuf,
+    hwmon_path: Option<PathBuf>,
+    key: String,
+    name: String,
+    vendor: String,
+    properties: DeviceProperties,
+    clients: Mutex<DrmClientTracker>,
+    samples: SampleCache,
+}
+
+impl DrmBackend {
+    pub fn detect_all() -> Result<Vec<Self>> {
+        Self::detect_all_in(&FsRoot::global(), &HashSet::new())
+    }
+
+    /// Detect cards under the sysfs tree of `root`, leaving out the PCI
+    /// slots in `claimed`
+    pub fn detect_all_in(root: &FsRoot, claimed: &HashSet<String>) -> Result<Vec<Self>> {
+        let mut gpus = Vec::new();
+
+        let cards = match fs::read_dir(root.path("/sys/class/drm")) {
+            Ok(entries) => entries,
+            Err(_) => return Err(Error::Gpu("Failed to read /sys/class/drm".to_string())),
+        };
+
+        for entry in cards.flatten() {
+            let card_path = entry.path();
+            let name = card_path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("");
+
+            // Only main cards (card0, card1, ...), not connectors or render nodes
+            if !name.starts_with("card") || name.contains('-') {
+                continue;
+            }
+
+            let device_path = card_path.join("device");
+            let (Some(slot), Some(driver)) = (Self::read_slot(&device_path), Self::read_driver(&device_path)) else {
+                continue;
+            };
+            if claimed.contains(&slot) || FRAMEBUFFER_DRIVERS.contains(&driver.as_str()) {
+                continue;
+            }
+
+            let pci_id = PciId::read(&device_path);
+            let tracker = DrmClientTracker::new(root.clone(), slot.clone()).with_driver(driver.clone());
+
+            gpus.push(Self {
+                hwmon_path: Self::find_hwmon(&device_path),
+                name: Self::read_gpu_name(pci_id.as_ref(), &driver, &slot),
+                vendor: Self::vendor_label(pci_id.as_ref(), &driver),
+                properties: DeviceProperties::from_sysfs(root, &device_path),
+                card_path,
+                device_path,
+                key: slot,
+                clients: Mutex::new(tracker),
+                samples: SampleCache::new(),
+            });
+        }
+
+        if gpus.is_empty() {
+            return Err(Error::Gpu("No other DRM GPUs detected".to_string()));
+        }
+
+        gpus.sort_by(|a, b| a.card_path.cmp(&b.card_path));
+        Ok(gpus)
+    }
+
+    /// Bus address of the device: "0000:01:00.0" for PCI, "fde60000.gpu"
+    /// for platform devices
+    fn read_slot(device_path: &Path) -> Option<String> {
+        fs::read_link(device_path)
+            .ok()?
+            .file_name()?
+            .to_str()
+            .map(|s| s.to_string())
+    }
+
+    fn read_driver(device_path: &Path) -> Option<String> {
+        fs::read_link(device_path.join("driver"))
+            .ok()?
+            .file_name()?
+            .to_str()
+            .map(|s| s.to_string())
+    }
+
+    /// First hwmon device of the card that reports a temperature
+    fn find_hwmon(device_path: &Path) -> Option<PathBuf> {
+        let entries = fs::read_dir(device_path.join("hwmon")).ok()?;
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+        paths.into_iter().find(|path| !hwmon::read_temperatures(path).is_empty())
+    }
+
+    /// PCI ID database name when the card is on PCI, else the driver
+    fn read_gpu_name(pci_id: Option<&PciId>, driver: &str, slot: &str) -> String {
+        pci_id
+            .and_then(|id| PciIds::global().gpu_name(id))
+            .unwrap_or_else(|| format!("{} ({})", driver, slot))
+    }
+
+    fn vendor_label(pci_id: Option<&PciId>, driver: &str) -> String {
+        match pci_id.map(|id| id.vendor) {
+            Some(0x10de) => "NVIDIA".to_string(),
+            Some(0x1002) => "AMD".to_string(),
+            Some(0x8086) => "Intel".to_string(),
+            _ => driver.to_string(),
+        }
+    }
+
+    fn read_processes(&self) -> (Vec<GPUProcess>, Option<f32>, Option<u64>) {
+        match self.clients.lock() {
+            Ok(mut tracker) => {
+                let processes = tracker.processes();
+                let usage = tracker.usage();
+                (processes, usage.utilization, usage.memory_used)
+            }
+            Err(_) => (Vec::new(), None, None),
+        }
+    }
+}
+
+impl GPUBackend for DrmBackend {
+    fn collect(&self) -> GPUSample {
+        GPUSample::timed(|| {
+            let (processes, utilization, memory_used) = self.read_processes();
+            let sensors = self.hwmon_path.as_deref()
+                .map(hwmon::read_temperatures)
+                .unwrap_or_default();
+
+            GPUSample {
+                temperature: sensors.first().map(|sensor| sensor.temperature),
+                utilization,
+                memory_used,
+                processes,
+                sensors,
+                ..GPUSample::new(self.name.clone(), &self.vendor)
+            }
+        })
+    }
+
+    fn sample_cache(&self) -> &SampleCache {
+        &self.samples
+    }
+
+    fn device_key(&self) -> String {
+        self.key.clone()
+    }
+
+    fn is_stale(&self) -> bool {
+        Self::read_slot(&self.device_path).as_deref() != Some(self.key.as_str())
+            || self.hwmon_path.as_ref().is_some_and(|hwmon| !hwmon.exists())
+    }
+
+    fn pci_slot(&self) -> Option<String> {
+        PciId::read(&self.device_path).map(|_| self.key.clone())
+    }
+
+    fn properties(&self) -> DeviceProperties {
+        self.properties.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn vendor(&self) -> String {
+        self.vendor.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_detect_platform_and_skip_claimed() {
+        let root = TestRoot::new("drm");
+        let panfrost = "/sys/devices/platform/fde60000.gpu";
+        root.write_file(format!("{}/hwmon/hwmon0/temp1_input", panfrost), "48000\n").unwrap();
+        root.write_file("/sys/bus/platform/drivers/panfrost/bind", "").unwrap();
+        root.symlink(format!("{}/driver", panfrost), "../../../bus/platform/drivers/panfrost").unwrap();
+        root.symlink("/sys/class/drm/card0/device", "../../../devices/platform/fde60000.gpu").unwrap();
+
+        let amd = "/sys/devices/pci0000:00/0000:03:00.0";
+        root.write_file(format!("{}/vendor", amd), "0x1002\n").unwrap();
+        root.write_file(format!("{}/device", amd), "0x747e\n").unwrap();
+        root.write_file("/sys/bus/pci/drivers/amdgpu/bind", "").unwrap();
+        root.symlink(format!("{}/driver", amd), "../../../bus/pci/drivers/amdgpu").unwrap();
+        root.symlink("/sys/class/drm/card1/device", "../../../devices/pci0000:00/0000:03:00.0").unwrap();
+
+        let claimed = HashSet::from(["0000:03:00.0".to_string()]);
+        let gpus = DrmBackend::detect_all_in(&root, &claimed).unwrap();
+        assert_eq!(gpus.len(), 1);
+        assert_eq!(gpus[0].device_key(), "fde60000.gpu");
+        assert_eq!(gpus[0].name(), "panfrost (fde60000.gpu)");
+        assert_eq!(gpus[0].pci_slot(), None);
+        assert_eq!(gpus[0].properties().driver.as_deref(), Some("panfrost"));
+        assert_eq!(gpus[0].temperature(), Some(48.0));
+
+        let all = DrmBackend::detect_all_in(&root, &HashSet::new()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].vendor(), "AMD");
+    }
+}
@@ -0,0 +1,468 @@
+//! Per-process GPU usage from DRM fdinfo.
+//!
+//! Drivers implementing the DRM usage stats interface (amdgpu, i915, xe and
+//! most other DRM drivers) expose one block of `drm-*` keys per open client in
+//! `/proc/<pid>/fdinfo/<fd>`. Clients are tied to their card through the
+//! `drm-pdev` key, which holds the PCI address of the device. Non-PCI
+//! devices omit it, so their clients are matched by driver name instead.
+
+use crate::fsroot::FsRoot;
+use crate::gpu::backend::GPUProcess;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Busy counter of one engine class for a single client
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EngineCounter {
+    /// Busy time in ns (`drm-engine-*`) or busy cycles (`drm-cycles-*`)
+    pub busy: u64,
+    /// Total cycles elapsed (`drm-total-cycles-*`); `None` for ns counters
+    pub total: Option<u64>,
+    /// Number of engines of this class (`drm-engine-capacity-*`)
+    pub capacity: u32,
+}
+
+/// One DRM client as reported by a single fdinfo entry
+#[derive(Debug, Clone, Default)]
+pub struct DrmClient {
+    pub pid: u32,
+    pub client_id: u64,
+    pub driver: String,
+    pub pdev: String,
+    pub engines: HashMap<String, EngineCounter>,
+    /// Memory per region in bytes
+    pub memory: HashMap<String, u64>,
+}
+
+fn is_local_region(region: &str) -> bool {
+    region.starts_with("vram") || region.starts_with("local")
+}
+
+impl DrmClient {
+    /// Memory held by the client in device-local regions (`vram*`,
+    /// `local*`); `None` when it reports none
+    pub fn local_memory_bytes(&self) -> Option<u64> {
+        self.memory.keys().any(|region| is_local_region(region)).then(|| {
+            self.memory.iter()
+                .filter(|(region, _)| is_local_region(region))
+                .map(|(_, bytes)| bytes)
+                .sum()
+        })
+    }
+
+    /// Memory held by the client on the device. Device-local regions win
+    /// over system-backed ones when both exist, so discrete cards report
+    /// VRAM and integrated ones report their carve-out.
+    pub fn memory_bytes(&self) -> u64 {
+        if let Some(local) = self.local_memory_bytes() {
+            return local;
+        }
+
+        self.memory.iter()
+            .filter(|(region, _)| region.as_str() != "cpu")
+            .map(|(_, bytes)| bytes)
+            .sum()
+    }
+}
+
+/// Parse the content of one fdinfo file. Returns `None` for non-DRM fds or
+/// drivers that do not report usage stats.
+pub fn parse_fdinfo(content: &str) -> Option<DrmClient> {
+    let mut client = DrmClient::default();
+    let mut client_id = None;
+    // Memory keys in order of preference: resident, legacy memory, total
+    let mut resident = HashMap::new();
+    let mut legacy = HashMap::new();
+    let mut total = HashMap::new();
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+
+        if key == "drm-driver" {
+            client.driver = value.to_string();
+        } else if key == "drm-client-id" {
+            client_id = value.parse::<u64>().ok();
+        } else if key == "drm-pdev" {
+            client.pdev = value.to_string();
+        } else if let Some(engine) = key.strip_prefix("drm-engine-capacity-") {
+            if let Ok(capacity) = value.parse::<u32>() {
+                client.engines.entry(engine.to_string()).or_default().capacity = capacity;
+            }
+        } else if let Some(engine) = key.strip_prefix("drm-engine-") {
+            if let Some(ns) = parse_first_number(value) {
+                client.engines.entry(engine.to_string()).or_default().busy = ns;
+            }
+        } else if let Some(engine) = key.strip_prefix("drm-total-cycles-") {
+            if let Some(cycles) = parse_first_number(value) {
+                client.engines.entry(engine.to_string()).or_default().total = Some(cycles);
+            }
+        } else if let Some(engine) = key.strip_prefix("drm-cycles-") {
+            if let Some(cycles) = parse_first_number(value) {
+                client.engines.entry(engine.to_string()).or_default().busy = cycles;
+            }
+        } else if let Some(region) = key.strip_prefix("drm-resident-") {
+            if let Some(bytes) = parse_memory(value) {
+                resident.insert(region.to_string(), bytes);
+            }
+        } else if let Some(region) = key.strip_prefix("drm-memory-") {
+            if let Some(bytes) = parse_memory(value) {
+                legacy.insert(region.to_string(), bytes);
+            }
+        } else if let Some(region) = key.strip_prefix("drm-total-") {
+            if let Some(bytes) = parse_memory(value) {
+                total.insert(region.to_string(), bytes);
+            }
+        }
+    }
+
+    client.client_id = client_id?;
+    if client.driver.is_empty() {
+        return None;
+    }
+
+    client.memory = if !resident.is_empty() {
+        resident
+    } else if !legacy.is_empty() {
+        legacy
+    } else {
+        total
+    };
+
+    for engine in client.engines.values_mut() {
+        engine.capacity = engine.capacity.max(1);
+    }
+
+    Some(client)
+}
+
+fn parse_first_number(value: &str) -> Option<u64> {
+    value.split_whitespace().next()?.parse::<u64>().ok()
+}
+
+/// Parse "1234 KiB", "12 MiB" or a plain byte count into bytes
+fn parse_memory(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let amount = parts.next()?.parse::<u64>().ok()?;
+    let multiplier = match parts.next() {
+        None => 1,
+        Some("KiB") => 1024,
+        Some("MiB") => 1024 * 1024,
+        Some("GiB") => 1024 * 1024 * 1024,
+        Some(_) => return None,
+    };
+    Some(amount * multiplier)
+}
+
+/// Scans younger than this are reused, so every backend sampled in one
+/// refresh shares a single walk of /proc
+const SHARED_SCAN_AGE: Duration = Duration::from_millis(100);
+
+/// A walk of every process's DRM file descriptors
+#[derive(Clone)]
+struct Scan {
+    root: PathBuf,
+    at: Instant,
+    clients: Arc<Vec<DrmClient>>,
+}
+
+static LAST_SCAN: Mutex<Option<Scan>> = Mutex::new(None);
+
+/// Collect all DRM clients bound to the device at `pdev` (e.g. "0000:03:00.0")
+pub fn scan_clients(root: &FsRoot, pdev: &str) -> Vec<DrmClient> {
+    shared_scan(root).clients.iter()
+        .filter(|client| client.pdev == pdev)
+        .cloned()
+        .collect()
+}
+
+/// Clients of every card under `root`, from the last scan when it is
+/// recent enough
+fn shared_scan(root: &FsRoot) -> Scan {
+    let mut last = LAST_SCAN.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(scan) = last.as_ref() {
+        if scan.root == root.root() && scan.at.elapsed() < SHARED_SCAN_AGE {
+            return scan.clone();
+        }
+    }
+    let scan = Scan {
+        root: root.root().to_path_buf(),
+        at: Instant::now(),
+        clients: Arc::new(scan_all(root)),
+    };
+    *last = Some(scan.clone());
+    scan
+}
+
+/// Collect every DRM client that reports usage stats
+fn scan_all(root: &FsRoot) -> Vec<DrmClient> {
+    let mut clients = Vec::new();
+
+    let Ok(proc_entries) = fs::read_dir(root.path("/proc")) else {
+        return clients;
+    };
+
+    for entry in proc_entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        // Processes we may not inspect are silently skipped
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else { continue };
+
+        let mut seen_clients = Vec::new();
+        for fd in fds.flatten() {
+            let is_drm = fs::read_link(fd.path())
+                .map(|target| target.starts_with("/dev/dri/"))
+                .unwrap_or(false);
+            if !is_drm {
+                continue;
+            }
+
+            let fdinfo_path = entry.path().join("fdinfo").join(fd.file_name());
+            let Ok(content) = fs::read_to_string(fdinfo_path) else { continue };
+            let Some(mut client) = parse_fdinfo(&content) else { continue };
+
+            // Duplicated fds share a client; count each once
+            if seen_clients.contains(&client.client_id) {
+                continue;
+            }
+            seen_clients.push(client.client_id);
+
+            client.pid = pid;
+            clients.push(client);
+        }
+    }
+
+    clients
+}
+
+struct ClientSample {
+    at: Instant,
+    engines: HashMap<String, EngineCounter>,
+}
+
+/// Whole-device usage, summed over every client of the card
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeviceUsage {
+    /// Busiest engine class in percent; `None` until two scans were made
+    pub utilization: Option<f32>,
+    /// Memory held by all clients in bytes; `None` when no client reports
+    /// memory regions
+    pub memory_used: Option<u64>,
+    /// Part of `memory_used` in device-local regions; `None` when no client
+    /// reports one
+    pub local_memory_used: Option<u64>,
+}
+
+/// Turns successive fdinfo scans for one card into per-process usage.
+/// Engine counters are cumulative, so utilisation is the delta between two
+/// scans divided by the elapsed time.
+pub struct DrmClientTracker {
+    root: FsRoot,
+    pdev: String,
+    driver: Option<String>,
+    previous: HashMap<(u32, u64), ClientSample>,
+    /// Time of the scan behind `processes`; a shared scan seen before
+    /// returns them again instead of measuring over zero time
+    scanned_at: Option<Instant>,
+    processes: Vec<GPUProcess>,
+    usage: DeviceUsage,
+}
+
+impl DrmClientTracker {
+    pub fn new(root: FsRoot, pdev: String) -> Self {
+        Self {
+            root,
+            pdev,
+            driver: None,
+            previous: HashMap::new(),
+            scanned_at: None,
+            processes: Vec::new(),
+            usage: DeviceUsage::default(),
+        }
+    }
+
+    /// Also count clients without `drm-pdev`, which non-PCI (platform)
+    /// devices leave out, when they belong to `driver`
+    pub fn with_driver(mut self, driver: String) -> Self {
+        self.driver = Some(driver);
+        self
+    }
+
+    pub fn pdev(&self) -> &str {
+        &self.pdev
+    }
+
+    /// Scan fdinfo and return one entry per process using the card
+    pub fn processes(&mut self) -> Vec<GPUProcess> {
+        let scan = shared_scan(&self.root);
+        if self.scanned_at == Some(scan.at) {
+            return self.processes.clone();
+        }
+        let now = scan.at;
+        let clients = scan.clients.iter().filter(|client| {
+            client.pdev == self.pdev
+                || (client.pdev.is_empty() && self.driver.as_deref() == Some(client.driver.as_str()))
+        });
+
+        let mut by_pid: HashMap<u32, GPUProcess> = HashMap::new();
+        let mut current = HashMap::new();
+        let mut engine_totals: HashMap<String, f32> = HashMap::new();
+        let mut memory_used = None;
+        let mut local_memory_used = None;
+
+        for client in clients {
+            let key = (client.pid, client.client_id);
+            let utilization = self.previous.get(&key).map(|prev| {
+                let engines = Self::engine_utilization(prev, &client.engines, now);
+                for (engine, percent) in &engines {
+                    *engine_totals.entry(engine.clone()).or_default() += percent;
+                }
+                engines.into_iter().map(|(_, percent)| percent).fold(0.0, f32::max)
+            });
+            if !client.memory.is_empty() {
+                *memory_used.get_or_insert(0) += client.memory_bytes();
+            }
+            if let Some(local) = client.local_memory_bytes() {
+                *local_memory_used.get_or_insert(0) += local;
+            }
+
+            let process = by_pid.entry(client.pid).or_insert_with(|| GPUProcess {
+                pid: client.pid,
+                name: process_name(&self.root, client.pid),
+                memory_used: Some(0),
+                utilization: None,
+                encoder_utilization: None,
+                decoder_utilization: None,
+                client_type: None,
+            });
+            process.memory_used = Some(process.memory_used.unwrap_or(0) + client.memory_bytes());
+            if let Some(util) = utilization {
+                process.utilization = Some((process.utilization.unwrap_or(0.0) + util).min(100.0));
+            }
+
+            current.insert(key, ClientSample { at: now, engines: client.engines.clone() });
+        }
+
+        // Dropping samples of closed clients keeps the map bounded
+        self.previous = current;
+        self.usage = DeviceUsage {
+            // An idle card has no clients, which still means 0% after the first scan
+            utilization: self.scanned_at.is_some()
+                .then(|| engine_totals.into_values().fold(0.0, f32::max).min(100.0)),
+            memory_used,
+            local_memory_used,
+        };
+        self.scanned_at = Some(now);
+
+        let mut processes: Vec<GPUProcess> = by_pid.into_values().collect();
+        processes.sort_by(|a, b| b.memory_used.cmp(&a.memory_used).then(a.pid.cmp(&b.pid)));
+        self.processes = processes.clone();
+        processes
+    }
+
+    /// Device totals from the last call to `processes`
+    pub fn usage(&self) -> DeviceUsage {
+        self.usage
+    }
+
+    /// Utilisation of each engine class since the previous scan, in percent
+    fn engine_utilization(prev: &ClientSample, engines: &HashMap<String, EngineCounter>, now: Instant) -> Vec<(String, f32)> {
+        let elapsed_ns = now.duration_since(prev.at).as_nanos() as f64;
+
+        engines.iter()
+            .filter_map(|(name, counter)| {
+                let before = prev.engines.get(name)?;
+                let busy = counter.busy.checked_sub(before.busy)? as f64;
+                let percent = match (counter.total, before.total) {
+                    (Some(total), Some(total_before)) => {
+                        let elapsed_cycles = total.checked_sub(total_before)? as f64;
+                        if elapsed_cycles == 0.0 {
+                            return None;
+                        }
+                        busy / (elapsed_cycles * counter.capacity as f64) * 100.0
+                    }
+                    _ => {
+                        if elapsed_ns == 0.0 {
+                            return None;
+                        }
+                        busy / (elapsed_ns * counter.capacity as f64) * 100.0
+                    }
+                };
+                Some((name.clone(), percent.clamp(0.0, 100.0) as f32))
+            })
+            .collect()
+    }
+}
+
+pub fn process_name(root: &FsRoot, pid: u32) -> String {
+    fs::read_to_string(root.path(format!("/proc/{}/comm", pid)))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| format!("PID {}", pid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amdgpu_fdinfo() {
+        let content = "pos:\t0\nflags:\t02100002\nmnt_id:\t24\n\
+            drm-driver:\tamdgpu\ndrm-pdev:\t0000:03:00.0\ndrm-client-id:\t42\n\
+            drm-memory-vram:\t2048 KiB\ndrm-memory-gtt:\t512 KiB\ndrm-memory-cpu:\t0 KiB\n\
+            drm-engine-gfx:\t1500000 ns\ndrm-engine-compute:\t0 ns\n";
+
+        let client = parse_fdinfo(content).unwrap();
+        assert_eq!(client.driver, "amdgpu");
+        assert_eq!(client.pdev, "0000:03:00.0");
+        assert_eq!(client.client_id, 42);
+        assert_eq!(client.engines["gfx"].busy, 1_500_000);
+        assert_eq!(client.engines["gfx"].capacity, 1);
+        assert_eq!(client.memory_bytes(), 2048 * 1024);
+    }
+
+    #[test]
+    fn test_parse_xe_cycles() {
+        let content = "drm-driver:\txe\ndrm-pdev:\t0000:00:02.0\ndrm-client-id:\t7\n\
+            drm-total-system0:\t16 MiB\ndrm-resident-system0:\t8 MiB\n\
+            drm-cycles-rcs:\t300\ndrm-total-cycles-rcs:\t1000\n";
+
+        let client = parse_fdinfo(content).unwrap();
+        assert_eq!(client.engines["rcs"].busy, 300);
+        assert_eq!(client.engines["rcs"].total, Some(1000));
+        // Resident size is preferred over total
+        assert_eq!(client.memory_bytes(), 8 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_engine_utilization_divides_by_capacity() {
+        let at = Instant::now();
+        let counter = |busy, total| EngineCounter { busy, total, capacity: 2 };
+        let prev = ClientSample {
+            at,
+            engines: HashMap::from([
+                ("ccs".to_string(), counter(0, Some(0))),
+                ("rcs".to_string(), counter(0, None)),
+            ]),
+        };
+        // Both engines of each class busy for half of the interval
+        let now = at + Duration::from_millis(10);
+        let engines = HashMap::from([
+            ("ccs".to_string(), counter(1000, Some(1000))),
+            ("rcs".to_string(), counter(10_000_000, None)),
+        ]);
+
+        let mut percent = DrmClientTracker::engine_utilization(&prev, &engines, now);
+        percent.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(percent, vec![("ccs".to_string(), 50.0), ("rcs".to_string(), 50.0)]);
+    }
+
+    #[test]
+    fn test_parse_non_drm_fd() {
+        assert!(parse_fdinfo("pos:\t0\nflags:\t02\nmnt_id:\t1\n").is_none());
+    }
+}
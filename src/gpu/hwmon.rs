@@ -0,0 +1,47 @@
+//! Temperature sensors of a GPU's hwmon device.
+//!
+//! amdgpu registers `edge`, `junction` and `mem`; xe registers `pkg` and
+//! `vram`. Values are in millidegrees Celsius.
+
+use crate::gpu::backend::GPUSensor;
+use std::fs;
+use std::path::Path;
+
+/// Read every `temp*_input` with its label and critical limit, ordered by
+/// sensor index. Unlabelled sensors are named after their file ("temp2").
+pub fn read_temperatures(hwmon_path: &Path) -> Vec<GPUSensor> {
+    let Ok(entries) = fs::read_dir(hwmon_path) else {
+        return Vec::new();
+    };
+
+    let mut indices: Vec<u32> = entries.flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let name = name.to_str()?;
+            name.strip_prefix("temp")?.strip_suffix("_input")?.parse().ok()
+        })
+        .collect();
+    indices.sort_unstable();
+
+    indices.into_iter()
+        .filter_map(|i| {
+            let read_millidegrees = |suffix: &str| {
+                fs::read_to_string(hwmon_path.join(format!("temp{}_{}", i, suffix)))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<i64>().ok())
+                    .map(|v| v as f32 / 1000.0)
+            };
+
+            let label = fs::read_to_string(hwmon_path.join(format!("temp{}_label", i)))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| format!("temp{}", i));
+
+            Some(GPUSensor {
+                label,
+                temperature: read_millidegrees("input")?,
+                // Some firmware reports placeholder limits far outside reality
+                critical: read_millidegrees("crit").filter(|&c| c > 0.0 && c < 200.0),
+            })
+        })
+        .collect()
+}
@@ -1,60 +1,504 @@
 use crate::error::{Error, Result};
-use crate::gpu::backend::GPUBackend;
+use crate::gpu::backend::{GPUBackend, GPUSample, SampleCache};
+#[cfg(target_os = "linux")]
+use crate::gpu::backend::DeviceProperties;
+#[cfg(target_os = "linux")]
+use crate::fsroot::FsRoot;
+#[cfg(target_os = "linux")]
+use crate::gpu::fdinfo::DrmClientTracker;
+#[cfg(target_os = "linux")]
+use crate::gpu::hwmon;
+#[cfg(target_os = "linux")]
+use crate::gpu::pciids::{PciId, PciIds};
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
 
-pub struct IntelBackend;
+/// Kernel driver bound to an Intel GPU. i915 and xe expose the same data
+/// under different sysfs layouts.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IntelDriver {
+    I915,
+    Xe,
+}
+
+/// Last reading of a monotonically increasing counter (RC6 residency, energy)
+/// used to turn it into a rate between refreshes.
+#[cfg(target_os = "linux")]
+struct CounterSample {
+    value: u64,
+    at: Instant,
+}
+
+#[cfg(target_os = "linux")]
+pub struct IntelBackend {
+    card_path: PathBuf,
+    device_path: PathBuf,
+    hwmon_path: Option<PathBuf>,
+    driver: IntelDriver,
+    key: String,
+    /// PCI address; `None` when the device link could not be read, in
+    /// which case the key is the device path
+    pci_slot: Option<String>,
+    name: String,
+    memory_total: Option<u64>,
+    properties: DeviceProperties,
+    idle_sample: Mutex<Option<CounterSample>>,
+    energy_sample: Mutex<Option<CounterSample>>,
+    clients: Option<Mutex<DrmClientTracker>>,
+    samples: SampleCache,
+}
 
+#[cfg(target_os = "linux")]
 impl IntelBackend {
     pub fn detect_all() -> Result<Vec<Self>> {
-        Err(Error::Gpu("Intel GPU monitoring not yet implemented".to_string()))
+        Self::detect_all_in(&FsRoot::global())
+    }
+
+    /// Detect cards under the sysfs tree of `root`
+    pub fn detect_all_in(root: &FsRoot) -> Result<Vec<Self>> {
+        let mut gpus = Vec::new();
+
+        let cards = match fs::read_dir(root.path("/sys/class/drm")) {
+            Ok(entries) => entries,
+            Err(_) => return Err(Error::Gpu("Failed to read /sys/class/drm".to_string())),
+        };
+
+        for entry in cards.flatten() {
+            let card_path = entry.path();
+            let name = card_path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("");
+
+            // Only main cards (card0, card1, ...), not connectors or render nodes
+            if !name.starts_with("card") || name.contains('-') {
+                continue;
+            }
+
+            let device_path = card_path.join("device");
+            if !device_path.exists() {
+                continue;
+            }
+
+            match fs::read_to_string(device_path.join("vendor")) {
+                Ok(vendor) if vendor.trim() == "0x8086" => {}
+                _ => continue,
+            }
+
+            let driver = match Self::read_driver(&device_path) {
+                Some(driver) => driver,
+                None => continue,
+            };
+
+            let hwmon_path = Self::find_hwmon(&device_path);
+            let name = Self::read_gpu_name(&device_path);
+            let pci_slot = Self::read_pci_slot(&device_path);
+            let clients = pci_slot.clone()
+                .map(|slot| Mutex::new(DrmClientTracker::new(root.clone(), slot)));
+            let key = pci_slot.clone().unwrap_or_else(|| device_path.display().to_string());
+
+            let mut gpu = Self {
+                card_path,
+                device_path,
+                hwmon_path,
+                driver,
+                key,
+                pci_slot,
+                name,
+                memory_total: None,
+                properties: DeviceProperties::default(),
+                idle_sample: Mutex::new(None),
+                energy_sample: Mutex::new(None),
+                clients,
+                samples: SampleCache::new(),
+            };
+            // Local memory size never changes, so read it once
+            gpu.memory_total = gpu.local_memory_total().filter(|&total| total > 0);
+            gpu.properties = DeviceProperties {
+                max_clock: gpu.max_clock_speed(),
+                ..DeviceProperties::from_sysfs(root, &gpu.device_path)
+            };
+            gpus.push(gpu);
+        }
+
+        if gpus.is_empty() {
+            return Err(Error::Gpu("No Intel GPUs detected".to_string()));
+        }
+
+        gpus.sort_by(|a, b| a.card_path.cmp(&b.card_path));
+        Ok(gpus)
+    }
+
+    /// PCI address of the card (e.g. "0000:00:02.0"), as used by `drm-pdev`
+    fn read_pci_slot(device_path: &Path) -> Option<String> {
+        fs::read_link(device_path)
+            .ok()?
+            .file_name()?
+            .to_str()
+            .map(|s| s.to_string())
+    }
+
+    fn read_driver(device_path: &Path) -> Option<IntelDriver> {
+        let driver = fs::read_link(device_path.join("driver")).ok()?;
+        match driver.file_name()?.to_str()? {
+            "i915" => Some(IntelDriver::I915),
+            "xe" => Some(IntelDriver::Xe),
+            _ => None,
+        }
+    }
+
+    fn find_hwmon(device_path: &Path) -> Option<PathBuf> {
+        // Only discrete cards register a hwmon device
+        let entries = fs::read_dir(device_path.join("hwmon")).ok()?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Ok(name) = fs::read_to_string(path.join("name")) {
+                if matches!(name.trim(), "i915" | "xe") {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    fn read_gpu_name(device_path: &Path) -> String {
+        let ids = PciIds::global();
+        if let Some(id) = PciId::read(device_path) {
+            if let Some(name) = ids.override_name(&id) {
+                return name.to_string();
+            }
+            // "DG2 [Arc A770]" -> "Intel Arc A770"
+            if let Some(name) = ids.lookup_name(&id) {
+                return if name.starts_with("Intel") { name } else { format!("Intel {}", name) };
+            }
+        }
+
+        let device_id = fs::read_to_string(device_path.join("device"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        format!("Intel Graphics [{}]", device_id)
+    }
+
+    fn read_u64(path: &Path) -> Option<u64> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+    }
+
+    fn read_hwmon_value(&self, filename: &str) -> Option<u64> {
+        self.hwmon_path.as_ref()
+            .and_then(|hwmon| Self::read_u64(&hwmon.join(filename)))
+    }
+
+    /// Path of the first GT's frequency/idle directory for the bound driver
+    fn gt_path(&self) -> PathBuf {
+        match self.driver {
+            IntelDriver::I915 => self.card_path.join("gt/gt0"),
+            IntelDriver::Xe => self.device_path.join("tile0/gt0"),
+        }
+    }
+
+    /// Cumulative time the render GT spent in RC6 (idle), in milliseconds
+    fn idle_residency_ms(&self) -> Option<u64> {
+        match self.driver {
+            IntelDriver::I915 => Self::read_u64(&self.gt_path().join("rc6_residency_ms"))
+                .or_else(|| Self::read_u64(&self.card_path.join("power/rc6_residency_ms"))),
+            IntelDriver::Xe => Self::read_u64(&self.gt_path().join("gtidle/idle_residency_ms")),
+        }
+    }
+
+    /// Cumulative energy counter in microjoules
+    fn energy_uj(&self) -> Option<u64> {
+        self.read_hwmon_value("energy1_input")
+            .or_else(|| self.read_hwmon_value("energy2_input"))
+    }
+
+    /// Rate of change per second of a counter since the previous call
+    fn counter_rate(sample: &Mutex<Option<CounterSample>>, value: u64) -> Option<f64> {
+        let now = Instant::now();
+        let mut last = sample.lock().ok()?;
+        let rate = last.as_ref().and_then(|prev| {
+            let elapsed = now.duration_since(prev.at).as_secs_f64();
+            if elapsed > 0.0 && value >= prev.value {
+                Some((value - prev.value) as f64 / elapsed)
+            } else {
+                None
+            }
+        });
+        *last = Some(CounterSample { value, at: now });
+        rate
+    }
+
+    /// Hardware maximum (RP0) frequency of the GT, in MHz. The `max_freq`
+    /// files hold the adjustable soft limit instead.
+    fn max_clock_speed(&self) -> Option<u32> {
+        let value = match self.driver {
+            IntelDriver::I915 => Self::read_u64(&self.gt_path().join("rps_RP0_freq_mhz"))
+                .or_else(|| Self::read_u64(&self.card_path.join("gt_RP0_freq_mhz"))),
+            IntelDriver::Xe => Self::read_u64(&self.gt_path().join("freq0/rp0_freq")),
+        };
+        value.map(|mhz| mhz as u32)
+    }
+
+    fn local_memory_total(&self) -> Option<u64> {
+        match self.driver {
+            IntelDriver::I915 => Self::read_u64(&self.card_path.join("lmem_total_bytes")),
+            IntelDriver::Xe => Self::read_u64(&self.device_path.join("tile0/physical_vram_size_bytes")),
+        }
     }
 }
 
+#[cfg(target_os = "linux")]
 impl GPUBackend for IntelBackend {
+    fn collect(&self) -> GPUSample {
+        GPUSample::timed(|| {
+            // Scanned first: xe's VRAM usage is summed from the clients
+            let processes = self.read_processes();
+            GPUSample {
+                temperature: self.read_temperature(),
+                utilization: self.read_utilization(),
+                memory_used: self.read_memory_used(),
+                memory_total: self.memory_total,
+                power_draw: self.read_power_draw(),
+                power_limit: self.read_power_limit(),
+                clock_speed: self.read_clock_speed(),
+                memory_clock: self.read_memory_clock(),
+                fan_rpm: self.read_fan_rpm(),
+                processes,
+                sensors: self.hwmon_path.as_deref()
+                    .map(hwmon::read_temperatures)
+                    .unwrap_or_default(),
+                ..GPUSample::new(self.name.clone(), "Intel")
+            }
+        })
+    }
+
+    fn sample_cache(&self) -> &SampleCache {
+        &self.samples
+    }
+
+    fn device_key(&self) -> String {
+        self.key.clone()
+    }
+
+    fn pci_slot(&self) -> Option<String> {
+        self.pci_slot.clone()
+    }
+
+    fn properties(&self) -> DeviceProperties {
+        self.properties.clone()
+    }
+
+    fn is_stale(&self) -> bool {
+        // The card may have been renumbered after a driver rebind
+        Self::read_pci_slot(&self.device_path) != self.pci_slot
+            || self.hwmon_path.as_ref().is_some_and(|hwmon| !hwmon.exists())
+    }
+
     fn name(&self) -> String {
-        "Intel GPU".to_string()
+        self.name.clone()
     }
 
     fn vendor(&self) -> String {
         "Intel".to_string()
     }
+}
 
-    fn temperature(&self) -> Option<f32> {
-        None
+#[cfg(target_os = "linux")]
+impl IntelBackend {
+    fn read_temperature(&self) -> Option<f32> {
+        // i915 reports temp1 (card), xe reports temp2 (pkg) and temp3 (vram)
+        (1..=3)
+            .find_map(|i| self.read_hwmon_value(&format!("temp{}_input", i)))
+            .map(|t| t as f32 / 1000.0)
     }
 
-    fn utilization(&self) -> Option<f32> {
-        None
+    fn read_utilization(&self) -> Option<f32> {
+        // Busy time is whatever the GT did not spend in RC6
+        let idle_ms = self.idle_residency_ms()?;
+        let idle_ms_per_sec = Self::counter_rate(&self.idle_sample, idle_ms)?;
+        Some((100.0 - idle_ms_per_sec / 10.0).clamp(0.0, 100.0) as f32)
     }
 
-    fn memory_used(&self) -> Option<u64> {
-        None
+    fn read_memory_used(&self) -> Option<u64> {
+        match self.driver {
+            IntelDriver::I915 => {
+                let total = self.memory_total?;
+                let avail = Self::read_u64(&self.card_path.join("lmem_avail_bytes"))?;
+                Some(total.saturating_sub(avail))
+            }
+            // xe has no used-VRAM attribute; sum what the clients hold in
+            // VRAM, which leaves out the kernel's own allocations
+            IntelDriver::Xe => {
+                self.memory_total?;
+                let tracker = self.clients.as_ref()?.lock().ok()?;
+                Some(tracker.usage().local_memory_used.unwrap_or(0))
+            }
+        }
     }
 
-    fn memory_total(&self) -> Option<u64> {
-        None
+    fn read_power_draw(&self) -> Option<f32> {
+        let energy = self.energy_uj()?;
+        let microwatts = Self::counter_rate(&self.energy_sample, energy)?;
+        Some((microwatts / 1_000_000.0) as f32)
     }
 
-    fn power_draw(&self) -> Option<f32> {
-        None
+    fn read_power_limit(&self) -> Option<f32> {
+        self.read_hwmon_value("power1_max")
+            .or_else(|| self.read_hwmon_value("power2_max"))
+            .filter(|&p| p > 0)
+            .map(|p| p as f32 / 1_000_000.0) // µW to W
     }
 
-    fn power_limit(&self) -> Option<f32> {
-        None
+    fn read_clock_speed(&self) -> Option<u32> {
+        let value = match self.driver {
+            IntelDriver::I915 => Self::read_u64(&self.gt_path().join("rps_act_freq_mhz"))
+                .or_else(|| Self::read_u64(&self.card_path.join("gt_act_freq_mhz"))),
+            IntelDriver::Xe => Self::read_u64(&self.gt_path().join("freq0/act_freq")),
+        };
+        value.map(|mhz| mhz as u32)
     }
 
-    fn clock_speed(&self) -> Option<u32> {
+    fn read_memory_clock(&self) -> Option<u32> {
         None
     }
 
-    fn memory_clock(&self) -> Option<u32> {
-        None
+    fn read_fan_rpm(&self) -> Option<u32> {
+        self.read_hwmon_value("fan1_input")
+            .map(|rpm| rpm as u32)
     }
 
-    fn fan_speed(&self) -> Option<u32> {
-        None
+    fn read_processes(&self) -> Vec<crate::gpu::backend::GPUProcess> {
+        self.clients.as_ref()
+            .and_then(|clients| clients.lock().ok().map(|mut tracker| tracker.processes()))
+            .unwrap_or_default()
     }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct IntelBackend;
+
+#[cfg(not(target_os = "linux"))]
+impl IntelBackend {
+    pub fn detect_all() -> Result<Vec<Self>> {
+        Self::detect_all_in(&crate::fsroot::FsRoot::global())
+    }
+
+    pub fn detect_all_in(_root: &crate::fsroot::FsRoot) -> Result<Vec<Self>> {
+        Err(Error::Gpu("Intel GPU monitoring not supported on this platform".to_string()))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl GPUBackend for IntelBackend {
+    fn collect(&self) -> GPUSample {
+        GPUSample::new("Unsupported".to_string(), "Intel")
+    }
+
+    fn sample_cache(&self) -> &SampleCache {
+        static SAMPLES: SampleCache = SampleCache::new();
+        &SAMPLES
+    }
+
+    fn device_key(&self) -> String {
+        "unsupported".to_string()
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    fn add_card(root: &FsRoot, slot: &str, driver: &str) -> String {
+        let device = format!("/sys/devices/pci0000:00/{}", slot);
+        root.write_file(format!("{}/vendor", device), "0x8086\n").unwrap();
+        root.write_file(format!("{}/device", device), "0x56a0\n").unwrap();
+        root.symlink(format!("{}/driver", device), format!("../../../bus/pci/drivers/{}", driver)).unwrap();
+        root.symlink("/sys/class/drm/card0/device", format!("../../../devices/pci0000:00/{}", slot)).unwrap();
+        device
+    }
+
+    #[test]
+    fn test_i915_discrete_card() {
+        let root = TestRoot::new("intel-i915");
+        let device = add_card(&root, "0000:03:00.0", "i915");
+        root.write_file(format!("{}/hwmon/hwmon2/name", device), "i915\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon2/temp1_input", device), "45000\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon2/power1_max", device), "190000000\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon2/fan1_input", device), "1200\n").unwrap();
+        root.write_file("/sys/class/drm/card0/lmem_total_bytes", "17179869184\n").unwrap();
+        root.write_file("/sys/class/drm/card0/lmem_avail_bytes", "12884901888\n").unwrap();
+        root.write_file("/sys/class/drm/card0/gt/gt0/rps_act_freq_mhz", "2100\n").unwrap();
+        // The soft limit was lowered; the hardware maximum is RP0
+        root.write_file("/sys/class/drm/card0/gt/gt0/rps_max_freq_mhz", "2000\n").unwrap();
+        root.write_file("/sys/class/drm/card0/gt/gt0/rps_RP0_freq_mhz", "2400\n").unwrap();
+        root.write_file("/proc/sys/kernel/osrelease", "6.8.0\n").unwrap();
+
+        let gpus = IntelBackend::detect_all_in(&root).unwrap();
+        assert_eq!(gpus.len(), 1);
+        let sample = gpus[0].sample();
+        assert_eq!(gpus[0].device_key(), "0000:03:00.0");
+        assert_eq!(sample.temperature, Some(45.0));
+        assert_eq!(sample.memory_total, Some(16 << 30));
+        assert_eq!(sample.memory_used, Some(4 << 30));
+        assert_eq!(sample.power_limit, Some(190.0));
+        assert_eq!(sample.clock_speed, Some(2100));
+        assert_eq!(sample.fan_rpm, Some(1200));
+        assert_eq!(gpus[0].properties().max_clock, Some(2400));
+        assert_eq!(gpus[0].properties().driver_version.as_deref(), Some("kernel 6.8.0"));
+    }
+
+    #[test]
+    fn test_xe_discrete_card() {
+        let root = TestRoot::new("intel-xe");
+        let device = add_card(&root, "0000:03:00.0", "xe");
+        root.write_file(format!("{}/hwmon/hwmon4/name", device), "xe\n").unwrap();
+        root.write_file(format!("{}/hwmon/hwmon4/temp2_input", device), "52000\n").unwrap();
+        root.write_file(format!("{}/tile0/physical_vram_size_bytes", device), "12884901888\n").unwrap();
+        root.write_file(format!("{}/tile0/gt0/freq0/act_freq", device), "1850\n").unwrap();
+        root.write_file(format!("{}/tile0/gt0/freq0/max_freq", device), "2000\n").unwrap();
+        root.write_file(format!("{}/tile0/gt0/freq0/rp0_freq", device), "2850\n").unwrap();
+
+        // Two clients of one process, one of them only in system memory
+        std::fs::create_dir_all(root.path("/proc/500/fd")).unwrap();
+        std::os::unix::fs::symlink("/dev/dri/renderD128", root.path("/proc/500/fd/5")).unwrap();
+        std::os::unix::fs::symlink("/dev/dri/renderD128", root.path("/proc/500/fd/6")).unwrap();
+        root.write_file("/proc/500/comm", "game\n").unwrap();
+        root.write_file("/proc/500/fdinfo/5", "drm-driver:\txe\ndrm-pdev:\t0000:03:00.0\ndrm-client-id:\t1\n\
+            drm-resident-vram0:\t1024 MiB\ndrm-resident-system0:\t64 MiB\n").unwrap();
+        root.write_file("/proc/500/fdinfo/6", "drm-driver:\txe\ndrm-pdev:\t0000:03:00.0\ndrm-client-id:\t2\n\
+            drm-resident-system0:\t16 MiB\n").unwrap();
+
+        let gpus = IntelBackend::detect_all_in(&root).unwrap();
+        let sample = gpus[0].sample();
+        assert_eq!(sample.temperature, Some(52.0));
+        assert_eq!(sample.memory_total, Some(12 << 30));
+        assert_eq!(sample.memory_used, Some(1 << 30));
+        assert_eq!(sample.clock_speed, Some(1850));
+        assert_eq!(sample.processes.len(), 1);
+        assert_eq!(sample.processes[0].name, "game");
+        assert_eq!(gpus[0].properties().max_clock, Some(2850));
+    }
+
+    #[test]
+    fn test_card_without_pci_address() {
+        // The device is a plain directory, so there is no link to take the
+        // PCI address from
+        let root = TestRoot::new("intel-noslot");
+        let device = "/sys/class/drm/card0/device";
+        root.write_file(format!("{}/vendor", device), "0x8086\n").unwrap();
+        root.symlink(format!("{}/driver", device), "../../../../bus/pci/drivers/i915").unwrap();
 
-    fn processes(&self) -> Vec<crate::gpu::backend::GPUProcess> {
-        Vec::new()
+        let gpus = IntelBackend::detect_all_in(&root).unwrap();
+        assert_eq!(gpus[0].device_key(), root.path(device).display().to_string());
+        assert_eq!(gpus[0].pci_slot(), None);
+        assert!(!gpus[0].is_stale());
     }
 }
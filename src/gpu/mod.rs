@@ -1,4 +1,12 @@
 pub mod backend;
+pub mod control;
+pub mod pciids;
+
+#[cfg(target_os = "linux")]
+pub mod fdinfo;
+
+#[cfg(target_os = "linux")]
+pub mod hwmon;
 
 #[cfg(feature = "nvidia")]
 pub mod nvidia;
@@ -9,7 +17,15 @@ pub mod amd;
 #[cfg(feature = "intel")]
 pub mod intel;
 
+#[cfg(all(target_os = "linux", feature = "drm"))]
+pub mod drm;
+
 #[cfg(all(target_os = "macos", feature = "apple-gpu"))]
 pub mod apple;
 
-pub use backend::{GPUBackend, GPUManager, GPUInfo};
+pub use backend::{
+    DeviceProperties, GPUBackend, GPUEvent, GPUManager, GPUInfo, GPUSample, GPUProcess, SampleCache, GPUClientType,
+    GPUSensor, PcieLink, PcieThroughput, ThrottleReason,
+};
+pub use control::{ControlCapabilities, ControlRequest, FanCurve, PowerCapRange};
+pub use pciids::{PciId, PciIds};
@@ -1,19 +1,36 @@
 use crate::error::{Error, Result};
-use crate::gpu::backend::GPUBackend;
+use crate::gpu::control::{ControlCapabilities, ControlRequest, PowerCapRange};
+use crate::gpu::pciids::{PciId, PciIds};
+use crate::gpu::backend::{
+    DeviceProperties, GPUBackend, GPUSample, GPUSensor, SampleCache, PcieLink, PcieThroughput, ThrottleReason,
+};
 use nvml_wrapper::Nvml;
 use nvml_wrapper::Device;
-use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+use nvml_wrapper::bitmasks::device::ThrottleReasons;
+use nvml_wrapper::enum_wrappers::device::{Clock, PcieUtilCounter, TemperatureSensor, TemperatureThreshold};
+use nvml_wrapper::enums::device::UsedGpuMemory;
+use nvml_wrapper::error::NvmlError;
+use nvml_wrapper::struct_wrappers::device::ProcessUtilizationSample;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
 pub struct NvidiaBackend {
     device: Device<'static>,
+    key: String,
+    name: String,
+    memory_total: Option<u64>,
+    temp_slowdown: Option<f32>,
+    properties: DeviceProperties,
     nvml: &'static Nvml,
+    last_utilization_timestamp: Mutex<u64>, // µs, CPU clock
+    /// Power limit (mW) before the first change, put back on restore
+    original_power_limit: Mutex<Option<u32>>,
+    samples: SampleCache,
 }
 
 impl NvidiaBackend {
     pub fn detect_all() -> Result<Vec<Self>> {
-        let nvml = Box::leak(Box::new(
-            Nvml::init().map_err(|e| Error::Gpu(format!("Failed to initialize NVML: {}", e)))?
-        ));
+        let nvml = Self::nvml()?;
 
         let device_count = nvml.device_count()
             .map_err(|e| Error::Gpu(format!("Failed to get device count: {}", e)))?;
@@ -22,7 +39,30 @@ impl NvidiaBackend {
         for i in 0..device_count {
             match nvml.device_by_index(i) {
                 Ok(device) => {
-                    gpus.push(Self { device, nvml });
+                    let raw_name = device.name()
+                        .unwrap_or_else(|_| "Unknown NVIDIA GPU".to_string());
+                    let name = Self::resolve_name(&device, raw_name);
+                    let memory_total = device.memory_info().ok().map(|info| info.total);
+                    let key = device.uuid()
+                        .or_else(|_| device.pci_info().map(|pci| pci.bus_id))
+                        .unwrap_or_else(|_| format!("nvidia-{}", i));
+                    let temp_slowdown = device.temperature_threshold(TemperatureThreshold::Slowdown)
+                        .ok()
+                        .map(|t| t as f32);
+                    let properties = Self::read_properties(nvml, &device);
+
+                    gpus.push(Self {
+                        device,
+                        key,
+                        name,
+                        memory_total,
+                        temp_slowdown,
+                        properties,
+                        nvml,
+                        last_utilization_timestamp: Mutex::new(0),
+                        original_power_limit: Mutex::new(None),
+                        samples: SampleCache::new(),
+                    });
                 }
                 Err(e) => {
                     log::warn!("Failed to get NVIDIA device {}: {}", i, e);
@@ -36,272 +76,286 @@ impl NvidiaBackend {
 
         Ok(gpus)
     }
-    
-    fn get_known_gpu_name(name: &str) -> Option<String> {
-        // NVIDIA GPUs - match on device name patterns with specific brand models
-        
-        // RTX 40 Series
-        if name.contains("4090") {
-            if name.contains("ASUS") {
-                if name.contains("ROG") || name.contains("Strix") {
-                    return Some("ASUS ROG Strix RTX 4090 OC".to_string());
-                } else if name.contains("TUF") {
-                    return Some("ASUS TUF Gaming RTX 4090 OC".to_string());
-                }
-            } else if name.contains("MSI") {
-                if name.contains("SUPRIM") {
-                    return Some("MSI GeForce RTX 4090 Suprim X".to_string());
-                } else if name.contains("Gaming X") {
-                    return Some("MSI GeForce RTX 4090 Gaming X Trio".to_string());
-                }
-            } else if name.contains("Gigabyte") {
-                if name.contains("AORUS") {
-                    return Some("Gigabyte AORUS RTX 4090 Master".to_string());
-                } else if name.contains("Gaming") {
-                    return Some("Gigabyte GeForce RTX 4090 Gaming OC".to_string());
-                }
-            } else if name.contains("EVGA") {
-                return Some("EVGA GeForce RTX 4090 FTW3 Ultra".to_string());
-            } else if name.contains("Zotac") {
-                return Some("Zotac GeForce RTX 4090 AMP Extreme".to_string());
-            } else if name.contains("PNY") {
-                return Some("PNY GeForce RTX 4090 XLR8 Uprising".to_string());
-            }
-        } else if name.contains("4080") {
-            if name.contains("ASUS") {
-                if name.contains("TUF") {
-                    return Some("ASUS TUF Gaming RTX 4080 OC".to_string());
-                } else if name.contains("ROG") {
-                    return Some("ASUS ROG Strix RTX 4080 OC".to_string());
-                }
-            } else if name.contains("MSI") {
-                if name.contains("SUPRIM") {
-                    return Some("MSI GeForce RTX 4080 Suprim X".to_string());
-                } else {
-                    return Some("MSI GeForce RTX 4080 Gaming X Trio".to_string());
-                }
-            } else if name.contains("Gigabyte") {
-                if name.contains("AORUS") {
-                    return Some("Gigabyte AORUS RTX 4080 Master".to_string());
-                } else {
-                    return Some("Gigabyte GeForce RTX 4080 Gaming OC".to_string());
-                }
-            }
-        } else if name.contains("4070 Ti") {
-            if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 4070 Ti Gaming OC".to_string());
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 4070 Ti Gaming X Trio".to_string());
-            } else if name.contains("ASUS") {
-                return Some("ASUS TUF Gaming RTX 4070 Ti OC".to_string());
-            }
-        } else if name.contains("4070") {
-            if name.contains("ASUS") {
-                if name.contains("Dual") {
-                    return Some("ASUS Dual RTX 4070 OC".to_string());
-                } else {
-                    return Some("ASUS TUF Gaming RTX 4070".to_string());
-                }
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 4070 Gaming X Trio".to_string());
-            } else if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 4070 Windforce OC".to_string());
-            }
+
+    fn read_properties(nvml: &Nvml, device: &Device) -> DeviceProperties {
+        DeviceProperties {
+            driver: Some("nvidia".to_string()),
+            driver_version: nvml.sys_driver_version().ok(),
+            vbios_version: device.vbios_version().ok(),
+            compute_capability: device.cuda_compute_capability().ok()
+                .map(|cc| format!("{}.{}", cc.major, cc.minor)),
+            pci_bus_id: device.pci_info().ok().map(|pci| sysfs_bus_id(&pci.bus_id)),
+            uuid: device.uuid().ok(),
+            max_clock: device.max_clock_info(Clock::Graphics).ok(),
+            max_memory_clock: device.max_clock_info(Clock::Memory).ok(),
         }
-        
-        // RTX 30 Series
-        if name.contains("3090 Ti") {
-            if name.contains("ASUS") {
-                return Some("ASUS ROG Strix RTX 3090 Ti OC".to_string());
-            } else if name.contains("MSI") {
-                if name.contains("SUPRIM") {
-                    return Some("MSI GeForce RTX 3090 Ti Suprim X".to_string());
-                } else {
-                    return Some("MSI GeForce RTX 3090 Ti Gaming X Trio".to_string());
-                }
-            } else if name.contains("Gigabyte") {
-                return Some("Gigabyte AORUS RTX 3090 Ti Xtreme".to_string());
-            }
-        } else if name.contains("3090") {
-            if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3090 Gaming OC".to_string());
-            } else if name.contains("ASUS") {
-                return Some("ASUS ROG Strix RTX 3090 OC".to_string());
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3090 Gaming X Trio".to_string());
-            } else if name.contains("EVGA") {
-                return Some("EVGA GeForce RTX 3090 FTW3 Ultra".to_string());
-            }
-        } else if name.contains("3080 Ti") {
-            if name.contains("ASUS") {
-                if name.contains("TUF") {
-                    return Some("ASUS TUF Gaming RTX 3080 Ti OC".to_string());
-                } else {
-                    return Some("ASUS ROG Strix RTX 3080 Ti OC".to_string());
-                }
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3080 Ti Gaming X Trio".to_string());
-            } else if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3080 Ti Vision OC".to_string());
-            }
-        } else if name.contains("3080") {
-            if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3080 Gaming X Trio".to_string());
-            } else if name.contains("ASUS") {
-                if name.contains("TUF") {
-                    return Some("ASUS TUF Gaming RTX 3080 OC".to_string());
-                } else {
-                    return Some("ASUS ROG Strix RTX 3080 OC".to_string());
-                }
-            } else if name.contains("EVGA") {
-                return Some("EVGA GeForce RTX 3080 FTW3 Ultra".to_string());
-            } else if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3080 Gaming OC".to_string());
-            }
-        } else if name.contains("3070 Ti") {
-            if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3070 Ti Eagle OC".to_string());
-            } else if name.contains("ASUS") {
-                return Some("ASUS TUF Gaming RTX 3070 Ti OC".to_string());
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3070 Ti Gaming X Trio".to_string());
-            }
-        } else if name.contains("3070") {
-            if name.contains("ASUS") {
-                if name.contains("Dual") {
-                    return Some("ASUS Dual RTX 3070 OC".to_string());
-                } else {
-                    return Some("ASUS TUF Gaming RTX 3070 OC".to_string());
-                }
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3070 Gaming X Trio".to_string());
-            } else if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3070 Vision OC".to_string());
-            }
-        } else if name.contains("3060 Ti") {
-            if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3060 Ti Gaming X".to_string());
-            } else if name.contains("ASUS") {
-                return Some("ASUS Dual RTX 3060 Ti OC".to_string());
-            } else if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3060 Ti Gaming OC Pro".to_string());
-            }
-        } else if name.contains("3060") {
-            if name.contains("Gigabyte") {
-                return Some("Gigabyte GeForce RTX 3060 Gaming OC".to_string());
-            } else if name.contains("ASUS") {
-                return Some("ASUS TUF Gaming RTX 3060 OC".to_string());
-            } else if name.contains("MSI") {
-                return Some("MSI GeForce RTX 3060 Ventus 2X OC".to_string());
-            }
+    }
+
+    /// NVML is initialised once and shared by every device handle, so
+    /// re-detection does not create a new library instance each time.
+    fn nvml() -> Result<&'static Nvml> {
+        static NVML: OnceLock<Nvml> = OnceLock::new();
+
+        if let Some(nvml) = NVML.get() {
+            return Ok(nvml);
         }
-        
-        None
+        let nvml = Nvml::init()
+            .map_err(|e| Error::Gpu(format!("Failed to initialize NVML: {}", e)))?;
+        Ok(NVML.get_or_init(|| nvml))
+    }
+
+    /// Configured or pci.ids board name, falling back to NVML's chip name
+    fn resolve_name(device: &Device, raw_name: String) -> String {
+        let Ok(pci) = device.pci_info() else {
+            return raw_name;
+        };
+        // Both IDs pack the device/subdevice in the high 16 bits
+        let subsystem = pci.pci_sub_system_id.unwrap_or(0);
+        let id = PciId {
+            vendor: pci.pci_device_id as u16,
+            device: (pci.pci_device_id >> 16) as u16,
+            subvendor: subsystem as u16,
+            subdevice: (subsystem >> 16) as u16,
+        };
+        let ids = PciIds::global();
+        ids.override_name(&id)
+            .map(|name| name.to_string())
+            .or_else(|| ids.subsystem_name(&id).map(|name| name.to_string()))
+            .unwrap_or(raw_name)
+    }
+
+    /// Setters need a mutable handle; open a second one for the same device
+    fn control_handle(&self) -> Result<Device<'static>> {
+        self.nvml.device_by_uuid(self.key.as_str())
+            .or_else(|_| self.nvml.device_by_pci_bus_id(self.key.as_str()))
+            .map_err(|e| Error::Gpu(format!("Failed to open {}: {}", self.name, e)))
+    }
+
+    fn power_cap_range(&self) -> Option<PowerCapRange> {
+        let constraints = self.device.power_management_limit_constraints().ok()?;
+        Some(PowerCapRange {
+            min: constraints.min_limit as f32 / 1000.0,
+            max: constraints.max_limit as f32 / 1000.0,
+            default: self.device.power_management_limit_default().ok().map(|p| p as f32 / 1000.0),
+            current: self.device.power_management_limit().ok().map(|p| p as f32 / 1000.0),
+        })
+    }
+
+    fn set_power_limit(&self, milliwatts: u32) -> Result<()> {
+        self.control_handle()?
+            .set_power_management_limit(milliwatts)
+            .map_err(|e| Error::Gpu(format!("Failed to set power limit: {}", e)))
     }
 }
 
 impl GPUBackend for NvidiaBackend {
-    fn name(&self) -> String {
-        let raw_name = self.device.name()
-            .unwrap_or_else(|_| "Unknown NVIDIA GPU".to_string());
-        
-        // Try to get improved name from known GPU database
-        Self::get_known_gpu_name(&raw_name)
-            .unwrap_or(raw_name)
+    fn collect(&self) -> GPUSample {
+        use nvml_wrapper::enum_wrappers::device::Clock;
+
+        GPUSample::timed(|| {
+            // One query covers both used and total memory
+            let memory = self.device.memory_info().ok();
+            let temperature = self.device.temperature(TemperatureSensor::Gpu).ok().map(|t| t as f32);
+
+            GPUSample {
+                temperature,
+                utilization: self.device.utilization_rates().ok().map(|u| u.gpu as f32),
+                memory_used: memory.as_ref().map(|info| info.used),
+                memory_total: memory.map(|info| info.total).or(self.memory_total),
+                power_draw: self.device.power_usage().ok().map(|p| p as f32 / 1000.0),
+                power_limit: self.device.power_management_limit().ok().map(|p| p as f32 / 1000.0),
+                clock_speed: self.device.clock_info(Clock::Graphics).ok(),
+                memory_clock: self.device.clock_info(Clock::Memory).ok(),
+                fan_speed: self.device.fan_speed(0).ok(),
+                processes: self.read_processes(),
+                encoder_utilization: self.device.encoder_utilization().ok().map(|u| u.utilization as f32),
+                decoder_utilization: self.device.decoder_utilization().ok().map(|u| u.utilization as f32),
+                pcie_link: self.read_pcie_link(),
+                pcie_throughput: self.read_pcie_throughput(),
+                throttle_reasons: self.device.current_throttle_reasons().ok().map(Self::map_throttle_reasons),
+                // NVML only exposes the core sensor; its limit is the slowdown threshold
+                sensors: temperature.into_iter()
+                    .map(|temperature| GPUSensor {
+                        label: "gpu".to_string(),
+                        temperature,
+                        critical: self.temp_slowdown,
+                    })
+                    .collect(),
+                ..GPUSample::new(self.name.clone(), "NVIDIA")
+            }
+        })
     }
 
-    fn vendor(&self) -> String {
-        "NVIDIA".to_string()
+    fn sample_cache(&self) -> &SampleCache {
+        &self.samples
     }
 
-    fn temperature(&self) -> Option<f32> {
-        self.device.temperature(TemperatureSensor::Gpu)
-            .ok()
-            .map(|t| t as f32)
+    fn device_key(&self) -> String {
+        self.key.clone()
     }
 
-    fn utilization(&self) -> Option<f32> {
-        self.device.utilization_rates()
-            .ok()
-            .map(|u| u.gpu as f32)
+    fn pci_slot(&self) -> Option<String> {
+        self.properties.pci_bus_id.clone()
     }
 
-    fn memory_used(&self) -> Option<u64> {
-        self.device.memory_info()
-            .ok()
-            .map(|info| info.used)
+    fn properties(&self) -> DeviceProperties {
+        self.properties.clone()
     }
 
-    fn memory_total(&self) -> Option<u64> {
-        self.device.memory_info()
-            .ok()
-            .map(|info| info.total)
+    fn name(&self) -> String {
+        self.name.clone()
     }
 
-    fn power_draw(&self) -> Option<f32> {
-        self.device.power_usage()
-            .ok()
-            .map(|p| p as f32 / 1000.0)
+    fn vendor(&self) -> String {
+        "NVIDIA".to_string()
     }
 
-    fn power_limit(&self) -> Option<f32> {
-        self.device.power_management_limit()
-            .ok()
-            .map(|p| p as f32 / 1000.0)
+    fn control_capabilities(&self) -> ControlCapabilities {
+        ControlCapabilities {
+            power_cap: self.power_cap_range(),
+            ..ControlCapabilities::default()
+        }
     }
 
-    fn clock_speed(&self) -> Option<u32> {
-        self.device.clock_info(nvml_wrapper::enum_wrappers::device::Clock::Graphics)
-            .ok()
+    fn apply_control(&self, request: &ControlRequest, dry_run: bool) -> Result<Vec<String>> {
+        let ControlRequest::PowerCap(watts) = request else {
+            return Err(Error::Gpu(format!("{} is not supported on NVIDIA", request.describe())));
+        };
+        let range = self.power_cap_range()
+            .ok_or_else(|| Error::Gpu("Power limit is not adjustable".to_string()))?;
+        range.validate(*watts)?;
+
+        let milliwatts = (*watts * 1000.0).round() as u32;
+        if !dry_run {
+            let original = self.device.power_management_limit()
+                .map_err(|e| Error::Gpu(format!("Failed to read power limit: {}", e)))?;
+            self.set_power_limit(milliwatts)?;
+            if let Ok(mut saved) = self.original_power_limit.lock() {
+                saved.get_or_insert(original);
+            }
+        }
+        Ok(vec![format!("power limit = {} mW", milliwatts)])
     }
 
-    fn memory_clock(&self) -> Option<u32> {
-        self.device.clock_info(nvml_wrapper::enum_wrappers::device::Clock::Memory)
-            .ok()
+    fn restore_controls(&self) -> Result<usize> {
+        let original = self.original_power_limit.lock().ok().and_then(|mut saved| saved.take());
+        match original {
+            Some(milliwatts) => self.set_power_limit(milliwatts).map(|_| 1),
+            None => Ok(0),
+        }
     }
+}
 
-    fn fan_speed(&self) -> Option<u32> {
-        self.device.fan_speed(0)
-            .ok()
+impl NvidiaBackend {
+    fn read_pcie_link(&self) -> Option<PcieLink> {
+        let link = PcieLink {
+            current_gen: self.device.current_pcie_link_gen().ok(),
+            current_width: self.device.current_pcie_link_width().ok(),
+            max_gen: self.device.max_pcie_link_gen().ok(),
+            max_width: self.device.max_pcie_link_width().ok(),
+        };
+        (link != PcieLink::default()).then_some(link)
     }
 
-    fn processes(&self) -> Vec<crate::gpu::backend::GPUProcess> {
-        use crate::gpu::backend::GPUProcess;
+    /// Each counter is sampled by NVML over a 20ms window
+    fn read_pcie_throughput(&self) -> Option<PcieThroughput> {
+        Some(PcieThroughput {
+            tx_kbps: self.device.pcie_throughput(PcieUtilCounter::Send).ok()?,
+            rx_kbps: self.device.pcie_throughput(PcieUtilCounter::Receive).ok()?,
+        })
+    }
+
+    fn map_throttle_reasons(reasons: ThrottleReasons) -> Vec<ThrottleReason> {
+        let groups = [
+            (ThrottleReasons::SW_THERMAL_SLOWDOWN | ThrottleReasons::HW_THERMAL_SLOWDOWN, ThrottleReason::Thermal),
+            (ThrottleReasons::SW_POWER_CAP | ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN, ThrottleReason::PowerCap),
+            (ThrottleReasons::HW_SLOWDOWN, ThrottleReason::HwSlowdown),
+            (ThrottleReasons::SYNC_BOOST
+                | ThrottleReasons::APPLICATIONS_CLOCKS_SETTING
+                | ThrottleReasons::DISPLAY_CLOCK_SETTING, ThrottleReason::Other),
+        ];
+
+        // GPU_IDLE only means there is no work, not that clocks are held back
+        groups.into_iter()
+            .filter(|(mask, _)| reasons.intersects(*mask))
+            .map(|(_, reason)| reason)
+            .collect()
+    }
+
+    fn read_processes(&self) -> Vec<crate::gpu::backend::GPUProcess> {
+        use crate::gpu::backend::{GPUClientType, GPUProcess};
+        use std::collections::BTreeMap;
+        
+        // A PID can be both a compute and a graphics client; merge by PID
+        let mut by_pid: BTreeMap<u32, GPUProcess> = BTreeMap::new();
         
-        let mut gpu_processes = Vec::new();
+        let clients = [
+            (self.device.running_compute_processes(), GPUClientType::Compute),
+            (self.device.running_graphics_processes(), GPUClientType::Graphics),
+        ];
         
-        // Compute processes
-        if let Ok(processes) = self.device.running_compute_processes() {
+        for (result, client_type) in clients {
+            let Ok(processes) = result else { continue };
             for proc in processes {
-                let name = Self::get_process_name(proc.pid);
-                // Try to extract memory, default to 0 if unavailable
-                let mem = 0u64; // Simplified - NVML process info varies by version
-                gpu_processes.push(GPUProcess {
+                let mem = match proc.used_gpu_memory {
+                    UsedGpuMemory::Used(bytes) => Some(bytes),
+                    UsedGpuMemory::Unavailable => None,
+                };
+                
+                let entry = by_pid.entry(proc.pid).or_insert_with(|| GPUProcess {
                     pid: proc.pid,
-                    name,
-                    memory_used: mem,
+                    name: Self::get_process_name(proc.pid),
+                    memory_used: None,
+                    utilization: None,
+                    encoder_utilization: None,
+                    decoder_utilization: None,
+                    client_type: Some(client_type),
                 });
+                
+                entry.client_type = entry.client_type.map(|t| t.merge(client_type));
+                // Both lists report the same allocation, so keep the larger value
+                entry.memory_used = match (entry.memory_used, mem) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    (a, b) => a.or(b),
+                };
             }
         }
         
-        // Graphics processes
-        if let Ok(processes) = self.device.running_graphics_processes() {
-            for proc in processes {
-                let name = Self::get_process_name(proc.pid);
-                let mem = 0u64;
-                gpu_processes.push(GPUProcess {
-                    pid: proc.pid,
-                    name,
-                    memory_used: mem,
-                });
+        if let Some(samples) = self.process_utilization() {
+            for process in by_pid.values_mut() {
+                apply_utilization(process, samples.get(&process.pid));
             }
         }
         
-        gpu_processes
+        by_pid.into_values().collect()
     }
-}
 
-impl NvidiaBackend {
+    /// Latest utilisation sample per PID since the previous call. Returns
+    /// `None` when the device does not support per-process accounting.
+    fn process_utilization(&self) -> Option<HashMap<u32, ProcessUtilizationSample>> {
+        let mut last_seen = self.last_utilization_timestamp.lock().ok()?;
+        
+        let samples = match self.device.process_utilization_stats(*last_seen) {
+            Ok(samples) => samples,
+            // No samples in the window since the last call
+            Err(NvmlError::NotFound) => Vec::new(),
+            Err(_) => return None,
+        };
+        
+        let mut latest: HashMap<u32, ProcessUtilizationSample> = HashMap::new();
+        for sample in samples {
+            *last_seen = (*last_seen).max(sample.timestamp);
+            match latest.get(&sample.pid) {
+                Some(existing) if existing.timestamp >= sample.timestamp => {}
+                _ => {
+                    latest.insert(sample.pid, sample);
+                }
+            }
+        }
+        
+        Some(latest)
+    }
+    
     fn get_process_name(pid: u32) -> String {
         #[cfg(unix)]
         {
@@ -314,3 +368,68 @@ impl NvidiaBackend {
         format!("PID {}", pid)
     }
 }
+
+/// Utilisation from the process's latest sample. A process without one
+/// in the window stays `None`: NVML had nothing to report, which is not
+/// the same as idle.
+fn apply_utilization(process: &mut crate::gpu::backend::GPUProcess, sample: Option<&ProcessUtilizationSample>) {
+    process.utilization = sample.map(|s| s.sm_util as f32);
+    process.encoder_utilization = sample.map(|s| s.enc_util as f32);
+    process.decoder_utilization = sample.map(|s| s.dec_util as f32);
+}
+
+/// NVML pads the PCI domain to eight digits ("00000000:01:00.0"); sysfs
+/// and DRM use four ("0000:01:00.0")
+fn sysfs_bus_id(bus_id: &str) -> String {
+    let bus_id = bus_id.to_lowercase();
+    match bus_id.len().checked_sub("0000:01:00.0".len()) {
+        Some(start) => bus_id[start..].to_string(),
+        None => bus_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::backend::GPUProcess;
+
+    fn process(pid: u32) -> GPUProcess {
+        GPUProcess {
+            pid,
+            name: "test".to_string(),
+            memory_used: None,
+            utilization: None,
+            encoder_utilization: None,
+            decoder_utilization: None,
+            client_type: None,
+        }
+    }
+
+    #[test]
+    fn test_process_utilization_unknown_without_sample() {
+        let sample = ProcessUtilizationSample {
+            pid: 10,
+            timestamp: 1,
+            sm_util: 35,
+            mem_util: 10,
+            enc_util: 5,
+            dec_util: 0,
+        };
+        let mut sampled = process(10);
+        apply_utilization(&mut sampled, Some(&sample));
+        assert_eq!(sampled.utilization, Some(35.0));
+        assert_eq!(sampled.encoder_utilization, Some(5.0));
+        assert_eq!(sampled.decoder_utilization, Some(0.0));
+
+        let mut unsampled = process(11);
+        apply_utilization(&mut unsampled, None);
+        assert_eq!(unsampled.utilization, None);
+        assert_eq!(unsampled.encoder_utilization, None);
+    }
+
+    #[test]
+    fn test_sysfs_bus_id() {
+        assert_eq!(sysfs_bus_id("00000000:01:00.0"), "0000:01:00.0");
+        assert_eq!(sysfs_bus_id("0000:0A:00.0"), "0000:0a:00.0");
+    }
+}
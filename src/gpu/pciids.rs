@@ -0,0 +1,283 @@
+//! Vendor, device and subsystem names from the PCI ID database.
+//!
+//! The system copy of `pci.ids` is read once under the configured
+//! [`FsRoot`]; a small bundled subset covers systems without hwdata.
+//! Names configured under `[gpu.names]` take precedence over both.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// Locations of the system database, in order of preference
+pub const SYSTEM_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+];
+
+const BUNDLED: &str = include_str!("pci.ids");
+
+static GLOBAL_IDS: RwLock<Option<Arc<PciIds>>> = RwLock::new(None);
+
+/// Identity of a PCI function. Subsystem IDs are 0 when unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PciId {
+    pub vendor: u16,
+    pub device: u16,
+    pub subvendor: u16,
+    pub subdevice: u16,
+}
+
+impl PciId {
+    /// Read the IDs from a sysfs PCI device directory
+    pub fn read(device_path: &Path) -> Option<Self> {
+        let read_id = |name: &str| {
+            fs::read_to_string(device_path.join(name))
+                .ok()
+                .and_then(|s| parse_hex(s.trim()))
+        };
+
+        Some(Self {
+            vendor: read_id("vendor")?,
+            device: read_id("device")?,
+            subvendor: read_id("subsystem_vendor").unwrap_or(0),
+            subdevice: read_id("subsystem_device").unwrap_or(0),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Device {
+    name: String,
+    subsystems: HashMap<(u16, u16), String>,
+}
+
+#[derive(Debug, Default)]
+struct Vendor {
+    name: String,
+    devices: HashMap<u16, Device>,
+}
+
+/// (vendor, device, subsystem); no subsystem matches every board
+type OverrideKey = (u16, u16, Option<(u16, u16)>);
+
+#[derive(Debug, Default)]
+pub struct PciIds {
+    vendors: HashMap<u16, Vendor>,
+    overrides: HashMap<OverrideKey, String>,
+}
+
+impl PciIds {
+    /// Parse the `pci.ids` format. The device class section is skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut ids = Self::default();
+        let mut vendor: Option<u16> = None;
+        let mut device: Option<u16> = None;
+
+        for line in content.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // Classes ("C 03  Display controller") come after all vendors
+            if line.starts_with("C ") {
+                break;
+            }
+
+            if let Some(rest) = line.strip_prefix("\t\t") {
+                let (Some(vendor), Some(device)) = (vendor, device) else { continue };
+                let Some((ids_part, name)) = rest.split_once("  ") else { continue };
+                let mut sub = ids_part.split_whitespace().filter_map(parse_hex);
+                if let (Some(subvendor), Some(subdevice)) = (sub.next(), sub.next()) {
+                    if let Some(entry) = ids.vendors.get_mut(&vendor)
+                        .and_then(|v| v.devices.get_mut(&device))
+                    {
+                        entry.subsystems.insert((subvendor, subdevice), name.trim().to_string());
+                    }
+                }
+            } else if let Some(rest) = line.strip_prefix('\t') {
+                let Some(vendor) = vendor else { continue };
+                let Some((id, name)) = rest.split_once("  ") else { continue };
+                device = parse_hex(id);
+                if let (Some(id), Some(entry)) = (device, ids.vendors.get_mut(&vendor)) {
+                    entry.devices.insert(id, Device {
+                        name: name.trim().to_string(),
+                        ..Device::default()
+                    });
+                }
+            } else {
+                let Some((id, name)) = line.split_once("  ") else { continue };
+                vendor = parse_hex(id);
+                device = None;
+                if let Some(id) = vendor {
+                    ids.vendors.insert(id, Vendor {
+                        name: name.trim().to_string(),
+                        ..Vendor::default()
+                    });
+                }
+            }
+        }
+
+        ids
+    }
+
+    /// The subset shipped with the binary
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED)
+    }
+
+    /// System database under `root`, falling back to the bundled subset
+    pub fn load(root: &FsRoot) -> Self {
+        for path in SYSTEM_PATHS {
+            if let Ok(content) = fs::read_to_string(root.path(path)) {
+                log::debug!("Loaded PCI ID database from {}", path);
+                return Self::parse(&content);
+            }
+        }
+        log::debug!("No system pci.ids found, using bundled subset");
+        Self::bundled()
+    }
+
+    /// Add user names keyed by "vendor:device" or
+    /// "vendor:device:subvendor:subdevice" (hex, e.g. "1002:747e")
+    pub fn with_overrides(mut self, names: &HashMap<String, String>) -> Self {
+        for (key, name) in names {
+            let parts: Option<Vec<u16>> = key.split(':').map(parse_hex).collect();
+            let key = match parts.as_deref() {
+                Some(&[vendor, device]) => (vendor, device, None),
+                Some(&[vendor, device, subvendor, subdevice]) => {
+                    (vendor, device, Some((subvendor, subdevice)))
+                }
+                _ => {
+                    log::warn!("Ignoring GPU name override with invalid PCI ID: {}", key);
+                    continue;
+                }
+            };
+            self.overrides.insert(key, name.clone());
+        }
+        self
+    }
+
+    /// Database shared by the GPU backends
+    pub fn global() -> Arc<PciIds> {
+        if let Some(ids) = GLOBAL_IDS.read().ok().and_then(|ids| ids.clone()) {
+            return ids;
+        }
+        let ids = Arc::new(Self::load(&FsRoot::global()));
+        if let Ok(mut global) = GLOBAL_IDS.write() {
+            global.get_or_insert_with(|| ids.clone());
+        }
+        ids
+    }
+
+    pub fn set_global(ids: PciIds) {
+        if let Ok(mut global) = GLOBAL_IDS.write() {
+            *global = Some(Arc::new(ids));
+        }
+    }
+
+    pub fn vendor_name(&self, vendor: u16) -> Option<&str> {
+        self.vendors.get(&vendor).map(|v| v.name.as_str())
+    }
+
+    pub fn device_name(&self, vendor: u16, device: u16) -> Option<&str> {
+        self.device(vendor, device).map(|d| d.name.as_str())
+    }
+
+    pub fn subsystem_name(&self, id: &PciId) -> Option<&str> {
+        self.device(id.vendor, id.device)?
+            .subsystems
+            .get(&(id.subvendor, id.subdevice))
+            .map(|s| s.as_str())
+    }
+
+    /// User-configured name, most specific match first
+    pub fn override_name(&self, id: &PciId) -> Option<&str> {
+        self.overrides.get(&(id.vendor, id.device, Some((id.subvendor, id.subdevice))))
+            .or_else(|| self.overrides.get(&(id.vendor, id.device, None)))
+            .map(|s| s.as_str())
+    }
+
+    /// Board name from the database: the subsystem name when the card is
+    /// listed, else the marketing name of the chip
+    pub fn lookup_name(&self, id: &PciId) -> Option<String> {
+        if let Some(name) = self.subsystem_name(id) {
+            return Some(name.to_string());
+        }
+        self.device_name(id.vendor, id.device).map(|name| marketing_name(name).to_string())
+    }
+
+    /// Override, then database
+    pub fn gpu_name(&self, id: &PciId) -> Option<String> {
+        self.override_name(id)
+            .map(|name| name.to_string())
+            .or_else(|| self.lookup_name(id))
+    }
+
+    fn device(&self, vendor: u16, device: u16) -> Option<&Device> {
+        self.vendors.get(&vendor)?.devices.get(&device)
+    }
+}
+
+/// "Navi 32 [Radeon RX 7700 XT / 7800 XT]" -> "Radeon RX 7700 XT / 7800 XT"
+fn marketing_name(name: &str) -> &str {
+    name.split_once('[')
+        .and_then(|(_, rest)| rest.split_once(']'))
+        .map(|(inner, _)| inner.trim())
+        .filter(|inner| !inner.is_empty())
+        .unwrap_or(name)
+}
+
+fn parse_hex(value: &str) -> Option<u16> {
+    let value = value.trim();
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    u16::from_str_radix(value, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_parse_and_resolve_names() {
+        let content = "\
+# comment
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t747e  Navi 32 [Radeon RX 7700 XT / 7800 XT]
+\t\t1da2 e471  NITRO+ RX 7800 XT
+10de  NVIDIA Corporation
+\t2684  AD102 [GeForce RTX 4090]
+C 03  Display controller
+\t00  VGA compatible controller
+";
+        let overrides = HashMap::from([
+            ("10de:2684".to_string(), "Lab RTX 4090".to_string()),
+            ("bogus".to_string(), "ignored".to_string()),
+        ]);
+        let ids = PciIds::parse(content).with_overrides(&overrides);
+
+        let nitro = PciId { vendor: 0x1002, device: 0x747e, subvendor: 0x1da2, subdevice: 0xe471 };
+        let reference = PciId { subvendor: 0x1002, subdevice: 0x0e3b, ..nitro };
+        assert_eq!(ids.vendor_name(0x1002), Some("Advanced Micro Devices, Inc. [AMD/ATI]"));
+        assert_eq!(ids.gpu_name(&nitro).as_deref(), Some("NITRO+ RX 7800 XT"));
+        assert_eq!(ids.gpu_name(&reference).as_deref(), Some("Radeon RX 7700 XT / 7800 XT"));
+
+        let rtx = PciId { vendor: 0x10de, device: 0x2684, subvendor: 0x1043, subdevice: 0x889d };
+        assert_eq!(ids.gpu_name(&rtx).as_deref(), Some("Lab RTX 4090"));
+        assert_eq!(ids.vendor_name(0x03), None);
+
+        // The bundled subset must stay parseable
+        assert!(PciIds::bundled().device_name(0x1002, 0x747e).is_some());
+    }
+
+    #[test]
+    fn test_load_reads_below_root() {
+        // A replayed fixture resolves names from its own tree, never the host's
+        let root = TestRoot::new("pciids");
+        assert!(PciIds::load(&root).device_name(0x1002, 0x747e).is_some());
+
+        root.write_file(SYSTEM_PATHS[1], "1002  AMD\n\t747e  Fixture Card\n").unwrap();
+        assert_eq!(PciIds::load(&root).device_name(0x1002, 0x747e), Some("Fixture Card"));
+    }
+}
@@ -59,6 +59,19 @@ impl<T: Clone> CircularBuffer<T> {
     pub fn get_at(&self, index: usize) -> Option<(u64, T)> {
         self.data.get(index).map(|dp| (dp.timestamp, dp.value.clone()))
     }
+
+    /// Latest value recorded at `timestamp`. Buffers that skip updates
+    /// (metrics a device does not report) are not index-aligned with the
+    /// CPU buffer, so playback looks them up by time.
+    pub fn value_at(&self, timestamp: u64) -> Option<T> {
+        // Timestamps only grow, so the last point at `timestamp` sits just
+        // before the first later one
+        let end = self.data.partition_point(|dp| dp.timestamp <= timestamp);
+        end.checked_sub(1)
+            .and_then(|i| self.data.get(i))
+            .filter(|dp| dp.timestamp == timestamp)
+            .map(|dp| dp.value.clone())
+    }
 }
 
 impl<T: Clone> Default for CircularBuffer<T> {
@@ -67,24 +80,139 @@ impl<T: Clone> Default for CircularBuffer<T> {
     }
 }
 
+/// History of one physical GPU, identified by its device key
+pub struct GpuHistory {
+    pub key: String,
+    pub name: String,
+    /// Whether the GPU was present in the latest update
+    pub present: bool,
+    pub temp: CircularBuffer<f32>,
+    pub util: CircularBuffer<f32>,
+    pub mem: CircularBuffer<f32>,
+    /// GTT and CPU-visible VRAM usage in percent, where reported (AMD)
+    pub gtt: CircularBuffer<f32>,
+    pub vis_vram: CircularBuffer<f32>,
+    /// Power draw in watts
+    pub power: CircularBuffer<f32>,
+    /// Core and memory clocks in MHz
+    pub clock: CircularBuffer<f32>,
+    pub mem_clock: CircularBuffer<f32>,
+    /// Fan duty in percent and fan speed in RPM; a GPU reports one of them
+    pub fan: CircularBuffer<f32>,
+    pub fan_rpm: CircularBuffer<f32>,
+    /// Latest known power limit in watts
+    pub power_limit: Option<f32>,
+}
+
+impl GpuHistory {
+    pub fn new(key: String, name: String, capacity: usize) -> Self {
+        Self {
+            key,
+            name,
+            present: true,
+            temp: CircularBuffer::new(capacity),
+            util: CircularBuffer::new(capacity),
+            mem: CircularBuffer::new(capacity),
+            gtt: CircularBuffer::new(capacity),
+            vis_vram: CircularBuffer::new(capacity),
+            power: CircularBuffer::new(capacity),
+            clock: CircularBuffer::new(capacity),
+            mem_clock: CircularBuffer::new(capacity),
+            fan: CircularBuffer::new(capacity),
+            fan_rpm: CircularBuffer::new(capacity),
+            power_limit: None,
+        }
+    }
+}
+
+/// History of one block device, identified by its kernel name
+pub struct DiskHistory {
+    pub name: String,
+    /// Throughput in bytes per second
+    pub read: CircularBuffer<f32>,
+    pub write: CircularBuffer<f32>,
+    /// Busy time in percent
+    pub util: CircularBuffer<f32>,
+    /// Average request latency in milliseconds
+    pub latency: CircularBuffer<f32>,
+}
+
+impl DiskHistory {
+    pub fn new(name: String, capacity: usize) -> Self {
+        Self {
+            name,
+            read: CircularBuffer::new(capacity),
+            write: CircularBuffer::new(capacity),
+            util: CircularBuffer::new(capacity),
+            latency: CircularBuffer::new(capacity),
+        }
+    }
+}
+
+/// Traffic history of one network interface
+pub struct NetworkHistory {
+    pub name: String,
+    /// Receive and transmit rates in bytes per second
+    pub rx: CircularBuffer<f32>,
+    pub tx: CircularBuffer<f32>,
+}
+
+impl NetworkHistory {
+    pub fn new(name: String, capacity: usize) -> Self {
+        Self {
+            name,
+            rx: CircularBuffer::new(capacity),
+            tx: CircularBuffer::new(capacity),
+        }
+    }
+}
+
+/// History of one CPU or board sensor, identified by its key ("k10temp/Tctl")
+pub struct SensorHistory {
+    pub key: String,
+    pub kind: crate::metrics::SensorKind,
+    pub values: CircularBuffer<f32>,
+}
+
+impl SensorHistory {
+    pub fn new(key: String, kind: crate::metrics::SensorKind, capacity: usize) -> Self {
+        Self {
+            key,
+            kind,
+            values: CircularBuffer::new(capacity),
+        }
+    }
+}
+
 pub struct MetricsHistory {
     pub cpu_usage: CircularBuffer<f32>,
     pub memory_usage: CircularBuffer<f32>,
     pub swap_usage: CircularBuffer<f32>,
-    pub gpu_temp: Vec<CircularBuffer<f32>>,
-    pub gpu_util: Vec<CircularBuffer<f32>>,
-    pub gpu_mem: Vec<CircularBuffer<f32>>,
+    /// Shares of CPU time in steal and iowait, in percent
+    pub cpu_steal: CircularBuffer<f32>,
+    pub cpu_iowait: CircularBuffer<f32>,
+    /// Per-GPU history in order of first appearance. Entries of removed
+    /// GPUs are kept so a re-attached card continues its history.
+    pub gpus: Vec<GpuHistory>,
+    pub disks: Vec<DiskHistory>,
+    pub networks: Vec<NetworkHistory>,
+    pub sensors: Vec<SensorHistory>,
+    capacity: usize,
 }
 
 impl MetricsHistory {
-    pub fn new(capacity: usize, gpu_count: usize) -> Self {
+    pub fn new(capacity: usize) -> Self {
         Self {
             cpu_usage: CircularBuffer::new(capacity),
             memory_usage: CircularBuffer::new(capacity),
             swap_usage: CircularBuffer::new(capacity),
-            gpu_temp: (0..gpu_count).map(|_| CircularBuffer::new(capacity)).collect(),
-            gpu_util: (0..gpu_count).map(|_| CircularBuffer::new(capacity)).collect(),
-            gpu_mem: (0..gpu_count).map(|_| CircularBuffer::new(capacity)).collect(),
+            cpu_steal: CircularBuffer::new(capacity),
+            cpu_iowait: CircularBuffer::new(capacity),
+            gpus: Vec::new(),
+            disks: Vec::new(),
+            networks: Vec::new(),
+            sensors: Vec::new(),
+            capacity,
         }
     }
 
@@ -99,26 +227,217 @@ impl MetricsHistory {
         self.memory_usage.push(memory_usage, timestamp);
         self.swap_usage.push(swap_usage, timestamp);
 
-        for (i, gpu_info) in gpu_infos.iter().enumerate() {
-            if i < self.gpu_temp.len() {
-                if let Some(temp) = gpu_info.temperature {
-                    self.gpu_temp[i].push(temp, timestamp);
+        for gpu in self.gpus.iter_mut() {
+            gpu.present = false;
+        }
+
+        for gpu_info in gpu_infos {
+            let gpu = self.gpu_entry(&gpu_info.key, &gpu_info.name);
+            gpu.present = true;
+            if let Some(temp) = gpu_info.temperature {
+                gpu.temp.push(temp, timestamp);
+            }
+            if let Some(util) = gpu_info.utilization {
+                gpu.util.push(util, timestamp);
+            }
+            if let Some(mem_percent) = gpu_info.memory_usage_percent() {
+                gpu.mem.push(mem_percent, timestamp);
+            }
+            if let Some(gtt_percent) = gpu_info.gtt_usage_percent() {
+                gpu.gtt.push(gtt_percent, timestamp);
+            }
+            if let Some(vis_percent) = gpu_info.vis_vram_usage_percent() {
+                gpu.vis_vram.push(vis_percent, timestamp);
+            }
+            if let Some(power) = gpu_info.power_draw {
+                gpu.power.push(power, timestamp);
+            }
+            if let Some(clock) = gpu_info.clock_speed {
+                gpu.clock.push(clock as f32, timestamp);
+            }
+            if let Some(mem_clock) = gpu_info.memory_clock {
+                gpu.mem_clock.push(mem_clock as f32, timestamp);
+            }
+            if let Some(fan) = gpu_info.fan_speed {
+                gpu.fan.push(fan as f32, timestamp);
+            }
+            if let Some(rpm) = gpu_info.fan_rpm {
+                gpu.fan_rpm.push(rpm as f32, timestamp);
+            }
+            if gpu_info.power_limit.is_some() {
+                gpu.power_limit = gpu_info.power_limit;
+            }
+        }
+    }
+
+    pub fn update_cpu_times(&mut self, timestamp: u64, cpu: &crate::metrics::CpuBreakdown) {
+        self.cpu_steal.push(cpu.steal, timestamp);
+        self.cpu_iowait.push(cpu.iowait, timestamp);
+    }
+
+    pub fn update_disks(&mut self, timestamp: u64, disks: &[crate::metrics::DiskIo]) {
+        for disk in disks {
+            let pos = match self.disks.iter().position(|d| d.name == disk.name) {
+                Some(pos) => pos,
+                None => {
+                    self.disks.push(DiskHistory::new(disk.name.clone(), self.capacity));
+                    self.disks.len() - 1
                 }
-                if let Some(util) = gpu_info.utilization {
-                    self.gpu_util[i].push(util, timestamp);
+            };
+            let history = &mut self.disks[pos];
+            history.read.push(disk.read_bytes_per_sec as f32, timestamp);
+            history.write.push(disk.write_bytes_per_sec as f32, timestamp);
+            history.util.push(disk.utilization, timestamp);
+            if let Some(latency) = disk.avg_latency_ms {
+                history.latency.push(latency, timestamp);
+            }
+        }
+        if let Some(start) = self.window_start() {
+            self.disks.retain(|disk| seen_since(&disk.read, start));
+        }
+    }
+
+    pub fn update_networks(&mut self, timestamp: u64, interfaces: &[crate::metrics::NetworkInterface]) {
+        for iface in interfaces {
+            let pos = match self.networks.iter().position(|n| n.name == iface.name) {
+                Some(pos) => pos,
+                None => {
+                    self.networks.push(NetworkHistory::new(iface.name.clone(), self.capacity));
+                    self.networks.len() - 1
                 }
-                if let Some(mem_percent) = gpu_info.memory_usage_percent() {
-                    self.gpu_mem[i].push(mem_percent, timestamp);
+            };
+            let history = &mut self.networks[pos];
+            history.rx.push(iface.rx_bytes_per_sec as f32, timestamp);
+            history.tx.push(iface.tx_bytes_per_sec as f32, timestamp);
+        }
+        if let Some(start) = self.window_start() {
+            self.networks.retain(|network| seen_since(&network.rx, start));
+        }
+    }
+
+    pub fn update_sensors(&mut self, timestamp: u64, readings: &[crate::metrics::SensorReading]) {
+        for reading in readings {
+            let key = reading.key();
+            let pos = match self.sensors.iter().position(|s| s.key == key) {
+                Some(pos) => pos,
+                None => {
+                    self.sensors.push(SensorHistory::new(key, reading.kind, self.capacity));
+                    self.sensors.len() - 1
                 }
+            };
+            self.sensors[pos].values.push(reading.value, timestamp);
+        }
+        if let Some(start) = self.window_start() {
+            self.sensors.retain(|sensor| seen_since(&sensor.values, start));
+        }
+    }
+
+    /// Oldest time the CPU history still covers. Disks, interfaces and
+    /// sensors not seen since then are dropped, so short-lived devices
+    /// (USB sticks, container veths) do not pile up.
+    fn window_start(&self) -> Option<u64> {
+        self.cpu_usage.get_all().front().map(|dp| dp.timestamp)
+    }
+
+    pub fn sensor(&self, key: &str) -> Option<&SensorHistory> {
+        self.sensors.iter().find(|sensor| sensor.key == key)
+    }
+
+    pub fn network(&self, name: &str) -> Option<&NetworkHistory> {
+        self.networks.iter().find(|network| network.name == name)
+    }
+
+    pub fn disk(&self, name: &str) -> Option<&DiskHistory> {
+        self.disks.iter().find(|disk| disk.name == name)
+    }
+
+    pub fn gpu(&self, key: &str) -> Option<&GpuHistory> {
+        self.gpus.iter().find(|gpu| gpu.key == key)
+    }
+
+    fn gpu_entry(&mut self, key: &str, name: &str) -> &mut GpuHistory {
+        let pos = match self.gpus.iter().position(|gpu| gpu.key == key) {
+            Some(pos) => pos,
+            None => {
+                self.gpus.push(GpuHistory::new(key.to_string(), name.to_string(), self.capacity));
+                self.gpus.len() - 1
             }
+        };
+        &mut self.gpus[pos]
+    }
+}
+
+/// Whether `buffer` has a point at or after `start`
+fn seen_since(buffer: &CircularBuffer<f32>, start: u64) -> bool {
+    buffer.get_all().back().is_some_and(|dp| dp.timestamp >= start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_at() {
+        let mut buffer = CircularBuffer::new(4);
+        for (value, timestamp) in [(1.0, 10), (2.0, 11), (3.0, 13), (4.0, 14), (5.0, 14)] {
+            buffer.push(value, timestamp);
         }
+        assert_eq!(buffer.value_at(10), None); // evicted
+        assert_eq!(buffer.value_at(11), Some(2.0));
+        assert_eq!(buffer.value_at(12), None);
+        assert_eq!(buffer.value_at(14), Some(5.0));
+        assert_eq!(buffer.value_at(15), None);
+    }
+
+    #[test]
+    fn test_fan_units_kept_apart() {
+        let percent = crate::gpu::GPUSample {
+            fan_speed: Some(40),
+            ..crate::gpu::GPUSample::new("GeForce".to_string(), "NVIDIA")
+        };
+        let rpm = crate::gpu::GPUSample {
+            fan_rpm: Some(1500),
+            ..crate::gpu::GPUSample::new("Radeon".to_string(), "AMD")
+        };
+        let gpus = [
+            crate::gpu::GPUInfo::from_sample(0, "0000:01:00.0".to_string(), percent),
+            crate::gpu::GPUInfo::from_sample(1, "0000:03:00.0".to_string(), rpm),
+        ];
+        let mut history = MetricsHistory::new(10);
+        history.update(1, 0.0, 0.0, 0.0, &gpus);
+        assert_eq!((history.gpus[0].fan.get_latest(), history.gpus[0].fan_rpm.get_latest()), (Some(&40.0), None));
+        assert_eq!((history.gpus[1].fan.get_latest(), history.gpus[1].fan_rpm.get_latest()), (None, Some(&1500.0)));
     }
 
-    pub fn resize_gpu_buffers(&mut self, new_count: usize, capacity: usize) {
-        while self.gpu_temp.len() < new_count {
-            self.gpu_temp.push(CircularBuffer::new(capacity));
-            self.gpu_util.push(CircularBuffer::new(capacity));
-            self.gpu_mem.push(CircularBuffer::new(capacity));
+    #[test]
+    fn test_prune_devices_outside_window() {
+        let veth = |name: &str| crate::metrics::NetworkInterface {
+            name: name.to_string(),
+            operstate: "up".to_string(),
+            loopback: false,
+            speed_mbps: None,
+            mtu: None,
+            mac: None,
+            addresses: Vec::new(),
+            rx_bytes_per_sec: 0.0,
+            tx_bytes_per_sec: 0.0,
+            rx_packets_per_sec: 0.0,
+            tx_packets_per_sec: 0.0,
+            rx_errors_per_sec: 0.0,
+            tx_errors_per_sec: 0.0,
+            rx_drops_per_sec: 0.0,
+            tx_drops_per_sec: 0.0,
+            rx_total: 0,
+            tx_total: 0,
+        };
+        let mut history = MetricsHistory::new(3);
+        for timestamp in 0..5 {
+            history.update(timestamp, 0.0, 0.0, 0.0, &[]);
+            let interfaces = if timestamp < 1 { vec![veth("eth0"), veth("veth1")] } else { vec![veth("eth0")] };
+            history.update_networks(timestamp, &interfaces);
+            let names: Vec<&str> = history.networks.iter().map(|n| n.name.as_str()).collect();
+            // veth1 was last seen at 0, which leaves the 3-sample window at 3
+            assert_eq!(names.len(), if timestamp < 3 { 2 } else { 1 }, "at {}", timestamp);
         }
     }
 }
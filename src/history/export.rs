@@ -4,10 +4,36 @@ use std::fs::File;
 use std::io::Write;
 use serde_json::json;
 
+const GPU_CSV_COLUMNS: [&str; 10] = [
+    "temp", "util", "mem", "gtt", "vis_vram", "power_w", "core_mhz", "mem_mhz", "fan_pct", "fan_rpm",
+];
+const DISK_CSV_COLUMNS: [&str; 4] = ["read_bps", "write_bps", "util", "latency_ms"];
+const NETWORK_CSV_COLUMNS: [&str; 2] = ["rx_bps", "tx_bps"];
+
 pub fn export_to_csv(history: &MetricsHistory, path: &str) -> Result<()> {
     let mut file = File::create(path)?;
     
-    writeln!(file, "timestamp,cpu_usage,memory_usage,swap_usage")?;
+    // One column per GPU metric; GPUs are numbered in order of appearance
+    let mut header = "timestamp,cpu_usage,memory_usage,swap_usage,cpu_steal,cpu_iowait".to_string();
+    for i in 0..history.gpus.len() {
+        for column in GPU_CSV_COLUMNS {
+            header.push_str(&format!(",gpu{}_{}", i, column));
+        }
+    }
+    for disk in &history.disks {
+        for column in DISK_CSV_COLUMNS {
+            header.push_str(&format!(",disk_{}_{}", disk.name, column));
+        }
+    }
+    for network in &history.networks {
+        for column in NETWORK_CSV_COLUMNS {
+            header.push_str(&format!(",net_{}_{}", network.name, column));
+        }
+    }
+    for sensor in &history.sensors {
+        header.push_str(&format!(",sensor_{}", sensor.key));
+    }
+    writeln!(file, "{}", header)?;
     
     let cpu_data = history.cpu_usage.get_all();
     let mem_data = history.memory_usage.get_all();
@@ -21,7 +47,50 @@ pub fn export_to_csv(history: &MetricsHistory, path: &str) -> Result<()> {
         let mem = mem_data.get(i).map(|d| d.value).unwrap_or(0.0);
         let swap = swap_data.get(i).map(|d| d.value).unwrap_or(0.0);
         
-        writeln!(file, "{},{:.2},{:.2},{:.2}", timestamp, cpu, mem, swap)?;
+        // GPU, disk, network and sensor metrics without a sample at this time are left empty
+        let mut device_cells = String::new();
+        for buffer in [&history.cpu_steal, &history.cpu_iowait] {
+            device_cells.push(',');
+            if let Some(value) = buffer.value_at(timestamp) {
+                device_cells.push_str(&format!("{:.2}", value));
+            }
+        }
+        for gpu in &history.gpus {
+            let buffers = [
+                &gpu.temp, &gpu.util, &gpu.mem, &gpu.gtt, &gpu.vis_vram,
+                &gpu.power, &gpu.clock, &gpu.mem_clock, &gpu.fan, &gpu.fan_rpm,
+            ];
+            for buffer in buffers {
+                device_cells.push(',');
+                if let Some(value) = buffer.value_at(timestamp) {
+                    device_cells.push_str(&format!("{:.2}", value));
+                }
+            }
+        }
+        for disk in &history.disks {
+            for buffer in [&disk.read, &disk.write, &disk.util, &disk.latency] {
+                device_cells.push(',');
+                if let Some(value) = buffer.value_at(timestamp) {
+                    device_cells.push_str(&format!("{:.2}", value));
+                }
+            }
+        }
+        for network in &history.networks {
+            for buffer in [&network.rx, &network.tx] {
+                device_cells.push(',');
+                if let Some(value) = buffer.value_at(timestamp) {
+                    device_cells.push_str(&format!("{:.2}", value));
+                }
+            }
+        }
+        for sensor in &history.sensors {
+            device_cells.push(',');
+            if let Some(value) = sensor.values.value_at(timestamp) {
+                device_cells.push_str(&format!("{:.2}", value));
+            }
+        }
+        
+        writeln!(file, "{},{:.2},{:.2},{:.2}{}", timestamp, cpu, mem, swap, device_cells)?;
     }
     
     Ok(())
@@ -40,37 +109,66 @@ pub fn export_to_json(history: &MetricsHistory, path: &str) -> Result<()> {
         .map(|dp| json!({"timestamp": dp.timestamp, "value": dp.value}))
         .collect();
     
-    let mut gpu_data = Vec::new();
-    for (i, gpu_temp_buffer) in history.gpu_temp.iter().enumerate() {
-        let temp_data: Vec<_> = gpu_temp_buffer.get_all().iter()
+    let points = |buffer: &crate::history::CircularBuffer<f32>| -> Vec<_> {
+        buffer.get_all().iter()
             .map(|dp| json!({"timestamp": dp.timestamp, "value": dp.value}))
-            .collect();
-        
-        let util_data: Vec<_> = history.gpu_util.get(i)
-            .map(|b| b.get_all().iter()
-                .map(|dp| json!({"timestamp": dp.timestamp, "value": dp.value}))
-                .collect())
-            .unwrap_or_default();
-        
-        let mem_data: Vec<_> = history.gpu_mem.get(i)
-            .map(|b| b.get_all().iter()
-                .map(|dp| json!({"timestamp": dp.timestamp, "value": dp.value}))
-                .collect())
-            .unwrap_or_default();
-        
-        gpu_data.push(json!({
-            "gpu_id": i,
-            "temperature": temp_data,
-            "utilization": util_data,
-            "memory": mem_data,
-        }));
-    }
+            .collect()
+    };
+
+    let gpu_data: Vec<_> = history.gpus.iter()
+        .map(|gpu| json!({
+            "key": gpu.key,
+            "name": gpu.name,
+            "temperature": points(&gpu.temp),
+            "utilization": points(&gpu.util),
+            "memory": points(&gpu.mem),
+            "gtt": points(&gpu.gtt),
+            "visible_vram": points(&gpu.vis_vram),
+            "power": points(&gpu.power),
+            "power_limit": gpu.power_limit,
+            "core_clock": points(&gpu.clock),
+            "memory_clock": points(&gpu.mem_clock),
+            "fan_percent": points(&gpu.fan),
+            "fan_rpm": points(&gpu.fan_rpm),
+        }))
+        .collect();
+
+    let disk_data: Vec<_> = history.disks.iter()
+        .map(|disk| json!({
+            "name": disk.name,
+            "read_bytes_per_sec": points(&disk.read),
+            "write_bytes_per_sec": points(&disk.write),
+            "utilization": points(&disk.util),
+            "latency_ms": points(&disk.latency),
+        }))
+        .collect();
+
+    let network_data: Vec<_> = history.networks.iter()
+        .map(|network| json!({
+            "name": network.name,
+            "rx_bytes_per_sec": points(&network.rx),
+            "tx_bytes_per_sec": points(&network.tx),
+        }))
+        .collect();
+
+    let sensor_data: Vec<_> = history.sensors.iter()
+        .map(|sensor| json!({
+            "key": sensor.key,
+            "unit": sensor.kind.unit(),
+            "values": points(&sensor.values),
+        }))
+        .collect();
     
     let output = json!({
         "cpu": cpu_data,
         "memory": mem_data,
         "swap": swap_data,
+        "cpu_steal": points(&history.cpu_steal),
+        "cpu_iowait": points(&history.cpu_iowait),
         "gpus": gpu_data,
+        "disks": disk_data,
+        "networks": network_data,
+        "sensors": sensor_data,
     });
     
     let json_str = serde_json::to_string_pretty(&output)
@@ -81,3 +179,35 @@ pub fn export_to_json(history: &MetricsHistory, path: &str) -> Result<()> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+    use crate::gpu::{GPUInfo, GPUSample};
+
+    #[test]
+    fn test_csv_gpu_columns() {
+        let sample = GPUSample {
+            temperature: Some(60.0),
+            memory_used: Some(1 << 30),
+            memory_total: Some(4 << 30),
+            gtt_used: Some(1 << 30),
+            gtt_total: Some(8 << 30),
+            vis_vram_used: Some(128 << 20),
+            vis_vram_total: Some(256 << 20),
+            ..GPUSample::new("Radeon 780M".to_string(), "AMD")
+        };
+        let mut history = MetricsHistory::new(10);
+        history.update(100, 10.0, 20.0, 0.0, &[GPUInfo::from_sample(0, "0000:c4:00.0".to_string(), sample)]);
+
+        let root = TestRoot::new("export-csv");
+        let path = root.path("/history.csv");
+        export_to_csv(&history, path.to_str().unwrap()).unwrap();
+        let csv = std::fs::read_to_string(path).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), "timestamp,cpu_usage,memory_usage,swap_usage,cpu_steal,cpu_iowait,\
+            gpu0_temp,gpu0_util,gpu0_mem,gpu0_gtt,gpu0_vis_vram,gpu0_power_w,gpu0_core_mhz,gpu0_mem_mhz,gpu0_fan_pct,gpu0_fan_rpm");
+        assert_eq!(lines.next().unwrap(), "100,10.00,20.00,0.00,,,60.00,,25.00,12.50,50.00,,,,,");
+    }
+}
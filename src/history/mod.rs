@@ -1,5 +1,5 @@
 pub mod buffer;
 pub mod export;
 
-pub use buffer::{CircularBuffer, DataPoint, MetricsHistory};
+pub use buffer::{CircularBuffer, DataPoint, DiskHistory, GpuHistory, MetricsHistory, NetworkHistory, SensorHistory};
 pub use export::{export_to_csv, export_to_json};
@@ -10,6 +10,7 @@ pub mod history;
 pub mod logger;
 pub mod trends;
 pub mod daemon;
+pub mod fsroot;
 
 pub use app::App;
 pub use config::Config;
@@ -1,5 +1,9 @@
 use clap::Parser;
+use gleam_observer::fsroot::{self, FsRoot};
+use gleam_observer::gpu::{self, DeviceProperties, GPUInfo};
 use gleam_observer::{App, Config, Result};
+use serde_json::json;
+use std::path::Path;
 use std::time::Duration;
 
 #[derive(Parser, Debug)]
@@ -32,6 +36,9 @@ struct Args {
 
     #[arg(long, help = "Run UI only (no daemon)")]
     ui_only: bool,
+
+    #[arg(long, help = "Record a sysfs/procfs snapshot for fixture mode and exit", value_name = "FILE")]
+    record_fixture: Option<String>,
 }
 
 fn main() -> Result<()> {
@@ -56,7 +63,18 @@ fn main() -> Result<()> {
         config.refresh.interval_ms = args.refresh_rate;
     }
 
+    if let Some(out) = args.record_fixture {
+        let root = FsRoot::from_config(&config.system)?;
+        let entries = fsroot::record_snapshot(&root, fsroot::DEFAULT_RECORD_PATHS, Path::new(&out))?;
+        log::info!("Recorded {} entries to {}", entries, out);
+        return Ok(());
+    }
+
     let enable_gpu = !args.no_gpu;
+    let gpu_control = enable_gpu && config.gpu.control.enabled;
+    if gpu_control {
+        gpu::control::install_panic_hook();
+    }
 
     if args.tray {
         #[cfg(all(unix, feature = "systray"))]
@@ -72,13 +90,21 @@ fn main() -> Result<()> {
                 "Systray feature not enabled".to_string()
             ));
         }
-    } else if args.headless {
-        run_headless(config, args.export, enable_gpu)?;
-    } else {
-        run_tui(config, enable_gpu)?;
     }
 
-    Ok(())
+    // Changed GPU settings are put back and an unpacked fixture removed
+    // when the process is killed; the headless loop only ends that way
+    #[cfg(unix)]
+    gpu::control::install_signal_handler()?;
+
+    let result = if args.headless {
+        run_headless(config, args.export, enable_gpu)
+    } else {
+        run_tui(config, enable_gpu)
+    };
+    // Removes the directory of an unpacked fixture
+    FsRoot::clear_global();
+    result
 }
 
 fn run_headless(config: Config, export_format: Option<String>, enable_gpu: bool) -> Result<()> {
@@ -87,8 +113,8 @@ fn run_headless(config: Config, export_format: Option<String>, enable_gpu: bool)
     let mut app = App::new(config, enable_gpu)?;
     
     if let Some(gpu_manager) = &app.gpu {
-        for (i, gpu_info) in gpu_manager.get_info().iter().enumerate() {
-            log::info!("GPU {}: {} - {}", i, gpu_info.vendor, gpu_info.name);
+        for (i, gpu) in gpu_manager.gpus().iter().enumerate() {
+            log::info!("GPU {}: {} - {}", i, gpu.vendor(), gpu.name());
         }
     }
     
@@ -97,32 +123,21 @@ fn run_headless(config: Config, export_format: Option<String>, enable_gpu: bool)
         
         match export_format.as_deref() {
             Some("json") => {
-                let gpu_data = if let Some(gpu_manager) = &app.gpu {
-                    let gpus: Vec<String> = gpu_manager.get_info().iter()
-                        .map(|g| format!(
-                            "{{\"name\": \"{}\", \"vendor\": \"{}\", \"temp\": {}, \"util\": {}, \"mem_used_mb\": {}, \"mem_total_mb\": {}}}",
-                            g.name,
-                            g.vendor,
-                            g.temperature.map(|t| format!("{:.1}", t)).unwrap_or_else(|| "null".to_string()),
-                            g.utilization.map(|u| format!("{:.1}", u)).unwrap_or_else(|| "null".to_string()),
-                            g.memory_used.map(|m| (m / 1024 / 1024).to_string()).unwrap_or_else(|| "null".to_string()),
-                            g.memory_total.map(|m| (m / 1024 / 1024).to_string()).unwrap_or_else(|| "null".to_string())
-                        ))
+                let mut output = json!({
+                    "cpu": round2(app.metrics.global_cpu_usage()),
+                    "memory": round2(app.metrics.memory_usage_percent()),
+                });
+                if let Some(gpu_manager) = &app.gpu {
+                    output["gpus"] = app.gpu_info_cache.iter()
+                        .map(|g| gpu_json(g, gpu_manager.properties(&g.key).as_ref()))
                         .collect();
-                    format!(", \"gpus\": [{}]", gpus.join(", "))
-                } else {
-                    String::new()
-                };
+                }
                 
-                println!("{{\"cpu\": {:.2}, \"memory\": {:.2}{}}}", 
-                    app.metrics.global_cpu_usage(),
-                    app.metrics.memory_usage_percent(),
-                    gpu_data
-                );
+                println!("{}", output);
             }
             Some("csv") => {
-                let gpu_csv = if let Some(gpu_manager) = &app.gpu {
-                    let gpu_vals: Vec<String> = gpu_manager.get_info().iter()
+                let gpu_csv = if app.gpu.is_some() {
+                    let gpu_vals: Vec<String> = app.gpu_info_cache.iter()
                         .map(|g| format!("{},{}", 
                             g.temperature.map(|t| format!("{:.1}", t)).unwrap_or_else(|| "N/A".to_string()),
                             g.utilization.map(|u| format!("{:.1}", u)).unwrap_or_else(|| "N/A".to_string())
@@ -149,13 +164,15 @@ fn run_headless(config: Config, export_format: Option<String>, enable_gpu: bool)
                     app.metrics.memory_usage_percent()
                 );
                 
-                if let Some(gpu_manager) = &app.gpu {
-                    for (i, gpu_info) in gpu_manager.get_info().iter().enumerate() {
-                        print!(" | GPU{}: {:.1}°C {:.1}%", 
-                            i,
-                            gpu_info.temperature.unwrap_or(0.0),
-                            gpu_info.utilization.unwrap_or(0.0)
-                        );
+                for (i, gpu_info) in app.gpu_info_cache.iter().enumerate() {
+                    print!(" | GPU{}: {:.1}°C {:.1}%", 
+                        i,
+                        gpu_info.temperature.unwrap_or(0.0),
+                        gpu_info.utilization.unwrap_or(0.0)
+                    );
+                    if let Some(reasons) = gpu_info.throttle_reasons.as_ref().filter(|r| !r.is_empty()) {
+                        let labels: Vec<&str> = reasons.iter().map(|r| r.label()).collect();
+                        print!(" [throttled: {}]", labels.join(", "));
                     }
                 }
                 println!();
@@ -166,6 +183,60 @@ fn run_headless(config: Config, export_format: Option<String>, enable_gpu: bool)
     }
 }
 
+fn round1(value: f32) -> f64 {
+    (value as f64 * 10.0).round() / 10.0
+}
+
+/// CPU and memory keep the two decimals they had before the JSON output
+/// was built with serde_json
+fn round2(value: f32) -> f64 {
+    (value as f64 * 100.0).round() / 100.0
+}
+
+fn gpu_json(g: &GPUInfo, properties: Option<&DeviceProperties>) -> serde_json::Value {
+    json!({
+        "key": g.key,
+        "name": g.name,
+        "vendor": g.vendor,
+        "properties": properties.map(|p| json!({
+            "driver": p.driver,
+            "driver_version": p.driver_version,
+            "vbios_version": p.vbios_version,
+            "compute_capability": p.compute_capability,
+            "pci_bus_id": p.pci_bus_id,
+            "uuid": p.uuid,
+            "max_clock_mhz": p.max_clock,
+            "max_memory_clock_mhz": p.max_memory_clock,
+        })),
+        "temp": g.temperature.map(round1),
+        "sensors": g.sensors.iter().map(|s| json!({
+            "label": s.label,
+            "temp": round1(s.temperature),
+            "critical": s.critical.map(round1),
+        })).collect::<Vec<_>>(),
+        "util": g.utilization.map(round1),
+        "mem_used_mb": g.memory_used.map(|m| m / 1024 / 1024),
+        "mem_total_mb": g.memory_total.map(|m| m / 1024 / 1024),
+        "gtt_used_mb": g.gtt_used.map(|m| m / 1024 / 1024),
+        "gtt_total_mb": g.gtt_total.map(|m| m / 1024 / 1024),
+        "vis_vram_used_mb": g.vis_vram_used.map(|m| m / 1024 / 1024),
+        "vis_vram_total_mb": g.vis_vram_total.map(|m| m / 1024 / 1024),
+        "encoder_util": g.encoder_utilization.map(round1),
+        "decoder_util": g.decoder_utilization.map(round1),
+        "media_util": g.media_utilization.map(round1),
+        "pcie": g.pcie_link.map(|link| json!({
+            "current_gen": link.current_gen,
+            "current_width": link.current_width,
+            "max_gen": link.max_gen,
+            "max_width": link.max_width,
+            "tx_kbps": g.pcie_throughput.map(|t| t.tx_kbps),
+            "rx_kbps": g.pcie_throughput.map(|t| t.rx_kbps),
+        })),
+        "throttle_reasons": g.throttle_reasons.as_ref()
+            .map(|reasons| reasons.iter().map(|r| r.label()).collect::<Vec<_>>()),
+    })
+}
+
 fn run_tui(config: Config, enable_gpu: bool) -> Result<()> {
     log::info!("Starting TUI mode");
     
@@ -176,8 +247,8 @@ fn run_tui(config: Config, enable_gpu: bool) -> Result<()> {
     
     if let Some(gpu_manager) = &app.gpu {
         log::info!("GPUs detected: {}", gpu_manager.gpu_count());
-        for (i, gpu_info) in gpu_manager.get_info().iter().enumerate() {
-            log::info!("  GPU {}: {} - {}", i, gpu_info.vendor, gpu_info.name);
+        for (i, gpu) in gpu_manager.gpus().iter().enumerate() {
+            log::info!("  GPU {}: {} - {}", i, gpu.vendor(), gpu.name());
         }
     } else {
         log::info!("No GPUs detected or GPU monitoring disabled");
@@ -0,0 +1,316 @@
+//! Resource usage of the cgroup v2 hierarchy (systemd slices, services,
+//! scopes and containers).
+//!
+//! The kernel counts descendants in cpu.stat, memory.current, io.stat and
+//! pids.current, so every node's values are already totals of its subtree.
+
+use crate::fsroot::FsRoot;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Guard against bind-mount loops; real hierarchies are far shallower
+pub(crate) const MAX_DEPTH: usize = 32;
+
+/// The cgroup v2 hierarchy: /sys/fs/cgroup, or /sys/fs/cgroup/unified on
+/// hybrid setups
+pub(crate) fn cgroup_root(root: &FsRoot) -> Option<PathBuf> {
+    ["/sys/fs/cgroup", "/sys/fs/cgroup/unified"].iter()
+        .map(|path| root.path(path))
+        .find(|path| path.join("cgroup.controllers").exists())
+}
+
+/// Cumulative counters kept between refreshes for the rates
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    usage_usec: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupNode {
+    /// Path below the cgroup root; "" for the root itself
+    pub path: String,
+    /// Last path component, "/" for the root
+    pub name: String,
+    /// Child paths, busiest first
+    pub children: Vec<String>,
+    /// CPU time used per wall time; 100 is one full core
+    pub cpu_percent: f32,
+    pub memory_current: Option<u64>,
+    /// `None` when unlimited
+    pub memory_max: Option<u64>,
+    pub io_read_bytes_per_sec: f64,
+    pub io_write_bytes_per_sec: f64,
+    pub pids_current: Option<u64>,
+    /// Processes directly in this cgroup
+    pub procs: Vec<u32>,
+}
+
+pub struct CgroupMetrics {
+    root: FsRoot,
+    previous: HashMap<String, Counters>,
+    last_refresh: Option<Instant>,
+    nodes: HashMap<String, CgroupNode>,
+}
+
+impl CgroupMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            previous: HashMap::new(),
+            last_refresh: None,
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.refresh_at(Instant::now());
+    }
+
+    /// `refresh` with the time of the read given, for tests
+    pub(crate) fn refresh_at(&mut self, now: Instant) {
+        let Some(cgroup_root) = cgroup_root(&self.root) else {
+            return;
+        };
+        let elapsed = self.last_refresh.map(|last| now.duration_since(last));
+
+        let mut nodes = HashMap::new();
+        let mut counters = HashMap::new();
+        self.walk(&cgroup_root, &cgroup_root, 0, elapsed, &mut nodes, &mut counters);
+
+        // Busiest children first, so the heavy slice is at the top
+        let cpu: HashMap<String, f32> = nodes.iter().map(|(path, node)| (path.clone(), node.cpu_percent)).collect();
+        for node in nodes.values_mut() {
+            node.children.sort_by(|a, b| {
+                let (cpu_a, cpu_b) = (cpu.get(a).copied().unwrap_or(0.0), cpu.get(b).copied().unwrap_or(0.0));
+                cpu_b.total_cmp(&cpu_a).then_with(|| a.cmp(b))
+            });
+        }
+
+        self.nodes = nodes;
+        self.previous = counters;
+        self.last_refresh = Some(now);
+    }
+
+    fn walk(
+        &self,
+        cgroup_root: &Path,
+        dir: &Path,
+        depth: usize,
+        elapsed: Option<Duration>,
+        nodes: &mut HashMap<String, CgroupNode>,
+        counters: &mut HashMap<String, Counters>,
+    ) {
+        let Ok(relative) = dir.strip_prefix(cgroup_root) else { return };
+        let path = relative.to_string_lossy().to_string();
+
+        let cur = Counters {
+            usage_usec: read_keyed(&dir.join("cpu.stat"), "usage_usec").unwrap_or(0),
+            ..read_io_stat(&dir.join("io.stat"))
+        };
+        let mut node = CgroupNode {
+            name: if path.is_empty() { "/".to_string() } else { relative_name(relative) },
+            path: path.clone(),
+            children: Vec::new(),
+            cpu_percent: 0.0,
+            memory_current: read_u64(&dir.join("memory.current")),
+            memory_max: read_u64(&dir.join("memory.max")),
+            io_read_bytes_per_sec: 0.0,
+            io_write_bytes_per_sec: 0.0,
+            pids_current: read_u64(&dir.join("pids.current")),
+            procs: read_procs(&dir.join("cgroup.procs")),
+        };
+        if let (Some(elapsed), Some(prev)) = (elapsed, self.previous.get(&path)) {
+            let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+            node.cpu_percent = (cur.usage_usec.saturating_sub(prev.usage_usec) as f64 / (secs * 1e4)) as f32;
+            node.io_read_bytes_per_sec = cur.read_bytes.saturating_sub(prev.read_bytes) as f64 / secs;
+            node.io_write_bytes_per_sec = cur.write_bytes.saturating_sub(prev.write_bytes) as f64 / secs;
+        }
+        counters.insert(path.clone(), cur);
+
+        if depth < MAX_DEPTH {
+            if let Ok(entries) = fs::read_dir(dir) {
+                for entry in entries.flatten() {
+                    if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                        continue;
+                    }
+                    let child = entry.path();
+                    if let Ok(child_relative) = child.strip_prefix(cgroup_root) {
+                        node.children.push(child_relative.to_string_lossy().to_string());
+                    }
+                    self.walk(cgroup_root, &child, depth + 1, elapsed, nodes, counters);
+                }
+            }
+        }
+
+        nodes.insert(path, node);
+    }
+
+    /// False without a cgroup v2 hierarchy
+    pub fn available(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&CgroupNode> {
+        self.nodes.get(path)
+    }
+
+    /// Nodes in tree order with their depth, skipping the descendants of
+    /// collapsed paths
+    pub fn visible(&self, collapsed: &HashSet<String>) -> Vec<(&CgroupNode, usize)> {
+        let mut order = Vec::new();
+        if let Some(root) = self.nodes.get("") {
+            self.visit(root, 0, collapsed, &mut order);
+        }
+        order
+    }
+
+    /// Position of `path` in `visible(collapsed)`. A cgroup that is hidden
+    /// under a collapsed parent or went away resolves to its closest
+    /// visible ancestor.
+    pub fn visible_index(&self, collapsed: &HashSet<String>, path: &str) -> Option<usize> {
+        let visible = self.visible(collapsed);
+        let mut path = path;
+        loop {
+            if let Some(index) = visible.iter().position(|(node, _)| node.path == path) {
+                return Some(index);
+            }
+            if path.is_empty() {
+                return None;
+            }
+            path = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+        }
+    }
+
+    fn visit<'a>(&'a self, node: &'a CgroupNode, depth: usize, collapsed: &HashSet<String>, order: &mut Vec<(&'a CgroupNode, usize)>) {
+        order.push((node, depth));
+        if collapsed.contains(&node.path) {
+            return;
+        }
+        for child in node.children.iter().filter_map(|path| self.nodes.get(path)) {
+            self.visit(child, depth + 1, collapsed, order);
+        }
+    }
+
+    /// Processes of the cgroup and all its descendants
+    pub fn subtree_pids(&self, path: &str) -> Vec<u32> {
+        let mut pids = Vec::new();
+        let mut pending = vec![path.to_string()];
+        while let Some(path) = pending.pop() {
+            if let Some(node) = self.nodes.get(&path) {
+                pids.extend(&node.procs);
+                pending.extend(node.children.iter().cloned());
+            }
+        }
+        pids.sort_unstable();
+        pids
+    }
+}
+
+impl Default for CgroupMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn relative_name(relative: &Path) -> String {
+    relative.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// A number, or `None` for "max" and missing files
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Value of `key` in a flat keyed file such as cpu.stat
+fn read_keyed(path: &Path, key: &str) -> Option<u64> {
+    fs::read_to_string(path).ok()?
+        .lines()
+        .find_map(|line| {
+            let (k, v) = line.split_once(' ')?;
+            (k == key).then(|| v.trim().parse().ok()).flatten()
+        })
+}
+
+/// Bytes read and written, summed over all devices:
+/// `8:0 rbytes=1024 wbytes=4096 rios=1 wios=1 dbytes=0 dios=0`
+fn read_io_stat(path: &Path) -> Counters {
+    let mut counters = Counters::default();
+    let Ok(content) = fs::read_to_string(path) else {
+        return counters;
+    };
+    for field in content.split_whitespace() {
+        let Some((key, value)) = field.split_once('=') else { continue };
+        let value: u64 = value.parse().unwrap_or(0);
+        match key {
+            "rbytes" => counters.read_bytes += value,
+            "wbytes" => counters.write_bytes += value,
+            _ => {}
+        }
+    }
+    counters
+}
+
+fn read_procs(path: &Path) -> Vec<u32> {
+    fs::read_to_string(path)
+        .map(|content| content.lines().filter_map(|line| line.trim().parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_cgroup_tree_and_rates() {
+        let root = TestRoot::new("cgroup");
+        let service = "/sys/fs/cgroup/system.slice/docker.service";
+        root.write_file("/sys/fs/cgroup/cgroup.controllers", "cpu io memory pids\n").unwrap();
+        root.write_file("/sys/fs/cgroup/cpu.stat", "usage_usec 9000000\n").unwrap();
+        root.write_file("/sys/fs/cgroup/cgroup.procs", "1\n").unwrap();
+        root.write_file("/sys/fs/cgroup/system.slice/memory.current", "2097152\n").unwrap();
+        root.write_file(format!("{}/cpu.stat", service), "usage_usec 1000000\nuser_usec 800000\n").unwrap();
+        root.write_file(format!("{}/memory.current", service), "1048576\n").unwrap();
+        root.write_file(format!("{}/memory.max", service), "max\n").unwrap();
+        root.write_file(format!("{}/io.stat", service), "8:0 rbytes=1000 wbytes=0 rios=1 wios=0\n").unwrap();
+        root.write_file(format!("{}/pids.current", service), "12\n").unwrap();
+        root.write_file(format!("{}/cgroup.procs", service), "4242\n4243\n").unwrap();
+
+        let mut cgroups = CgroupMetrics::with_root(root.clone());
+        let start = Instant::now();
+        cgroups.refresh_at(start);
+        root.write_file(format!("{}/cpu.stat", service), "usage_usec 3000000\n").unwrap();
+        root.write_file(format!("{}/io.stat", service),
+            "8:0 rbytes=5000 wbytes=2000 rios=2 wios=1\n259:0 rbytes=0 wbytes=2000\n").unwrap();
+        cgroups.refresh_at(start + Duration::from_secs(2));
+
+        let docker = cgroups.get("system.slice/docker.service").unwrap();
+        assert_eq!(docker.cpu_percent, 100.0);
+        assert_eq!(docker.io_write_bytes_per_sec, 2000.0);
+        assert_eq!(docker.memory_current, Some(1048576));
+        assert_eq!(docker.memory_max, None);
+        assert_eq!(docker.pids_current, Some(12));
+
+        let visible: Vec<_> = cgroups.visible(&HashSet::new()).iter().map(|(n, d)| (n.name.clone(), *d)).collect();
+        assert_eq!(visible, vec![("/".to_string(), 0), ("system.slice".to_string(), 1), ("docker.service".to_string(), 2)]);
+        let collapsed = HashSet::from(["system.slice".to_string()]);
+        assert_eq!(cgroups.visible(&collapsed).len(), 2);
+        assert_eq!(cgroups.subtree_pids(""), vec![1, 4242, 4243]);
+
+        // Selections are kept by path and fall back to the closest visible ancestor
+        let service = "system.slice/docker.service";
+        assert_eq!(cgroups.visible_index(&HashSet::new(), service), Some(2));
+        assert_eq!(cgroups.visible_index(&collapsed, service), Some(1));
+        assert_eq!(cgroups.visible_index(&HashSet::new(), "system.slice/gone.service"), Some(1));
+    }
+}
@@ -0,0 +1,193 @@
+//! Where CPU time goes, from the jiffy counters in /proc/stat.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+
+/// Cumulative jiffies of one `cpu` line in /proc/stat
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// All time spent, counting guest time once: the kernel already
+    /// includes it in user and nice
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait
+            + self.irq + self.softirq + self.steal
+    }
+}
+
+/// Parse the aggregate ("cpu") and per-core ("cpu0", ...) lines of /proc/stat.
+/// Older kernels have fewer columns; missing ones count as zero.
+pub fn parse_proc_stat(content: &str) -> Vec<(String, CpuTimes)> {
+    content.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let values: Vec<u64> = fields.map(|v| v.parse().unwrap_or(0)).collect();
+            let value = |i: usize| values.get(i).copied().unwrap_or(0);
+            (values.len() >= 4).then(|| (name, CpuTimes {
+                user: value(0),
+                nice: value(1),
+                system: value(2),
+                idle: value(3),
+                iowait: value(4),
+                irq: value(5),
+                softirq: value(6),
+                steal: value(7),
+                guest: value(8),
+                guest_nice: value(9),
+            }))
+        })
+        .collect()
+}
+
+/// Share of one CPU's time per state between two refreshes, in percent.
+/// The fields add up to 100; user and nice exclude guest time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuBreakdown {
+    /// "cpu" for the aggregate, "cpu0", "cpu1", ... per core
+    pub name: String,
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+    /// Time spent running guests (VMs) on this host
+    pub guest: f32,
+}
+
+impl CpuBreakdown {
+    fn from_delta(name: String, prev: &CpuTimes, cur: &CpuTimes) -> Self {
+        let total = cur.total().saturating_sub(prev.total());
+        if total == 0 {
+            return Self { name, idle: 100.0, ..Self::default() };
+        }
+        let delta = |now: u64, before: u64| now.saturating_sub(before);
+        let percent = |jiffies: u64| jiffies as f32 * 100.0 / total as f32;
+
+        let guest = delta(cur.guest, prev.guest);
+        let guest_nice = delta(cur.guest_nice, prev.guest_nice);
+        Self {
+            name,
+            user: percent(delta(cur.user, prev.user).saturating_sub(guest)),
+            nice: percent(delta(cur.nice, prev.nice).saturating_sub(guest_nice)),
+            system: percent(delta(cur.system, prev.system)),
+            idle: percent(delta(cur.idle, prev.idle)),
+            iowait: percent(delta(cur.iowait, prev.iowait)),
+            irq: percent(delta(cur.irq, prev.irq)),
+            softirq: percent(delta(cur.softirq, prev.softirq)),
+            steal: percent(delta(cur.steal, prev.steal)),
+            guest: percent(guest + guest_nice),
+        }
+    }
+
+    /// Time not spent idle or waiting for I/O
+    pub fn busy(&self) -> f32 {
+        (100.0 - self.idle - self.iowait).max(0.0)
+    }
+}
+
+/// Turns successive /proc/stat reads into per-state CPU time shares
+pub struct CpuTimeMetrics {
+    root: FsRoot,
+    previous: HashMap<String, CpuTimes>,
+    total: Option<CpuBreakdown>,
+    cores: Vec<CpuBreakdown>,
+}
+
+impl CpuTimeMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            previous: HashMap::new(),
+            total: None,
+            cores: Vec::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        let Ok(content) = fs::read_to_string(self.root.path("/proc/stat")) else {
+            return;
+        };
+
+        let mut total = None;
+        let mut cores = Vec::new();
+        let mut stats = HashMap::new();
+        for (name, cur) in parse_proc_stat(&content) {
+            if let Some(prev) = self.previous.get(&name) {
+                let breakdown = CpuBreakdown::from_delta(name.clone(), prev, &cur);
+                if name == "cpu" {
+                    total = Some(breakdown);
+                } else {
+                    cores.push(breakdown);
+                }
+            }
+            stats.insert(name, cur);
+        }
+
+        // Kernel order is by core number already
+        self.total = total;
+        self.cores = cores;
+        self.previous = stats;
+    }
+
+    /// All CPUs together; `None` until two refreshes have happened
+    pub fn total(&self) -> Option<&CpuBreakdown> {
+        self.total.as_ref()
+    }
+
+    /// Online cores, in kernel order
+    pub fn cores(&self) -> &[CpuBreakdown] {
+        &self.cores
+    }
+}
+
+impl Default for CpuTimeMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_breakdown() {
+        let before = "cpu  1000 100 500 8000 200 10 20 50 300 0\n\
+            cpu0 500 50 250 4000 100 5 10 25 150 0\n\
+            intr 12345\n";
+        let after = "cpu  1400 100 600 8300 300 10 20 150 400 0\n";
+        let (prev, cur) = (parse_proc_stat(before), parse_proc_stat(after));
+        assert_eq!(prev.len(), 2);
+
+        // 1000 jiffies elapsed, 100 of user time was a guest
+        let cpu = CpuBreakdown::from_delta("cpu".to_string(), &prev[0].1, &cur[0].1);
+        assert_eq!(cpu.user, 30.0);
+        assert_eq!(cpu.guest, 10.0);
+        assert_eq!(cpu.steal, 10.0);
+        assert_eq!(cpu.iowait, 10.0);
+        assert_eq!(cpu.idle, 30.0);
+        assert_eq!(cpu.busy(), 60.0);
+    }
+}
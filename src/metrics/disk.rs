@@ -1,3 +1,7 @@
+use crate::fsroot::FsRoot;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::time::{Duration, Instant};
 use sysinfo::{Disks};
 
 pub struct DiskMetrics {
@@ -55,3 +59,278 @@ impl DiskInfo {
         (self.used_space() as f32 / self.total_space as f32) * 100.0
     }
 }
+
+/// Bytes per sector in /proc/diskstats, independent of the device
+const SECTOR_SIZE: u64 = 512;
+
+/// Cumulative counters of one block device from /proc/diskstats
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiskStat {
+    pub reads: u64,
+    pub sectors_read: u64,
+    pub read_ms: u64,
+    pub writes: u64,
+    pub sectors_written: u64,
+    pub write_ms: u64,
+    pub in_flight: u64,
+    /// Time the device had I/O in flight (`io_ticks`)
+    pub io_ms: u64,
+}
+
+/// Parse /proc/diskstats into (device name, counters)
+pub fn parse_diskstats(content: &str) -> Vec<(String, DiskStat)> {
+    content.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let name = fields.get(2)?;
+            let value = |i: usize| fields.get(i).and_then(|v| v.parse::<u64>().ok());
+            Some((name.to_string(), DiskStat {
+                reads: value(3)?,
+                sectors_read: value(5)?,
+                read_ms: value(6)?,
+                writes: value(7)?,
+                sectors_written: value(9)?,
+                write_ms: value(10)?,
+                in_flight: value(11)?,
+                io_ms: value(12)?,
+            }))
+        })
+        .collect()
+}
+
+/// I/O activity of one block device between two refreshes
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskIo {
+    pub name: String,
+    /// Mount points of the device and its partitions
+    pub mount_points: Vec<String>,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// Share of wall time the device was busy, in percent
+    pub utilization: f32,
+    /// Average time per completed request; `None` when nothing completed
+    pub avg_latency_ms: Option<f32>,
+    pub in_flight: u64,
+}
+
+impl DiskIo {
+    fn from_delta(name: String, prev: &DiskStat, cur: &DiskStat, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let delta = |now: u64, before: u64| now.saturating_sub(before);
+
+        let reads = delta(cur.reads, prev.reads);
+        let writes = delta(cur.writes, prev.writes);
+        let request_ms = delta(cur.read_ms, prev.read_ms) + delta(cur.write_ms, prev.write_ms);
+
+        Self {
+            name,
+            mount_points: Vec::new(),
+            read_bytes_per_sec: (delta(cur.sectors_read, prev.sectors_read) * SECTOR_SIZE) as f64 / secs,
+            write_bytes_per_sec: (delta(cur.sectors_written, prev.sectors_written) * SECTOR_SIZE) as f64 / secs,
+            read_iops: reads as f64 / secs,
+            write_iops: writes as f64 / secs,
+            utilization: (delta(cur.io_ms, prev.io_ms) as f64 / (secs * 10.0)).min(100.0) as f32,
+            avg_latency_ms: (reads + writes > 0).then(|| request_ms as f32 / (reads + writes) as f32),
+            in_flight: cur.in_flight,
+        }
+    }
+}
+
+/// Turns successive /proc/diskstats reads into per-device rates. Only whole
+/// devices are reported; partitions count towards their disk.
+pub struct DiskIoMetrics {
+    root: FsRoot,
+    previous: HashMap<String, DiskStat>,
+    last_refresh: Option<Instant>,
+    devices: Vec<DiskIo>,
+}
+
+impl DiskIoMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            previous: HashMap::new(),
+            last_refresh: None,
+            devices: Vec::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.refresh_at(Instant::now());
+    }
+
+    /// `refresh` with the time of the read given, for tests
+    pub(crate) fn refresh_at(&mut self, now: Instant) {
+        let Ok(content) = fs::read_to_string(self.root.path("/proc/diskstats")) else {
+            return;
+        };
+        let raw = parse_diskstats(&content);
+        let whole = self.whole_devices(&raw);
+        let stats: HashMap<String, DiskStat> = raw.into_iter()
+            .filter(|(name, _)| match &whole {
+                Some(whole) => whole.contains(name),
+                // Without sysfs, at least leave out loop and RAM disks
+                None => !name.starts_with("loop") && !name.starts_with("ram"),
+            })
+            .collect();
+
+        if let Some(last) = self.last_refresh {
+            let elapsed = now.duration_since(last);
+            let mounts = self.mount_points(whole.as_ref());
+            let mut devices: Vec<DiskIo> = stats.iter()
+                .filter_map(|(name, cur)| {
+                    let prev = self.previous.get(name)?;
+                    let mut io = DiskIo::from_delta(name.clone(), prev, cur, elapsed);
+                    io.mount_points = mounts.get(name).cloned().unwrap_or_default();
+                    Some(io)
+                })
+                .collect();
+            devices.sort_by(|a, b| a.name.cmp(&b.name));
+            self.devices = devices;
+        }
+
+        self.previous = stats;
+        self.last_refresh = Some(now);
+    }
+
+    /// Devices from the latest refresh, sorted by name
+    pub fn devices(&self) -> &[DiskIo] {
+        &self.devices
+    }
+
+    /// Whole disks from /sys/block, without loop and RAM disks that have
+    /// never done any I/O according to `stats`, the unfiltered diskstats
+    fn whole_devices(&self, stats: &[(String, DiskStat)]) -> Option<HashSet<String>> {
+        let entries = fs::read_dir(self.root.path("/sys/block")).ok()?;
+        Some(entries.flatten()
+            .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+            .filter(|name| {
+                let virtual_disk = name.starts_with("loop") || name.starts_with("ram");
+                !virtual_disk || stats.iter().any(|(stat_name, s)| stat_name == name && s.reads + s.writes > 0)
+            })
+            .collect())
+    }
+
+    /// Mount points by whole device. Partitions are attributed to the disk
+    /// whose name they extend (nvme0n1p2 -> nvme0n1), device-mapper names
+    /// to their dm-N node.
+    fn mount_points(&self, whole: Option<&HashSet<String>>) -> HashMap<String, Vec<String>> {
+        let mut mounts: HashMap<String, Vec<String>> = HashMap::new();
+        let Ok(content) = fs::read_to_string(self.root.path("/proc/mounts")) else {
+            return mounts;
+        };
+
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(source), Some(target)) = (fields.next(), fields.next()) else { continue };
+            let Some(device) = source.strip_prefix("/dev/") else { continue };
+
+            let device = match device.strip_prefix("mapper/") {
+                Some(dm_name) => match self.dm_node(dm_name) {
+                    Some(node) => node,
+                    None => continue,
+                },
+                None => device.to_string(),
+            };
+            let disk = match whole {
+                Some(whole) if !whole.contains(&device) => {
+                    match whole.iter().filter(|disk| device.starts_with(disk.as_str())).max_by_key(|disk| disk.len()) {
+                        Some(disk) => disk.clone(),
+                        None => continue,
+                    }
+                }
+                _ => device,
+            };
+
+            // Mount targets escape spaces as \040
+            let target = target.replace("\\040", " ");
+            let points = mounts.entry(disk).or_default();
+            if !points.contains(&target) {
+                points.push(target);
+            }
+        }
+
+        mounts
+    }
+
+    fn dm_node(&self, dm_name: &str) -> Option<String> {
+        let entries = fs::read_dir(self.root.path("/sys/block")).ok()?;
+        entries.flatten()
+            .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+            .filter(|name| name.starts_with("dm-"))
+            .find(|name| {
+                fs::read_to_string(self.root.path(format!("/sys/block/{}/dm/name", name)))
+                    .is_ok_and(|s| s.trim() == dm_name)
+            })
+    }
+}
+
+impl Default for DiskIoMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_disk_io_rates() {
+        let before = "259       0 nvme0n1 1000 0 20000 500 2000 0 40000 1500 0 1800 2000 0 0 0 0\n\
+            259       1 nvme0n1p1 10 0 80 5 0 0 0 0 0 5 5\n";
+        let after = "259       0 nvme0n1 1100 0 22048 600 2300 0 44096 2400 3 2300 3000 0 0 0 0\n";
+        let (prev, cur) = (parse_diskstats(before), parse_diskstats(after));
+        assert_eq!(prev.len(), 2);
+        assert_eq!(prev[1].0, "nvme0n1p1");
+
+        let io = DiskIo::from_delta("nvme0n1".to_string(), &prev[0].1, &cur[0].1, Duration::from_secs(2));
+        assert_eq!(io.read_bytes_per_sec, 2048.0 * 512.0 / 2.0);
+        assert_eq!(io.write_iops, 150.0);
+        assert_eq!(io.utilization, 25.0);
+        assert_eq!(io.avg_latency_ms, Some(1000.0 / 400.0));
+        assert_eq!(io.in_flight, 3);
+    }
+
+    #[test]
+    fn test_whole_devices_and_mount_points() {
+        let root = TestRoot::new("diskio");
+        for name in ["sda", "loop0", "loop1", "ram0", "dm-0"] {
+            root.write_file(format!("/sys/block/{}/size", name), "1000\n").unwrap();
+        }
+        root.write_file("/sys/block/dm-0/dm/name", "cryptroot\n").unwrap();
+        root.write_file("/proc/mounts", "/dev/sda1 / ext4 rw 0 0\n\
+            /dev/sda2 /boot\\040efi vfat rw 0 0\n\
+            /dev/mapper/cryptroot /home ext4 rw 0 0\n\
+            proc /proc proc rw 0 0\n").unwrap();
+        let diskstats = |reads: u64| format!(
+            "8 0 sda {reads} 0 8 1 0 0 0 0 0 1 1\n\
+             8 1 sda1 {reads} 0 8 1 0 0 0 0 0 1 1\n\
+             8 2 sda2 1 0 8 1 0 0 0 0 0 1 1\n\
+             7 0 loop0 {reads} 0 8 1 0 0 0 0 0 1 1\n\
+             7 1 loop1 0 0 0 0 0 0 0 0 0 0 0\n\
+             1 0 ram0 0 0 0 0 0 0 0 0 0 0 0\n\
+             253 0 dm-0 {reads} 0 8 1 0 0 0 0 0 1 1\n");
+
+        let mut disks = DiskIoMetrics::with_root(root.clone());
+        let start = Instant::now();
+        root.write_file("/proc/diskstats", diskstats(5)).unwrap();
+        disks.refresh_at(start);
+        root.write_file("/proc/diskstats", diskstats(15)).unwrap();
+        disks.refresh_at(start + Duration::from_secs(1));
+
+        // Partitions and idle loop and RAM disks are left out
+        let names: Vec<&str> = disks.devices().iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["dm-0", "loop0", "sda"]);
+        assert_eq!(disks.devices()[2].read_iops, 10.0);
+        assert_eq!(disks.devices()[0].mount_points, ["/home"]);
+        assert_eq!(disks.devices()[2].mount_points, ["/", "/boot efi"]);
+    }
+}
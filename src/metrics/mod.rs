@@ -1,7 +1,23 @@
 pub mod system;
+pub mod cpu_times;
 pub mod disk;
 pub mod network;
+pub mod sensors;
+pub mod pressure;
+pub mod cgroup;
+pub mod process_io;
+pub mod smaps;
+pub mod process_stat;
+pub mod users;
 
 pub use system::SystemMetrics;
-pub use disk::DiskMetrics;
-pub use network::NetworkMetrics;
+pub use cpu_times::{CpuBreakdown, CpuTimeMetrics};
+pub use disk::{DiskIo, DiskIoMetrics, DiskMetrics};
+pub use network::{NetworkInterface, NetworkMetrics};
+pub use cgroup::{CgroupMetrics, CgroupNode};
+pub use process_io::{ProcessIo, ProcessIoMetrics};
+pub use process_stat::{ProcessStat, ProcessStatMetrics};
+pub use users::UserNames;
+pub use smaps::{MemoryBreakdown, SmapsMetrics};
+pub use pressure::{CgroupPressure, Pressure, PressureLine, PressureMetrics, PressureResource};
+pub use sensors::{SensorKind, SensorMetrics, SensorReading};
@@ -1,32 +1,205 @@
-use sysinfo::Networks;
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+use std::net::Ipv6Addr;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
+/// `IFF_LOOPBACK` in /sys/class/net/<iface>/flags
+const IFF_LOOPBACK: u32 = 0x8;
+
+/// Cumulative counters of one interface from /sys/class/net/<iface>/statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetStat {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+impl NetStat {
+    fn read(iface_path: &Path) -> Option<Self> {
+        let stats = iface_path.join("statistics");
+        let value = |name: &str| read_u64(&stats.join(name));
+        Some(Self {
+            rx_bytes: value("rx_bytes")?,
+            tx_bytes: value("tx_bytes")?,
+            rx_packets: value("rx_packets").unwrap_or(0),
+            tx_packets: value("tx_packets").unwrap_or(0),
+            rx_errors: value("rx_errors").unwrap_or(0),
+            tx_errors: value("tx_errors").unwrap_or(0),
+            rx_dropped: value("rx_dropped").unwrap_or(0),
+            tx_dropped: value("tx_dropped").unwrap_or(0),
+        })
+    }
+}
+
+/// Traffic and link details of one network interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkInterface {
+    pub name: String,
+    /// Operational state as reported by the kernel ("up", "down", "unknown", ...)
+    pub operstate: String,
+    pub loopback: bool,
+    /// Negotiated link speed; `None` for virtual or disconnected links
+    pub speed_mbps: Option<u32>,
+    pub mtu: Option<u32>,
+    pub mac: Option<String>,
+    /// Addresses in CIDR notation, IPv4 first
+    pub addresses: Vec<String>,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    pub rx_errors_per_sec: f64,
+    pub tx_errors_per_sec: f64,
+    pub rx_drops_per_sec: f64,
+    pub tx_drops_per_sec: f64,
+    /// Totals since the interface came up
+    pub rx_total: u64,
+    pub tx_total: u64,
+}
+
+impl NetworkInterface {
+    /// "unknown" is what many virtual and tunnel devices report while passing traffic
+    pub fn is_up(&self) -> bool {
+        self.operstate == "up" || self.operstate == "unknown"
+    }
+
+    fn set_rates(&mut self, prev: &NetStat, cur: &NetStat, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+
+        self.rx_bytes_per_sec = rate(cur.rx_bytes, prev.rx_bytes);
+        self.tx_bytes_per_sec = rate(cur.tx_bytes, prev.tx_bytes);
+        self.rx_packets_per_sec = rate(cur.rx_packets, prev.rx_packets);
+        self.tx_packets_per_sec = rate(cur.tx_packets, prev.tx_packets);
+        self.rx_errors_per_sec = rate(cur.rx_errors, prev.rx_errors);
+        self.tx_errors_per_sec = rate(cur.tx_errors, prev.tx_errors);
+        self.rx_drops_per_sec = rate(cur.rx_dropped, prev.rx_dropped);
+        self.tx_drops_per_sec = rate(cur.tx_dropped, prev.tx_dropped);
+    }
+}
+
+/// Turns successive reads of the interface counters in sysfs into rates
 pub struct NetworkMetrics {
-    networks: Networks,
+    root: FsRoot,
+    previous: HashMap<String, NetStat>,
+    last_refresh: Option<Instant>,
+    interfaces: Vec<NetworkInterface>,
 }
 
 impl NetworkMetrics {
     pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
         Self {
-            networks: Networks::new_with_refreshed_list(),
+            root,
+            previous: HashMap::new(),
+            last_refresh: None,
+            interfaces: Vec::new(),
         }
     }
 
     pub fn refresh(&mut self) {
-        self.networks.refresh();
+        self.refresh_at(Instant::now());
+    }
+
+    /// `refresh` with the time of the read given, for tests
+    pub(crate) fn refresh_at(&mut self, now: Instant) {
+        let Ok(entries) = fs::read_dir(self.root.path("/sys/class/net")) else {
+            return;
+        };
+        let elapsed = self.last_refresh.map(|last| now.duration_since(last));
+        let mut addresses = self.read_addresses();
+
+        let mut stats = HashMap::new();
+        let mut interfaces = Vec::new();
+        for entry in entries.flatten() {
+            let Some(name) = entry.file_name().to_str().map(|s| s.to_string()) else { continue };
+            let path = entry.path();
+            let Some(stat) = NetStat::read(&path) else { continue };
+
+            let mut iface = Self::read_link(&name, &path);
+            iface.addresses = addresses.remove(&name).unwrap_or_default();
+            iface.rx_total = stat.rx_bytes;
+            iface.tx_total = stat.tx_bytes;
+            if let (Some(elapsed), Some(prev)) = (elapsed, self.previous.get(&name)) {
+                iface.set_rates(prev, &stat, elapsed);
+            }
+
+            stats.insert(name, stat);
+            interfaces.push(iface);
+        }
+
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        self.interfaces = interfaces;
+        self.previous = stats;
+        self.last_refresh = Some(now);
     }
 
-    pub fn list(&self) -> Vec<NetworkInfo> {
-        self.networks.iter()
-            .map(|(name, network)| NetworkInfo {
-                name: name.to_string(),
-                received: network.received(),
-                transmitted: network.transmitted(),
-                packets_received: network.packets_received(),
-                packets_transmitted: network.packets_transmitted(),
-                errors_on_received: network.errors_on_received(),
-                errors_on_transmitted: network.errors_on_transmitted(),
-            })
-            .collect()
+    /// Interfaces from the latest refresh, sorted by name
+    pub fn interfaces(&self) -> &[NetworkInterface] {
+        &self.interfaces
+    }
+
+    fn read_link(name: &str, path: &Path) -> NetworkInterface {
+        let read = |file: &str| fs::read_to_string(path.join(file))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let flags = read("flags")
+            .and_then(|s| u32::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .unwrap_or(0);
+
+        NetworkInterface {
+            name: name.to_string(),
+            operstate: read("operstate").unwrap_or_else(|| "unknown".to_string()),
+            loopback: flags & IFF_LOOPBACK != 0,
+            // Reading speed fails on links that are down; -1 means unknown
+            speed_mbps: read("speed").and_then(|s| s.parse::<i64>().ok())
+                .filter(|speed| *speed > 0)
+                .map(|speed| speed as u32),
+            mtu: read("mtu").and_then(|s| s.parse().ok()),
+            mac: read("address").filter(|mac| mac != "00:00:00:00:00:00"),
+            addresses: Vec::new(),
+            rx_bytes_per_sec: 0.0,
+            tx_bytes_per_sec: 0.0,
+            rx_packets_per_sec: 0.0,
+            tx_packets_per_sec: 0.0,
+            rx_errors_per_sec: 0.0,
+            tx_errors_per_sec: 0.0,
+            rx_drops_per_sec: 0.0,
+            tx_drops_per_sec: 0.0,
+            rx_total: 0,
+            tx_total: 0,
+        }
+    }
+
+    /// Addresses by interface: IPv4 from the interface address list
+    /// (getifaddrs), IPv6 from if_inet6. getifaddrs reports the running
+    /// system, so under another root only IPv6 addresses are known.
+    fn read_addresses(&self) -> HashMap<String, Vec<String>> {
+        let mut addresses: HashMap<String, Vec<String>> = HashMap::new();
+
+        if self.root.is_host() {
+            for (iface, address) in read_ipv4_addresses() {
+                addresses.entry(iface).or_default().push(address);
+            }
+        }
+
+        let if_inet6 = fs::read_to_string(self.root.path("/proc/net/if_inet6")).unwrap_or_default();
+        for (iface, address) in parse_if_inet6(&if_inet6) {
+            addresses.entry(iface).or_default().push(address);
+        }
+
+        addresses
     }
 }
 
@@ -36,13 +209,78 @@ impl Default for NetworkMetrics {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct NetworkInfo {
-    pub name: String,
-    pub received: u64,
-    pub transmitted: u64,
-    pub packets_received: u64,
-    pub packets_transmitted: u64,
-    pub errors_on_received: u64,
-    pub errors_on_transmitted: u64,
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// IPv4 addresses with their prefix length, per interface
+#[cfg(unix)]
+fn read_ipv4_addresses() -> Vec<(String, String)> {
+    let Ok(interfaces) = nix::ifaddrs::getifaddrs() else {
+        return Vec::new();
+    };
+    interfaces
+        .filter_map(|ifaddr| {
+            let address = ifaddr.address?.as_sockaddr_in()?.ip();
+            let prefix = ifaddr.netmask
+                .and_then(|mask| mask.as_sockaddr_in().map(|mask| u32::from(mask.ip()).count_ones()))
+                .unwrap_or(32);
+            Some((ifaddr.interface_name, format!("{}/{}", address, prefix)))
+        })
+        .collect()
+}
+
+#[cfg(not(unix))]
+fn read_ipv4_addresses() -> Vec<(String, String)> {
+    Vec::new()
+}
+
+/// IPv6 addresses from /proc/net/if_inet6: address, index, prefix length,
+/// scope, flags and interface name per line
+pub fn parse_if_inet6(content: &str) -> Vec<(String, String)> {
+    content.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let raw = u128::from_str_radix(fields.first()?, 16).ok()?;
+            let prefix = u8::from_str_radix(fields.get(2)?, 16).ok()?;
+            let iface = fields.get(5)?;
+            Some((iface.to_string(), format!("{}/{}", Ipv6Addr::from(raw), prefix)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_network_rates_and_addresses() {
+        let root = TestRoot::new("net");
+        let eth = "/sys/class/net/eth0";
+        root.write_file(format!("{}/operstate", eth), "up\n").unwrap();
+        root.write_file(format!("{}/speed", eth), "1000\n").unwrap();
+        root.write_file(format!("{}/mtu", eth), "1500\n").unwrap();
+        root.write_file(format!("{}/address", eth), "3c:7c:3f:aa:bb:cc\n").unwrap();
+        root.write_file(format!("{}/flags", eth), "0x1003\n").unwrap();
+        root.write_file(format!("{}/statistics/rx_bytes", eth), "1000\n").unwrap();
+        root.write_file(format!("{}/statistics/tx_bytes", eth), "500\n").unwrap();
+        root.write_file("/proc/net/if_inet6",
+            "fe800000000000003e7c3ffffeaabbcc 02 40 20 80     eth0\n").unwrap();
+
+        let mut metrics = NetworkMetrics::with_root(root.clone());
+        let start = Instant::now();
+        metrics.refresh_at(start);
+        root.write_file(format!("{}/statistics/rx_bytes", eth), "3000\n").unwrap();
+        metrics.refresh_at(start + Duration::from_secs(2));
+
+        let eth0 = &metrics.interfaces()[0];
+        assert!(eth0.is_up() && !eth0.loopback);
+        assert_eq!(eth0.speed_mbps, Some(1000));
+        assert_eq!(eth0.mac.as_deref(), Some("3c:7c:3f:aa:bb:cc"));
+        // IPv4 addresses come from the running system, not the test root
+        assert_eq!(eth0.addresses, vec!["fe80::3e7c:3fff:feaa:bbcc/64"]);
+        assert_eq!(eth0.rx_bytes_per_sec, 1000.0);
+        assert_eq!(eth0.tx_bytes_per_sec, 0.0);
+    }
 }
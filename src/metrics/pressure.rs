@@ -0,0 +1,262 @@
+//! Pressure Stall Information from /proc/pressure and cgroup v2.
+//!
+//! "some" is the share of time at least one task was stalled on the
+//! resource, "full" the share of time all non-idle tasks were stalled at
+//! once. Averages are percentages over 10, 60 and 300 seconds.
+
+use crate::fsroot::FsRoot;
+use crate::metrics::cgroup::{cgroup_root, MAX_DEPTH};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Per-cgroup pressure is re-read at most this often: walking the tree
+/// reads four files per cgroup, and avg10 moves little in between
+const CGROUP_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureResource {
+    Cpu,
+    Memory,
+    Io,
+    Irq,
+}
+
+impl PressureResource {
+    pub const ALL: [PressureResource; 4] = [
+        PressureResource::Cpu,
+        PressureResource::Memory,
+        PressureResource::Io,
+        PressureResource::Irq,
+    ];
+
+    /// File name under /proc/pressure; cgroups use "<name>.pressure"
+    pub fn name(&self) -> &'static str {
+        match self {
+            PressureResource::Cpu => "cpu",
+            PressureResource::Memory => "memory",
+            PressureResource::Io => "io",
+            PressureResource::Irq => "irq",
+        }
+    }
+}
+
+/// One "some" or "full" line
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureLine {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// Total stall time in microseconds
+    pub total_us: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pressure {
+    /// Not reported for irq
+    pub some: Option<PressureLine>,
+    /// Not reported for cpu before Linux 5.13
+    pub full: Option<PressureLine>,
+}
+
+/// Parse a pressure file:
+/// `some avg10=0.12 avg60=0.05 avg300=0.01 total=123456`
+pub fn parse_pressure(content: &str) -> Pressure {
+    let mut pressure = Pressure::default();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut parsed = PressureLine::default();
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else { continue };
+            match key {
+                "avg10" => parsed.avg10 = value.parse().unwrap_or(0.0),
+                "avg60" => parsed.avg60 = value.parse().unwrap_or(0.0),
+                "avg300" => parsed.avg300 = value.parse().unwrap_or(0.0),
+                "total" => parsed.total_us = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => pressure.some = Some(parsed),
+            Some("full") => pressure.full = Some(parsed),
+            _ => {}
+        }
+    }
+    pressure
+}
+
+/// Pressure of every resource a directory reports, from files named
+/// `<resource><suffix>`
+fn read_resources(dir: &Path, suffix: &str) -> Vec<(PressureResource, Pressure)> {
+    PressureResource::ALL.iter()
+        .filter_map(|resource| {
+            let content = fs::read_to_string(dir.join(format!("{}{}", resource.name(), suffix))).ok()?;
+            Some((*resource, parse_pressure(&content)))
+        })
+        .collect()
+}
+
+/// Pressure of one cgroup
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupPressure {
+    /// Path below the cgroup root, e.g. "system.slice/docker.service"
+    pub path: String,
+    pub resources: Vec<(PressureResource, Pressure)>,
+}
+
+impl CgroupPressure {
+    pub fn get(&self, resource: PressureResource) -> Option<&Pressure> {
+        self.resources.iter().find(|(r, _)| *r == resource).map(|(_, p)| p)
+    }
+
+    /// Highest "some" avg10 over all resources, for ranking
+    pub fn worst_some(&self) -> f32 {
+        self.resources.iter()
+            .filter_map(|(_, p)| p.some.map(|line| line.avg10))
+            .fold(0.0, f32::max)
+    }
+}
+
+pub struct PressureMetrics {
+    root: FsRoot,
+    system: Vec<(PressureResource, Pressure)>,
+    cgroups: Vec<CgroupPressure>,
+    last_cgroup_read: Option<Instant>,
+}
+
+impl PressureMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            system: Vec::new(),
+            cgroups: Vec::new(),
+            last_cgroup_read: None,
+        }
+    }
+
+    /// Read system-wide pressure, and per-cgroup pressure every
+    /// `CGROUP_INTERVAL`
+    pub fn refresh(&mut self) {
+        self.refresh_at(Instant::now());
+    }
+
+    /// `refresh` with the time of the read given, for tests
+    pub(crate) fn refresh_at(&mut self, now: Instant) {
+        self.system = read_resources(&self.root.path("/proc/pressure"), "");
+
+        if self.last_cgroup_read.is_some_and(|last| now.duration_since(last) < CGROUP_INTERVAL) {
+            return;
+        }
+        let mut cgroups = Vec::new();
+        if let Some(cgroup_root) = cgroup_root(&self.root) {
+            collect_cgroups(&cgroup_root, &cgroup_root, 0, &mut cgroups);
+        }
+        cgroups.sort_by(|a, b| a.path.cmp(&b.path));
+        self.cgroups = cgroups;
+        self.last_cgroup_read = Some(now);
+    }
+
+    /// False on kernels without PSI or with `psi=0`
+    pub fn available(&self) -> bool {
+        !self.system.is_empty()
+    }
+
+    /// System-wide pressure per resource
+    pub fn system(&self) -> &[(PressureResource, Pressure)] {
+        &self.system
+    }
+
+    pub fn get(&self, resource: PressureResource) -> Option<&Pressure> {
+        self.system.iter().find(|(r, _)| *r == resource).map(|(_, p)| p)
+    }
+
+    /// Cgroups with pressure files, sorted by path
+    pub fn cgroups(&self) -> &[CgroupPressure] {
+        &self.cgroups
+    }
+}
+
+impl Default for PressureMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every cgroup below `dir` with pressure files, down to `MAX_DEPTH`:
+/// Kubernetes puts containers four levels down
+/// (kubepods.slice/kubepods-burstable.slice/<pod>.slice/<container>.scope).
+fn collect_cgroups(cgroup_root: &Path, dir: &Path, depth: usize, out: &mut Vec<CgroupPressure>) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+
+        let resources = read_resources(&path, ".pressure");
+        if !resources.is_empty() {
+            if let Ok(relative) = path.strip_prefix(cgroup_root) {
+                out.push(CgroupPressure {
+                    path: relative.to_string_lossy().to_string(),
+                    resources,
+                });
+            }
+        }
+        collect_cgroups(cgroup_root, &path, depth + 1, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_system_and_cgroup_pressure() {
+        let root = TestRoot::new("psi");
+        root.write_file("/proc/pressure/memory",
+            "some avg10=12.50 avg60=4.00 avg300=1.00 total=987654\n\
+             full avg10=6.25 avg60=2.00 avg300=0.50 total=123456\n").unwrap();
+        root.write_file("/proc/pressure/irq", "full avg10=0.00 avg60=0.00 avg300=0.00 total=42\n").unwrap();
+        root.write_file("/sys/fs/cgroup/cgroup.controllers", "cpu io memory\n").unwrap();
+        root.write_file("/sys/fs/cgroup/system.slice/docker.service/io.pressure",
+            "some avg10=30.00 avg60=10.00 avg300=3.00 total=1\n\
+             full avg10=20.00 avg60=8.00 avg300=2.00 total=1\n").unwrap();
+        let pod = "kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1234.slice";
+        root.write_file(format!("/sys/fs/cgroup/{}/cri-containerd-abcd.scope/cpu.pressure", pod),
+            "some avg10=40.00 avg60=10.00 avg300=3.00 total=1\n").unwrap();
+
+        let mut psi = PressureMetrics::with_root(root.clone());
+        let start = Instant::now();
+        psi.refresh_at(start);
+        assert!(psi.available());
+        let memory = psi.get(PressureResource::Memory).unwrap();
+        assert_eq!(memory.full.unwrap().avg10, 6.25);
+        assert_eq!(memory.some.unwrap().total_us, 987654);
+        assert_eq!(psi.get(PressureResource::Irq).unwrap().some, None);
+
+        let paths: Vec<&str> = psi.cgroups().iter().map(|cgroup| cgroup.path.as_str()).collect();
+        assert_eq!(paths, [format!("{}/cri-containerd-abcd.scope", pod).as_str(), "system.slice/docker.service"]);
+        assert_eq!(psi.cgroups()[0].worst_some(), 40.0);
+        assert_eq!(psi.cgroups()[1].worst_some(), 30.0);
+
+        // System-wide pressure is read on every refresh, cgroups only
+        // every CGROUP_INTERVAL
+        root.write_file("/proc/pressure/memory", "some avg10=1.00 avg60=1.00 avg300=1.00 total=1\n").unwrap();
+        root.write_file("/sys/fs/cgroup/system.slice/docker.service/io.pressure",
+            "some avg10=5.00 avg60=1.00 avg300=1.00 total=2\n").unwrap();
+        psi.refresh_at(start + Duration::from_secs(1));
+        assert_eq!(psi.get(PressureResource::Memory).unwrap().some.unwrap().avg10, 1.0);
+        assert_eq!(psi.cgroups()[1].worst_some(), 30.0);
+        psi.refresh_at(start + CGROUP_INTERVAL);
+        assert_eq!(psi.cgroups()[1].worst_some(), 5.0);
+    }
+}
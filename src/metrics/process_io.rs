@@ -0,0 +1,154 @@
+//! Per-process disk I/O from /proc/<pid>/io.
+//!
+//! read_bytes and write_bytes count what reached the block layer, so page
+//! cache hits are excluded. Reading another user's file needs the same
+//! rights as ptrace; such processes have no I/O figures.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Cumulative counters of one /proc/<pid>/io
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IoCounters {
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub cancelled_write_bytes: u64,
+    /// read() and write() family syscalls, including those served from cache
+    pub syscr: u64,
+    pub syscw: u64,
+}
+
+pub fn parse_proc_io(content: &str) -> IoCounters {
+    let mut counters = IoCounters::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim().parse().unwrap_or(0);
+        match key {
+            "read_bytes" => counters.read_bytes = value,
+            "write_bytes" => counters.write_bytes = value,
+            "cancelled_write_bytes" => counters.cancelled_write_bytes = value,
+            "syscr" => counters.syscr = value,
+            "syscw" => counters.syscw = value,
+            _ => {}
+        }
+    }
+    counters
+}
+
+/// I/O of one process per second since the previous refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessIo {
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Writes dropped before reaching disk, e.g. by truncating dirty files
+    pub cancelled_write_bytes_per_sec: f64,
+    pub read_syscalls_per_sec: f64,
+    pub write_syscalls_per_sec: f64,
+}
+
+impl ProcessIo {
+    fn from_delta(prev: &IoCounters, cur: &IoCounters, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+        Self {
+            read_bytes_per_sec: rate(cur.read_bytes, prev.read_bytes),
+            write_bytes_per_sec: rate(cur.write_bytes, prev.write_bytes),
+            cancelled_write_bytes_per_sec: rate(cur.cancelled_write_bytes, prev.cancelled_write_bytes),
+            read_syscalls_per_sec: rate(cur.syscr, prev.syscr),
+            write_syscalls_per_sec: rate(cur.syscw, prev.syscw),
+        }
+    }
+}
+
+pub struct ProcessIoMetrics {
+    root: FsRoot,
+    previous: HashMap<u32, IoCounters>,
+    last_refresh: Option<Instant>,
+    rates: HashMap<u32, ProcessIo>,
+}
+
+impl ProcessIoMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            previous: HashMap::new(),
+            last_refresh: None,
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Read the counters of `pids`; processes that exited are dropped
+    pub fn refresh(&mut self, pids: impl IntoIterator<Item = u32>) {
+        self.refresh_at(pids, Instant::now());
+    }
+
+    /// `refresh` with the time of the read given, for tests
+    pub(crate) fn refresh_at(&mut self, pids: impl IntoIterator<Item = u32>, now: Instant) {
+        let elapsed = self.last_refresh.map(|last| now.duration_since(last));
+
+        let mut counters = HashMap::new();
+        let mut rates = HashMap::new();
+        for pid in pids {
+            let Ok(content) = fs::read_to_string(self.root.path(format!("/proc/{}/io", pid))) else {
+                continue;
+            };
+            let cur = parse_proc_io(&content);
+            // New processes start at zero until their second sample
+            let rate = match (elapsed, self.previous.get(&pid)) {
+                (Some(elapsed), Some(prev)) => ProcessIo::from_delta(prev, &cur, elapsed),
+                _ => ProcessIo::default(),
+            };
+            rates.insert(pid, rate);
+            counters.insert(pid, cur);
+        }
+
+        self.previous = counters;
+        self.rates = rates;
+        self.last_refresh = Some(now);
+    }
+
+    /// `None` when /proc/<pid>/io is not readable
+    pub fn get(&self, pid: u32) -> Option<ProcessIo> {
+        self.rates.get(&pid).copied()
+    }
+}
+
+impl Default for ProcessIoMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_process_io_rates() {
+        let root = TestRoot::new("procio");
+        let io = |read: u64, write: u64, syscr: u64| format!(
+            "rchar: 1\nwchar: 1\nsyscr: {}\nsyscw: 10\nread_bytes: {}\nwrite_bytes: {}\ncancelled_write_bytes: 0\n",
+            syscr, read, write);
+        root.write_file("/proc/42/io", io(4096, 0, 100)).unwrap();
+
+        let mut metrics = ProcessIoMetrics::with_root(root.clone());
+        let start = Instant::now();
+        metrics.refresh_at([42, 43], start);
+        assert_eq!(metrics.get(42), Some(ProcessIo::default()));
+        assert_eq!(metrics.get(43), None);
+
+        root.write_file("/proc/42/io", io(4096 + 2 * 1048576, 8192, 300)).unwrap();
+        metrics.refresh_at([42], start + Duration::from_secs(2));
+        let rate = metrics.get(42).unwrap();
+        assert_eq!(rate.read_bytes_per_sec, 1048576.0);
+        assert_eq!(rate.write_bytes_per_sec, 4096.0);
+        assert_eq!(rate.read_syscalls_per_sec, 100.0);
+    }
+}
@@ -0,0 +1,152 @@
+//! Scheduling and lifetime details per process from /proc/<pid>/stat.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Clock ticks per second of the times in /proc/<pid>/stat (USER_HZ);
+/// 100 if sysconf cannot tell
+fn clock_ticks() -> u64 {
+    nix::unistd::sysconf(nix::unistd::SysconfVar::CLK_TCK).ok().flatten()
+        .filter(|&ticks| ticks > 0)
+        .map_or(100, |ticks| ticks as u64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProcessStat {
+    /// R running, S sleeping, D uninterruptible, Z zombie, T stopped, I idle
+    pub state: char,
+    pub ppid: u32,
+    /// Kernel priority; 20 + nice for normal tasks, negative for real-time
+    pub priority: i64,
+    pub nice: i64,
+    pub threads: u64,
+    /// Seconds since the Unix epoch; `None` without the boot time
+    pub start_time: Option<u64>,
+    /// User plus system time
+    pub cpu_time: Duration,
+    /// Open file descriptors, when counting is enabled and permitted
+    pub fds: Option<usize>,
+}
+
+impl ProcessStat {
+    /// Time since the process started
+    pub fn elapsed(&self) -> Option<Duration> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        Some(Duration::from_secs(now.saturating_sub(self.start_time?)))
+    }
+}
+
+/// Parse /proc/<pid>/stat; `boot_time` is the `btime` of /proc/stat and
+/// `user_hz` the clock ticks per second. Fields are counted after the
+/// command name, which may contain spaces and parentheses.
+pub fn parse_stat(content: &str, boot_time: Option<u64>, user_hz: u64) -> Option<ProcessStat> {
+    let after_comm = &content[content.rfind(')')? + 1..];
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    let number = |i: usize| fields.get(i).and_then(|v| v.parse::<i64>().ok());
+    let ticks = |i: usize| number(i).unwrap_or(0).max(0) as u64;
+
+    Some(ProcessStat {
+        state: fields.first()?.chars().next()?,
+        ppid: number(1)? as u32,
+        priority: number(15).unwrap_or(0),
+        nice: number(16).unwrap_or(0),
+        threads: ticks(17),
+        start_time: boot_time.map(|boot| boot + ticks(19) / user_hz),
+        cpu_time: Duration::from_millis((ticks(11) + ticks(12)) * 1000 / user_hz),
+        fds: None,
+    })
+}
+
+pub struct ProcessStatMetrics {
+    root: FsRoot,
+    boot_time: Option<u64>,
+    user_hz: u64,
+    /// Counting descriptors lists every /proc/<pid>/fd, so it is opt-in
+    count_fds: bool,
+    stats: HashMap<u32, ProcessStat>,
+}
+
+impl ProcessStatMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        let boot_time = fs::read_to_string(root.path("/proc/stat")).ok()
+            .and_then(|content| content.lines()
+                .find_map(|line| line.strip_prefix("btime "))
+                .and_then(|v| v.trim().parse().ok()));
+        Self {
+            root,
+            boot_time,
+            user_hz: clock_ticks(),
+            count_fds: false,
+            stats: HashMap::new(),
+        }
+    }
+
+    pub fn set_count_fds(&mut self, count_fds: bool) {
+        self.count_fds = count_fds;
+    }
+
+    /// Read `pids`; processes that exited are dropped
+    pub fn refresh(&mut self, pids: impl IntoIterator<Item = u32>) {
+        self.stats = pids.into_iter()
+            .filter_map(|pid| {
+                let content = fs::read_to_string(self.root.path(format!("/proc/{}/stat", pid))).ok()?;
+                let mut stat = parse_stat(&content, self.boot_time, self.user_hz)?;
+                if self.count_fds {
+                    stat.fds = fs::read_dir(self.root.path(format!("/proc/{}/fd", pid)))
+                        .ok()
+                        .map(|entries| entries.count());
+                }
+                Some((pid, stat))
+            })
+            .collect();
+    }
+
+    pub fn get(&self, pid: u32) -> Option<ProcessStat> {
+        self.stats.get(&pid).copied()
+    }
+}
+
+impl Default for ProcessStatMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_process_stat() {
+        let root = TestRoot::new("procstat");
+        root.write_file("/proc/stat", "cpu  1 2 3 4\nbtime 1700000000\n").unwrap();
+        // utime 250 + stime 50 ticks, priority 25, nice 5, 4 threads, started 120s after boot
+        root.write_file("/proc/300/stat",
+            "300 (web (worker)) S 299 300 300 0 -1 4194560 100 0 0 0 250 50 0 0 25 5 4 0 12000 1000000 200").unwrap();
+        root.write_file("/proc/300/fd/0", "").unwrap();
+        root.write_file("/proc/300/fd/1", "").unwrap();
+
+        let mut stats = ProcessStatMetrics::with_root(root.clone());
+        stats.user_hz = 100;
+        stats.set_count_fds(true);
+        stats.refresh([300, 301]);
+        let stat = stats.get(300).unwrap();
+        assert_eq!((stat.state, stat.ppid, stat.priority, stat.nice, stat.threads), ('S', 299, 25, 5, 4));
+        assert_eq!(stat.start_time, Some(1700000120));
+        assert_eq!(stat.cpu_time, Duration::from_secs(3));
+        assert_eq!(stat.fds, Some(2));
+        assert!(stats.get(301).is_none());
+
+        // Times scale with the clock tick rate
+        let content = fs::read_to_string(root.path("/proc/300/stat")).unwrap();
+        let stat = parse_stat(&content, Some(1700000000), 250).unwrap();
+        assert_eq!((stat.start_time, stat.cpu_time), (Some(1700000048), Duration::from_millis(1200)));
+    }
+}
@@ -0,0 +1,321 @@
+//! Board sensors from hwmon and thermal zones.
+//!
+//! hwmon drivers (coretemp, k10temp, zenpower, nvme, nct6775, acpitz, ...)
+//! report temperatures in millidegrees, fans in RPM and voltages in
+//! millivolts. Thermal zones add what hwmon does not cover, such as
+//! x86_pkg_temp and ARM SoC zones. GPU drivers are left to the GPU backends.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// hwmon drivers of GPUs, read by the GPU backends
+const GPU_DRIVERS: &[&str] = &["amdgpu", "radeon", "nouveau", "i915", "xe"];
+
+/// Drivers and thermal zones that measure the CPU package or cores
+const CPU_CHIPS: &[&str] = &[
+    "coretemp", "k10temp", "zenpower", "x86_pkg_temp", "cpu_thermal", "cpu-thermal", "soc_thermal",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+}
+
+impl SensorKind {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    /// Driver name. When several devices share it, the address of the
+    /// device the hwmon node belongs to is appended ("nvme@0000:02:00.0",
+    /// "jc42@0-0018"), so the key survives hwmon renumbering.
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    /// °C, RPM or V
+    pub value: f32,
+    /// Critical temperature limit, where the driver reports one
+    pub critical: Option<f32>,
+}
+
+impl SensorReading {
+    /// Stable identifier for history and alert cooldowns
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.chip, self.label)
+    }
+
+    pub fn is_cpu(&self) -> bool {
+        let driver = self.chip.split(['@', '-']).next().unwrap_or(&self.chip);
+        CPU_CHIPS.contains(&self.chip.as_str()) || CPU_CHIPS.contains(&driver)
+    }
+}
+
+pub struct SensorMetrics {
+    root: FsRoot,
+    readings: Vec<SensorReading>,
+}
+
+impl SensorMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            readings: Vec::new(),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        let mut readings = self.read_hwmon();
+        let chips: Vec<String> = readings.iter().map(|r| r.chip.clone()).collect();
+        readings.extend(self.read_thermal_zones()
+            .into_iter()
+            .filter(|zone| !chips.contains(&zone.chip)));
+        readings.sort_by_key(|reading| reading.kind);
+        self.readings = readings;
+    }
+
+    /// Readings from the latest refresh: temperatures, then fans, then voltages
+    pub fn readings(&self) -> &[SensorReading] {
+        &self.readings
+    }
+
+    pub fn temperatures(&self) -> impl Iterator<Item = &SensorReading> {
+        self.readings.iter().filter(|r| r.kind == SensorKind::Temperature)
+    }
+
+    /// Hottest CPU temperature, if any CPU sensor is present
+    pub fn cpu_temperature(&self) -> Option<f32> {
+        self.temperatures()
+            .filter(|r| r.is_cpu())
+            .map(|r| r.value)
+            .reduce(f32::max)
+    }
+
+    fn read_hwmon(&self) -> Vec<SensorReading> {
+        let Ok(entries) = fs::read_dir(self.root.path("/sys/class/hwmon")) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        // hwmon10 after hwmon9
+        paths.sort_by_key(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.trim_start_matches("hwmon").parse::<u32>().ok())
+                .unwrap_or(u32::MAX)
+        });
+
+        let chips: Vec<(PathBuf, String, Option<String>)> = paths.into_iter()
+            .filter_map(|path| {
+                let name = read_trimmed(&path.join("name"))?;
+                if GPU_DRIVERS.contains(&name.as_str()) {
+                    return None;
+                }
+                let device = device_id(&path.join("device"));
+                Some((path, name, device))
+            })
+            .collect();
+        let mut shared: HashMap<&str, usize> = HashMap::new();
+        for (_, name, _) in &chips {
+            *shared.entry(name.as_str()).or_insert(0) += 1;
+        }
+
+        // Devices without a device link fall back to their order ("acpitz-1")
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        let mut readings = Vec::new();
+        for (path, name, device) in &chips {
+            let count = seen.entry(name.as_str()).or_insert(0);
+            let chip = match device {
+                _ if shared[name.as_str()] == 1 => name.clone(),
+                Some(device) => format!("{}@{}", name, device),
+                None if *count == 0 => name.clone(),
+                None => format!("{}-{}", name, count),
+            };
+            *count += 1;
+
+            readings.extend(read_channels(path, &chip, "temp", SensorKind::Temperature));
+            readings.extend(read_channels(path, &chip, "fan", SensorKind::Fan));
+            readings.extend(read_channels(path, &chip, "in", SensorKind::Voltage));
+        }
+        readings
+    }
+
+    fn read_thermal_zones(&self) -> Vec<SensorReading> {
+        let Ok(entries) = fs::read_dir(self.root.path("/sys/class/thermal")) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<_> = entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("thermal_zone")))
+            .collect();
+        paths.sort();
+
+        paths.into_iter()
+            .filter_map(|path| {
+                let zone = read_trimmed(&path.join("type"))?;
+                if GPU_DRIVERS.contains(&zone.as_str()) {
+                    return None;
+                }
+                let temperature = read_i64(&path.join("temp"))?;
+                let zone_name = path.file_name()?.to_str()?.to_string();
+                Some(SensorReading {
+                    chip: zone,
+                    label: zone_name,
+                    kind: SensorKind::Temperature,
+                    value: temperature as f32 / 1000.0,
+                    critical: critical_trip_point(&path),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for SensorMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn read_i64(path: &Path) -> Option<i64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// Stable name of the device behind a hwmon `device` link: the PCI
+/// address it sits under (NVMe controllers are renumbered like hwmon
+/// nodes), else the device's own name (an I2C address)
+fn device_id(link: &Path) -> Option<String> {
+    let target = fs::canonicalize(link).ok()?;
+    let is_pci_address = |name: &str| {
+        let bytes = name.as_bytes();
+        bytes.len() == 12 && bytes[4] == b':' && bytes[7] == b':' && bytes[10] == b'.'
+    };
+    target.ancestors()
+        .filter_map(|dir| dir.file_name()?.to_str())
+        .find(|name| is_pci_address(name))
+        .or_else(|| target.file_name()?.to_str())
+        .map(|s| s.to_string())
+}
+
+/// Every `<prefix>N_input` of a hwmon device with its label, ordered by
+/// channel index. Unlabelled channels are named after their file ("temp2").
+fn read_channels(path: &Path, chip: &str, prefix: &str, kind: SensorKind) -> Vec<SensorReading> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut indices: Vec<u32> = entries.flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            name.to_str()?.strip_prefix(prefix)?.strip_suffix("_input")?.parse().ok()
+        })
+        .collect();
+    indices.sort_unstable();
+
+    // Temperatures and voltages are in thousandths, fans in RPM
+    let scale = if kind == SensorKind::Fan { 1.0 } else { 1000.0 };
+    indices.into_iter()
+        .filter_map(|i| {
+            let value = read_i64(&path.join(format!("{}{}_input", prefix, i)))? as f32 / scale;
+            let label = read_trimmed(&path.join(format!("{}{}_label", prefix, i)))
+                .unwrap_or_else(|| format!("{}{}", prefix, i));
+            let critical = (kind == SensorKind::Temperature)
+                .then(|| read_i64(&path.join(format!("{}{}_crit", prefix, i))))
+                .flatten()
+                .map(|c| c as f32 / 1000.0)
+                // Some firmware reports placeholder limits far outside reality
+                .filter(|&c| c > 0.0 && c < 200.0);
+
+            Some(SensorReading {
+                chip: chip.to_string(),
+                label,
+                kind,
+                value,
+                critical,
+            })
+        })
+        .collect()
+}
+
+/// Temperature of the zone's "critical" trip point in °C
+fn critical_trip_point(zone: &Path) -> Option<f32> {
+    (0..16)
+        .find(|i| read_trimmed(&zone.join(format!("trip_point_{}_type", i))).as_deref() == Some("critical"))
+        .and_then(|i| read_i64(&zone.join(format!("trip_point_{}_temp", i))))
+        .map(|t| t as f32 / 1000.0)
+        .filter(|&c| c > 0.0 && c < 200.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_hwmon_and_thermal_sensors() {
+        let root = TestRoot::new("sensors");
+        root.write_file("/sys/class/hwmon/hwmon0/name", "k10temp\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon0/temp1_input", "61250\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon0/temp1_label", "Tctl\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon1/name", "nct6798\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon1/fan2_input", "1130\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon1/in0_input", "1216\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon1/in0_label", "Vcore\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon2/name", "amdgpu\n").unwrap();
+        root.write_file("/sys/class/hwmon/hwmon2/temp1_input", "50000\n").unwrap();
+        root.write_file("/sys/class/thermal/thermal_zone0/type", "acpitz\n").unwrap();
+        root.write_file("/sys/class/thermal/thermal_zone0/temp", "27800\n").unwrap();
+        root.write_file("/sys/class/thermal/thermal_zone0/trip_point_0_type", "critical\n").unwrap();
+        root.write_file("/sys/class/thermal/thermal_zone0/trip_point_0_temp", "105000\n").unwrap();
+
+        let mut sensors = SensorMetrics::with_root(root.clone());
+        sensors.refresh();
+        let keys: Vec<String> = sensors.readings().iter().map(|r| r.key()).collect();
+        assert_eq!(keys, vec!["k10temp/Tctl", "acpitz/thermal_zone0", "nct6798/fan2", "nct6798/Vcore"]);
+        assert_eq!(sensors.cpu_temperature(), Some(61.25));
+        assert_eq!(sensors.readings()[1].critical, Some(105.0));
+        assert_eq!(sensors.readings()[3].value, 1.216);
+    }
+
+    #[test]
+    fn test_shared_drivers_keyed_by_device() {
+        let root = TestRoot::new("sensors-nvme");
+        // hwmon and controller numbers follow probe order, which may change between boots
+        let drives = [("hwmon2", "0000:02:00.0", "nvme0", "38850"), ("hwmon3", "0000:01:00.0", "nvme1", "41850")];
+        for (hwmon, slot, nvme, temp) in drives {
+            let device = format!("devices/pci0000:00/{}/nvme/{}", slot, nvme);
+            root.write_file(format!("/sys/{}/dev", device), "259:0\n").unwrap();
+            root.write_file(format!("/sys/class/hwmon/{}/name", hwmon), "nvme\n").unwrap();
+            root.write_file(format!("/sys/class/hwmon/{}/temp1_input", hwmon), format!("{}\n", temp)).unwrap();
+            root.write_file(format!("/sys/class/hwmon/{}/temp1_label", hwmon), "Composite\n").unwrap();
+            root.symlink(format!("/sys/class/hwmon/{}/device", hwmon), format!("../../../{}", device)).unwrap();
+        }
+
+        let mut sensors = SensorMetrics::with_root(root.clone());
+        sensors.refresh();
+        let keys: Vec<(String, f32)> = sensors.readings().iter().map(|r| (r.key(), r.value)).collect();
+        assert_eq!(keys, vec![
+            ("nvme@0000:02:00.0/Composite".to_string(), 38.85),
+            ("nvme@0000:01:00.0/Composite".to_string(), 41.85),
+        ]);
+    }
+}
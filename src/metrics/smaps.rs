@@ -0,0 +1,125 @@
+//! Proportional and unique memory per process from /proc/<pid>/smaps_rollup.
+//!
+//! RSS counts every shared page in full for each process mapping it. PSS
+//! splits shared pages between their users, USS counts only private pages,
+//! so forked workers and shared libraries no longer add up to more than the
+//! machine has. Reading smaps_rollup walks the page tables, so only a few
+//! processes are read per refresh.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+
+/// Memory of one process in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemoryBreakdown {
+    pub rss: u64,
+    pub pss: u64,
+    /// Private clean + private dirty
+    pub uss: u64,
+    /// Shared clean + shared dirty
+    pub shared: u64,
+    pub swap: u64,
+    /// Swap split between processes sharing it, like PSS
+    pub swap_pss: u64,
+}
+
+/// Parse smaps_rollup; values are in kB: `Pss:  1234 kB`
+pub fn parse_smaps_rollup(content: &str) -> MemoryBreakdown {
+    let mut memory = MemoryBreakdown::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let bytes = value.split_whitespace().next()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0) * 1024;
+        match key {
+            "Rss" => memory.rss = bytes,
+            "Pss" => memory.pss = bytes,
+            "Private_Clean" | "Private_Dirty" => memory.uss += bytes,
+            "Shared_Clean" | "Shared_Dirty" => memory.shared += bytes,
+            "Swap" => memory.swap = bytes,
+            "SwapPss" => memory.swap_pss = bytes,
+            _ => {}
+        }
+    }
+    memory
+}
+
+pub struct SmapsMetrics {
+    root: FsRoot,
+    breakdowns: HashMap<u32, MemoryBreakdown>,
+}
+
+impl SmapsMetrics {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            breakdowns: HashMap::new(),
+        }
+    }
+
+    fn read(&self, pid: u32) -> Option<MemoryBreakdown> {
+        fs::read_to_string(self.root.path(format!("/proc/{}/smaps_rollup", pid)))
+            .ok()
+            .map(|content| parse_smaps_rollup(&content))
+    }
+
+    /// Read `pids`, dropping every other process
+    pub fn refresh(&mut self, pids: impl IntoIterator<Item = u32>) {
+        self.breakdowns = pids.into_iter()
+            .filter_map(|pid| Some((pid, self.read(pid)?)))
+            .collect();
+    }
+
+    /// Read one more process, e.g. the one selected in the process view
+    pub fn refresh_pid(&mut self, pid: u32) {
+        if let Some(memory) = self.read(pid) {
+            self.breakdowns.insert(pid, memory);
+        }
+    }
+
+    /// `None` when the process was not read or its smaps are not readable
+    pub fn get(&self, pid: u32) -> Option<MemoryBreakdown> {
+        self.breakdowns.get(&pid).copied()
+    }
+}
+
+impl Default for SmapsMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_smaps_rollup() {
+        let root = TestRoot::new("smaps");
+        root.write_file("/proc/7/smaps_rollup", "55d0c0000000-7ffd00000000 ---p 00000000 00:00 0  [rollup]\n\
+            Rss:               10240 kB\n\
+            Pss:                4096 kB\n\
+            Pss_Anon:           2048 kB\n\
+            Shared_Clean:       6144 kB\n\
+            Shared_Dirty:          0 kB\n\
+            Private_Clean:      1024 kB\n\
+            Private_Dirty:      3072 kB\n\
+            Swap:                512 kB\n\
+            SwapPss:             256 kB\n").unwrap();
+
+        let mut smaps = SmapsMetrics::with_root(root.clone());
+        smaps.refresh([7, 8]);
+        let memory = smaps.get(7).unwrap();
+        assert_eq!(memory.pss, 4096 * 1024);
+        assert_eq!(memory.uss, 4096 * 1024);
+        assert_eq!(memory.shared, 6144 * 1024);
+        assert_eq!((memory.swap, memory.swap_pss), (512 * 1024, 256 * 1024));
+        assert_eq!(smaps.get(8), None);
+    }
+}
@@ -1,19 +1,99 @@
+use crate::metrics::process_io::{ProcessIo, ProcessIoMetrics};
+use crate::metrics::process_stat::{ProcessStat, ProcessStatMetrics};
+use crate::metrics::smaps::{MemoryBreakdown, SmapsMetrics};
+use crate::metrics::users::UserNames;
 use sysinfo::{System, Process, Pid, ProcessesToUpdate};
 use std::collections::HashMap;
 
 pub struct SystemMetrics {
     system: System,
+    process_io: ProcessIoMetrics,
+    process_stat: ProcessStatMetrics,
+    users: UserNames,
+    smaps: SmapsMetrics,
+    /// Largest processes by RSS whose smaps_rollup is read on refresh
+    smaps_process_count: usize,
+    read_smaps: bool,
+    read_process_io: bool,
 }
 
 impl SystemMetrics {
     pub fn new() -> Self {
         Self {
             system: System::new_all(),
+            process_io: ProcessIoMetrics::new(),
+            process_stat: ProcessStatMetrics::new(),
+            users: UserNames::new(),
+            smaps: SmapsMetrics::new(),
+            smaps_process_count: 0,
+            read_smaps: false,
+            read_process_io: false,
         }
     }
 
+    /// Number of largest processes whose PSS/USS is read on refresh while
+    /// `set_read_smaps` is on; 0 reads them only through
+    /// `refresh_memory_detail`
+    pub fn set_smaps_process_count(&mut self, count: usize) {
+        self.smaps_process_count = count;
+    }
+
+    /// Read smaps_rollup of the largest processes on refresh; off (the
+    /// default), only processes read on request have PSS/USS
+    pub fn set_read_smaps(&mut self, read: bool) {
+        self.read_smaps = read;
+    }
+
+    /// Read /proc/<pid>/io of every process on refresh; off (the default),
+    /// processes carry no I/O figures
+    pub fn set_read_process_io(&mut self, read: bool) {
+        self.read_process_io = read;
+    }
+
+    /// Count open file descriptors of every process on refresh
+    pub fn set_count_fds(&mut self, count_fds: bool) {
+        self.process_stat.set_count_fds(count_fds);
+    }
+
     pub fn refresh(&mut self) {
         self.system.refresh_all();
+        self.refresh_process_details();
+        self.refresh_smaps();
+    }
+
+    fn refresh_process_details(&mut self) {
+        let pids: Vec<u32> = self.system.processes().keys().map(|pid| pid.as_u32()).collect();
+        if self.read_process_io {
+            self.process_io.refresh(pids.iter().copied());
+        } else {
+            // Forget the counters, so rates start over when reading resumes
+            self.process_io.refresh(std::iter::empty());
+        }
+        self.users.refresh();
+    }
+
+    fn refresh_smaps(&mut self) {
+        if !self.read_smaps || self.smaps_process_count == 0 {
+            // Drop figures that would otherwise go stale
+            self.smaps.refresh(std::iter::empty());
+            return;
+        }
+        let mut by_rss: Vec<(u32, u64)> = self.system.processes().iter()
+            .map(|(pid, process)| (pid.as_u32(), process.memory()))
+            .collect();
+        by_rss.sort_unstable_by_key(|&(_, rss)| std::cmp::Reverse(rss));
+        self.smaps.refresh(by_rss.into_iter().take(self.smaps_process_count).map(|(pid, _)| pid));
+    }
+
+    /// Read /proc/<pid>/stat of `pids`, e.g. the rows of the process view;
+    /// every other process has no stat details
+    pub fn refresh_process_stats(&mut self, pids: impl IntoIterator<Item = u32>) {
+        self.process_stat.refresh(pids);
+    }
+
+    /// Read PSS/USS of one process now, in addition to the largest ones
+    pub fn refresh_memory_detail(&mut self, pid: u32) {
+        self.smaps.refresh_pid(pid);
     }
 
     pub fn refresh_cpu(&mut self) {
@@ -26,6 +106,8 @@ impl SystemMetrics {
 
     pub fn refresh_processes(&mut self) {
         self.system.refresh_processes(ProcessesToUpdate::All);
+        self.refresh_process_details();
+        self.refresh_smaps();
     }
 
     pub fn cpu_usage(&self) -> Vec<f32> {
@@ -95,100 +177,73 @@ impl SystemMetrics {
     }
 
     pub fn top_processes_by_cpu(&self, limit: usize) -> Vec<ProcessInfo> {
-        let mut processes: Vec<_> = self.system.processes()
-            .iter()
-            .map(|(pid, process)| {
-                let cmd_vec: Vec<String> = process.cmd()
-                    .iter()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .collect();
-                let cmd = cmd_vec.join(" ");
-                let cmd_display = if cmd.is_empty() {
-                    process.name().to_string_lossy().to_string()
-                } else {
-                    cmd
-                };
-                
-                ProcessInfo {
-                    pid: pid.as_u32(),
-                    name: process.name().to_string_lossy().to_string(),
-                    cmd: cmd_display,
-                    cpu_usage: process.cpu_usage(),
-                    memory_kb: process.memory(),
-                    user: process.user_id()
-                        .map(|uid| uid.to_string())
-                        .unwrap_or_else(|| "unknown".to_string()),
-                }
-            })
-            .collect();
-
+        let mut processes = self.all_processes();
         processes.sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap());
         processes.truncate(limit);
         processes
     }
 
     pub fn top_processes_by_memory(&self, limit: usize) -> Vec<ProcessInfo> {
-        let mut processes: Vec<_> = self.system.processes()
-            .iter()
-            .map(|(pid, process)| {
-                let cmd_vec: Vec<String> = process.cmd()
-                    .iter()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .collect();
-                let cmd = cmd_vec.join(" ");
-                let cmd_display = if cmd.is_empty() {
-                    process.name().to_string_lossy().to_string()
-                } else {
-                    cmd
-                };
-                
-                ProcessInfo {
-                    pid: pid.as_u32(),
-                    name: process.name().to_string_lossy().to_string(),
-                    cmd: cmd_display,
-                    cpu_usage: process.cpu_usage(),
-                    memory_kb: process.memory(),
-                    user: process.user_id()
-                        .map(|uid| uid.to_string())
-                        .unwrap_or_else(|| "unknown".to_string()),
-                }
-            })
-            .collect();
-
+        let mut processes = self.all_processes();
         processes.sort_by(|a, b| b.memory_kb.cmp(&a.memory_kb));
         processes.truncate(limit);
         processes
     }
+
+    /// Processes by PSS, or USS with `unique`; processes whose smaps were
+    /// not read sort last
+    pub fn top_processes_by_memory_detail(&self, limit: usize, unique: bool) -> Vec<ProcessInfo> {
+        let size = |p: &ProcessInfo| p.memory_detail.map(|m| if unique { m.uss } else { m.pss });
+        let mut processes = self.all_processes();
+        processes.sort_by_key(|p| std::cmp::Reverse(size(p)));
+        processes.truncate(limit);
+        processes
+    }
+
+    /// Heaviest disk readers, or writers with `write`; processes without
+    /// readable I/O counters sort last
+    pub fn top_processes_by_disk(&self, limit: usize, write: bool) -> Vec<ProcessInfo> {
+        let rate = |p: &ProcessInfo| p.io.map(|io| if write { io.write_bytes_per_sec } else { io.read_bytes_per_sec });
+        let mut processes = self.all_processes();
+        processes.sort_by(|a, b| rate(b).unwrap_or(-1.0).total_cmp(&rate(a).unwrap_or(-1.0)));
+        processes.truncate(limit);
+        processes
+    }
     
     /// Get all processes as a flat list
     pub fn all_processes(&self) -> Vec<ProcessInfo> {
         self.system.processes()
             .iter()
-            .map(|(pid, process)| {
-                let cmd_vec: Vec<String> = process.cmd()
-                    .iter()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .collect();
-                let cmd = cmd_vec.join(" ");
-                let cmd_display = if cmd.is_empty() {
-                    process.name().to_string_lossy().to_string()
-                } else {
-                    cmd
-                };
-                
-                ProcessInfo {
-                    pid: pid.as_u32(),
-                    name: process.name().to_string_lossy().to_string(),
-                    cmd: cmd_display,
-                    cpu_usage: process.cpu_usage(),
-                    memory_kb: process.memory(),
-                    user: process.user_id()
-                        .map(|uid| uid.to_string())
-                        .unwrap_or_else(|| "unknown".to_string()),
-                }
-            })
+            .map(|(pid, process)| self.process_info(*pid, process))
             .collect()
     }
+
+    fn process_info(&self, pid: Pid, process: &Process) -> ProcessInfo {
+        let cmd_vec: Vec<String> = process.cmd()
+            .iter()
+            .map(|s| s.to_string_lossy().to_string())
+            .collect();
+        let cmd = cmd_vec.join(" ");
+        let cmd_display = if cmd.is_empty() {
+            process.name().to_string_lossy().to_string()
+        } else {
+            cmd
+        };
+        
+        ProcessInfo {
+            pid: pid.as_u32(),
+            name: process.name().to_string_lossy().to_string(),
+            cmd: cmd_display,
+            cpu_usage: process.cpu_usage(),
+            memory_kb: process.memory(),
+            user: process.user_id()
+                .map(|uid| self.users.name(**uid))
+                .unwrap_or_else(|| "unknown".to_string()),
+            io: self.process_io.get(pid.as_u32()),
+            memory_detail: self.smaps.get(pid.as_u32()),
+            stat: self.process_stat.get(pid.as_u32()),
+        }
+    }
 }
 
 impl Default for SystemMetrics {
@@ -205,7 +260,7 @@ pub struct CpuInfo {
     pub frequency: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
@@ -213,10 +268,28 @@ pub struct ProcessInfo {
     pub cpu_usage: f32,
     pub memory_kb: u64,  // Memory in KB for consistency
     pub user: String,
+    /// Disk I/O rates; `None` when /proc/<pid>/io is not readable
+    pub io: Option<ProcessIo>,
+    /// PSS/USS breakdown, for the processes whose smaps were read
+    pub memory_detail: Option<MemoryBreakdown>,
+    /// State, priority, threads, start and CPU time
+    pub stat: Option<ProcessStat>,
 }
 
 impl ProcessInfo {
     pub fn memory(&self) -> u64 {
         self.memory_kb
     }
+
+    /// A root process named `name` with everything else unset, for tests
+    #[cfg(test)]
+    pub(crate) fn test(pid: u32, name: &str) -> Self {
+        Self {
+            pid,
+            name: name.to_string(),
+            cmd: name.to_string(),
+            user: "root".to_string(),
+            ..Default::default()
+        }
+    }
 }
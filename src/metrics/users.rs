@@ -0,0 +1,88 @@
+//! User names from /etc/passwd.
+//!
+//! Users from LDAP or systemd-homed are not in the file and keep showing
+//! their numeric UID.
+
+use crate::fsroot::FsRoot;
+use std::collections::HashMap;
+use std::fs;
+use std::time::SystemTime;
+
+/// UID to name: `name:password:uid:gid:gecos:home:shell`
+pub fn parse_passwd(content: &str) -> HashMap<u32, String> {
+    content.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+pub struct UserNames {
+    root: FsRoot,
+    names: HashMap<u32, String>,
+    /// Modification time of the file when it was last read
+    modified: Option<SystemTime>,
+}
+
+impl UserNames {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        let mut users = Self {
+            root,
+            names: HashMap::new(),
+            modified: None,
+        };
+        users.refresh();
+        users
+    }
+
+    /// Re-read /etc/passwd if it changed since the last read
+    pub fn refresh(&mut self) {
+        let path = self.root.path("/etc/passwd");
+        let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if modified.is_some() && modified == self.modified {
+            return;
+        }
+        self.names = fs::read_to_string(&path)
+            .map(|content| parse_passwd(&content))
+            .unwrap_or_default();
+        self.modified = modified;
+    }
+
+    /// User name, or the UID as text when it is not in /etc/passwd
+    pub fn name(&self, uid: u32) -> String {
+        self.names.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+    }
+}
+
+impl Default for UserNames {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_user_names() {
+        let root = TestRoot::new("users");
+        root.write_file("/etc/passwd", "root:x:0:0:root:/root:/bin/bash\n\
+            # comment\n\
+            alice:x:1000:1000:Alice,,,:/home/alice:/bin/zsh\n").unwrap();
+
+        let users = UserNames::with_root(root.clone());
+        assert_eq!(users.name(0), "root");
+        assert_eq!(users.name(1000), "alice");
+        assert_eq!(users.name(1001), "1001");
+    }
+}
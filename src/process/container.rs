@@ -0,0 +1,335 @@
+//! Map processes to Docker, Podman, containerd, CRI-O and LXC containers.
+//!
+//! The container comes from the cgroup path in /proc/<pid>/cgroup, for
+//! example `/system.slice/docker-<id>.scope` or
+//! `/kubepods/burstable/pod<uid>/<id>`. Names are read from the runtime's
+//! state on disk; no daemon is contacted.
+
+use crate::fsroot::FsRoot;
+use crate::metrics::system::ProcessInfo;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Container state directories of containerd, one per namespace
+const CONTAINERD_TASKS: &str = "/run/containerd/io.containerd.runtime.v2.task";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    Containerd,
+    CriO,
+    Lxc,
+}
+
+impl ContainerRuntime {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Containerd => "containerd",
+            ContainerRuntime::CriO => "cri-o",
+            ContainerRuntime::Lxc => "lxc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container {
+    /// Full container ID; the name for LXC
+    pub id: String,
+    pub runtime: ContainerRuntime,
+    /// Container name, "pod/container" on Kubernetes, or the short ID when
+    /// the runtime state could not be read
+    pub name: String,
+}
+
+impl Container {
+    /// Case-insensitive match on the name, or an ID prefix
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query) || self.id.starts_with(&query)
+    }
+}
+
+/// `@` filter on the container of a process: `host` selects processes
+/// outside containers, anything else a container by name or ID
+pub fn matches_filter(container: Option<&Container>, query: &str) -> bool {
+    if query.eq_ignore_ascii_case("host") {
+        return container.is_none();
+    }
+    container.is_some_and(|c| c.matches(query))
+}
+
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Runtime and ID (name for LXC) of the container a process belongs to,
+/// from the content of /proc/<pid>/cgroup. cgroup v1 lists one path per
+/// hierarchy, v2 a single `0::` line; the first container found wins.
+pub fn parse_cgroup(content: &str) -> Option<(ContainerRuntime, String)> {
+    content.lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .find_map(parse_cgroup_path)
+}
+
+fn parse_cgroup_path(path: &str) -> Option<(ContainerRuntime, String)> {
+    let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    let kubernetes = path.contains("kubepods");
+
+    for (i, component) in components.iter().enumerate().rev() {
+        let unit = component.trim_end_matches(".scope");
+        // systemd cgroup driver: <prefix>-<id>.scope
+        let prefixed = [
+            ("docker-", ContainerRuntime::Docker),
+            ("libpod-", ContainerRuntime::Podman),
+            ("cri-containerd-", ContainerRuntime::Containerd),
+            ("crio-", ContainerRuntime::CriO),
+        ];
+        for (prefix, runtime) in prefixed {
+            if let Some(id) = unit.strip_prefix(prefix).filter(|id| is_container_id(id)) {
+                return Some((runtime, id.to_string()));
+            }
+        }
+
+        if let Some(name) = component.strip_prefix("lxc.payload.") {
+            return Some((ContainerRuntime::Lxc, name.to_string()));
+        }
+
+        // cgroupfs driver: the bare ID below a runtime directory
+        if is_container_id(component) {
+            let parent = i.checked_sub(1).map(|p| components[p]).unwrap_or("");
+            let runtime = if parent == "docker" {
+                ContainerRuntime::Docker
+            } else if parent.starts_with("libpod") {
+                ContainerRuntime::Podman
+            } else if kubernetes && path.contains("crio") {
+                ContainerRuntime::CriO
+            } else {
+                ContainerRuntime::Containerd
+            };
+            return Some((runtime, component.to_string()));
+        }
+    }
+
+    // Legacy LXC layout: /lxc/<name>
+    match components.as_slice() {
+        ["lxc", name, ..] => Some((ContainerRuntime::Lxc, name.to_string())),
+        _ => None,
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Name from the annotations of an OCI runtime spec: "pod/container" when
+/// Kubernetes created it, else the nerdctl name
+fn oci_spec_name(spec: &Value) -> Option<String> {
+    let annotations = spec.get("annotations")?;
+    let get = |key: &str| annotations.get(key).and_then(Value::as_str);
+    let pod = get("io.kubernetes.cri.sandbox-name").or_else(|| get("io.kubernetes.pod.name"));
+    let container = get("io.kubernetes.cri.container-name").or_else(|| get("io.kubernetes.container.name"));
+    match (pod, container) {
+        (Some(pod), Some(container)) => Some(format!("{}/{}", pod, container)),
+        (None, Some(container)) => Some(container.to_string()),
+        _ => get("nerdctl/name").map(str::to_string),
+    }
+}
+
+/// Resolves and caches the container of every process
+pub struct ContainerResolver {
+    root: FsRoot,
+    by_pid: HashMap<u32, Option<Container>>,
+    /// Names by container ID, kept while the container has processes
+    names: HashMap<String, String>,
+}
+
+impl ContainerResolver {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            by_pid: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Look up processes not seen before and forget those that exited,
+    /// along with the names of containers left without processes
+    pub fn refresh(&mut self, pids: &[u32]) {
+        let mut by_pid = HashMap::with_capacity(pids.len());
+        for &pid in pids {
+            let container = match self.by_pid.remove(&pid) {
+                Some(container) => container,
+                None => self.resolve(pid),
+            };
+            by_pid.insert(pid, container);
+        }
+        self.by_pid = by_pid;
+        let by_pid = &self.by_pid;
+        self.names.retain(|id, _| by_pid.values().flatten().any(|c| &c.id == id));
+    }
+
+    fn resolve(&mut self, pid: u32) -> Option<Container> {
+        let content = fs::read_to_string(self.root.path(format!("/proc/{}/cgroup", pid))).ok()?;
+        let (runtime, id) = parse_cgroup(&content)?;
+        let name = match self.names.get(&id) {
+            Some(name) => name.clone(),
+            None => {
+                let name = self.read_name(runtime, &id)
+                    .unwrap_or_else(|| id.chars().take(12).collect());
+                self.names.insert(id.clone(), name.clone());
+                name
+            }
+        };
+        Some(Container { id, runtime, name })
+    }
+
+    fn read_name(&self, runtime: ContainerRuntime, id: &str) -> Option<String> {
+        match runtime {
+            ContainerRuntime::Docker => {
+                let config = read_json(&self.root.path(format!("/var/lib/docker/containers/{}/config.v2.json", id)))?;
+                config.get("Name")?.as_str().map(|name| name.trim_start_matches('/').to_string())
+            }
+            ContainerRuntime::Podman => {
+                let containers = read_json(&self.root.path("/var/lib/containers/storage/overlay-containers/containers.json"))?;
+                containers.as_array()?.iter()
+                    .find(|c| c.get("id").and_then(Value::as_str) == Some(id))?
+                    .get("names")?.get(0)?.as_str().map(str::to_string)
+            }
+            ContainerRuntime::Containerd => {
+                let namespaces = fs::read_dir(self.root.path(CONTAINERD_TASKS)).ok()?;
+                namespaces.flatten()
+                    .find_map(|ns| read_json(&ns.path().join(id).join("config.json")))
+                    .and_then(|spec| oci_spec_name(&spec))
+            }
+            ContainerRuntime::CriO => {
+                let spec = read_json(&self.root.path(format!(
+                    "/run/containers/storage/overlay-containers/{}/userdata/config.json", id)))?;
+                oci_spec_name(&spec)
+            }
+            ContainerRuntime::Lxc => Some(id.to_string()),
+        }
+    }
+
+    /// Container of a process; `None` for processes on the host
+    pub fn get(&self, pid: u32) -> Option<&Container> {
+        self.by_pid.get(&pid).and_then(Option::as_ref)
+    }
+}
+
+impl Default for ContainerResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Totals of the processes in one container
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerGroup {
+    /// `None` for processes running on the host
+    pub container: Option<Container>,
+    pub processes: usize,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    /// GPU memory in bytes, over all GPUs
+    pub gpu_memory: u64,
+}
+
+impl ContainerGroup {
+    pub fn label(&self) -> &str {
+        self.container.as_ref().map(|c| c.name.as_str()).unwrap_or("host")
+    }
+}
+
+/// Group processes by container, busiest first with the host last
+pub fn group_by_container(
+    processes: &[ProcessInfo],
+    resolver: &ContainerResolver,
+    gpu_memory: &HashMap<u32, u64>,
+) -> Vec<ContainerGroup> {
+    let mut groups: Vec<ContainerGroup> = Vec::new();
+    for process in processes {
+        let container = resolver.get(process.pid);
+        let group = match groups.iter().position(|g| g.container.as_ref().map(|c| &c.id) == container.map(|c| &c.id)) {
+            Some(pos) => &mut groups[pos],
+            None => {
+                groups.push(ContainerGroup {
+                    container: container.cloned(),
+                    processes: 0,
+                    cpu_usage: 0.0,
+                    memory: 0,
+                    gpu_memory: 0,
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        group.processes += 1;
+        group.cpu_usage += process.cpu_usage;
+        group.memory += process.memory_kb;
+        group.gpu_memory += gpu_memory.get(&process.pid).copied().unwrap_or(0);
+    }
+
+    groups.sort_by(|a, b| {
+        a.container.is_none().cmp(&b.container.is_none())
+            .then_with(|| b.cpu_usage.total_cmp(&a.cpu_usage))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_container_from_cgroup() {
+        let id = "4f0b2d8c6a1e3b5d7f9a0c2e4b6d8f1a3c5e7b9d0f2a4c6e8b1d3f5a7c9e0b2d";
+        let docker = format!("0::/system.slice/docker-{}.scope\n", id);
+        assert_eq!(parse_cgroup(&docker), Some((ContainerRuntime::Docker, id.to_string())));
+        let podman = format!("0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container\n", id);
+        assert_eq!(parse_cgroup(&podman), Some((ContainerRuntime::Podman, id.to_string())));
+        let kubepods = format!("12:memory:/kubepods/burstable/pod0a1b2c3d/{}\n0::/\n", id);
+        assert_eq!(parse_cgroup(&kubepods), Some((ContainerRuntime::Containerd, id.to_string())));
+        assert_eq!(parse_cgroup("0::/lxc.payload.web/init.scope\n"), Some((ContainerRuntime::Lxc, "web".to_string())));
+        assert_eq!(parse_cgroup("0::/user.slice/user-1000.slice/session-2.scope\n"), None);
+
+        let root = TestRoot::new("container");
+        root.write_file("/proc/10/cgroup", &docker).unwrap();
+        root.write_file("/proc/11/cgroup", &docker).unwrap();
+        root.write_file("/proc/12/cgroup", "0::/init.scope\n").unwrap();
+        root.write_file(format!("/var/lib/docker/containers/{}/config.v2.json", id), r#"{"Name":"/redis"}"#).unwrap();
+
+        let mut resolver = ContainerResolver::with_root(root.clone());
+        resolver.refresh(&[10, 11, 12]);
+        assert_eq!(resolver.get(10).map(|c| c.name.as_str()), Some("redis"));
+        assert!(resolver.get(12).is_none());
+
+        let process = |pid, cpu_usage| ProcessInfo { cpu_usage, memory_kb: 1000, ..ProcessInfo::test(pid, "p") };
+        let gpu_memory = HashMap::from([(11, 4096)]);
+        let groups = group_by_container(&[process(10, 5.0), process(11, 2.5), process(12, 50.0)], &resolver, &gpu_memory);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].label(), "redis");
+        assert_eq!((groups[0].processes, groups[0].cpu_usage, groups[0].gpu_memory), (2, 7.5, 4096));
+        assert_eq!(groups[1].label(), "host");
+
+        // `@host` only selects processes outside containers, even when a
+        // container name contains "host"
+        let localhost = Container { id: id.to_string(), runtime: ContainerRuntime::Docker, name: "localhost-db".to_string() };
+        assert!(matches_filter(None, "host"));
+        assert!(!matches_filter(Some(&localhost), "host"));
+        assert!(matches_filter(Some(&localhost), "localhost"));
+        assert!(!matches_filter(None, "redis"));
+
+        // Names are dropped once the container has no processes left
+        resolver.refresh(&[12]);
+        assert!(resolver.names.is_empty());
+    }
+}
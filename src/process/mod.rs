@@ -1,5 +1,9 @@
 pub mod tree;
 pub mod signals;
+pub mod container;
+pub mod sockets;
 
-pub use tree::{ProcessTree, ProcessNode};
+pub use tree::{ProcessTree, ProcessNode, SubtreeMemory};
+pub use container::{Container, ContainerGroup, ContainerResolver, ContainerRuntime};
+pub use sockets::{Socket, SocketInspector, SocketProtocol};
 pub use signals::{smart_kill, force_kill, send_signal_to_process};
@@ -1,6 +1,6 @@
 use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
-use std::path::Path;
+use crate::fsroot::FsRoot;
 use std::time::Duration;
 use tokio::time::sleep;
 use anyhow::{Result, anyhow};
@@ -69,7 +69,7 @@ pub fn send_signal_to_process(pid: u32, sig: Signal) -> Result<()> {
 
 /// Check if process exists by checking /proc/[pid]
 fn process_exists(pid: u32) -> bool {
-    Path::new(&format!("/proc/{}", pid)).exists()
+    FsRoot::global().path(format!("/proc/{}", pid)).exists()
 }
 
 /// Send signal with custom signal number
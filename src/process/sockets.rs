@@ -0,0 +1,282 @@
+//! Sockets per process, like `ss -p`.
+//!
+//! The socket tables in /proc/net/{tcp,tcp6,udp,udp6,unix} are keyed by
+//! inode; /proc/<pid>/fd links of the form `socket:[<inode>]` tie them to
+//! processes. Walking every fd is costly, so the inspector only refreshes
+//! on request. Processes of other users are only visible as root.
+
+use crate::fsroot::FsRoot;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+const TCP_STATES: [&str; 12] = [
+    "UNKNOWN", "ESTABLISHED", "SYN_SENT", "SYN_RECV", "FIN_WAIT1", "FIN_WAIT2",
+    "TIME_WAIT", "CLOSE", "CLOSE_WAIT", "LAST_ACK", "LISTEN", "CLOSING",
+];
+const TCP_LISTEN: u8 = 0x0A;
+/// Unbound or unconnected UDP sockets report TCP_CLOSE
+const UDP_UNCONNECTED: u8 = 0x07;
+/// __SO_ACCEPTCON in the flags of /proc/net/unix
+const UNIX_ACCEPTCON: u32 = 0x10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketProtocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Unix,
+}
+
+impl SocketProtocol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SocketProtocol::Tcp => "tcp",
+            SocketProtocol::Tcp6 => "tcp6",
+            SocketProtocol::Udp => "udp",
+            SocketProtocol::Udp6 => "udp6",
+            SocketProtocol::Unix => "unix",
+        }
+    }
+
+    fn is_udp(&self) -> bool {
+        matches!(self, SocketProtocol::Udp | SocketProtocol::Udp6)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Socket {
+    pub protocol: SocketProtocol,
+    /// `None` for unix sockets
+    pub local: Option<SocketAddr>,
+    pub remote: Option<SocketAddr>,
+    /// TCP state name; "UNCONN" for unconnected UDP
+    pub state: &'static str,
+    pub listening: bool,
+    pub inode: u64,
+    /// Path or abstract name ("@...") of a unix socket
+    pub path: Option<String>,
+}
+
+impl Socket {
+    pub fn local_port(&self) -> Option<u16> {
+        self.local.map(|addr| addr.port())
+    }
+
+    /// "tcp LISTEN 0.0.0.0:8080" or "tcp ESTABLISHED 10.0.0.2:51234 → 1.1.1.1:443"
+    pub fn describe(&self) -> String {
+        let endpoint = match (&self.local, &self.path) {
+            (Some(local), _) => local.to_string(),
+            (None, Some(path)) => path.clone(),
+            (None, None) => "-".to_string(),
+        };
+        match self.remote.filter(|remote| remote.port() != 0) {
+            Some(remote) => format!("{} {} {} → {}", self.protocol.name(), self.state, endpoint, remote),
+            None => format!("{} {} {}", self.protocol.name(), self.state, endpoint),
+        }
+    }
+}
+
+/// `0100007F:1F90`; the address words are printed in host byte order
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words: Vec<[u8; 4]> = (0..address.len() / 8)
+        .map(|i| u32::from_str_radix(&address[i * 8..i * 8 + 8], 16).map(u32::to_ne_bytes))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let ip = match words.as_slice() {
+        [word] => IpAddr::V4(Ipv4Addr::from(*word)),
+        [a, b, c, d] => {
+            let mut bytes = [0u8; 16];
+            for (i, word) in [a, b, c, d].iter().enumerate() {
+                bytes[i * 4..i * 4 + 4].copy_from_slice(*word);
+            }
+            IpAddr::V6(Ipv6Addr::from(bytes))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+/// Parse /proc/net/{tcp,tcp6,udp,udp6}:
+/// `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode`
+pub fn parse_inet(content: &str, protocol: SocketProtocol) -> Vec<Socket> {
+    content.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local = parse_address(fields.get(1)?)?;
+            let remote = parse_address(fields.get(2)?)?;
+            let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+            let (state_name, listening) = if protocol.is_udp() {
+                if state == UDP_UNCONNECTED { ("UNCONN", true) } else { ("ESTABLISHED", false) }
+            } else {
+                (TCP_STATES.get(state as usize).copied().unwrap_or("UNKNOWN"), state == TCP_LISTEN)
+            };
+            Some(Socket {
+                protocol,
+                local: Some(local),
+                remote: Some(remote),
+                state: state_name,
+                listening,
+                inode,
+                path: None,
+            })
+        })
+        .collect()
+}
+
+/// Parse /proc/net/unix: `Num RefCount Protocol Flags Type St Inode Path`
+pub fn parse_unix(content: &str) -> Vec<Socket> {
+    content.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+            let state = u8::from_str_radix(fields.get(5)?, 16).ok()?;
+            let inode = fields.get(6)?.parse().ok()?;
+            let listening = flags & UNIX_ACCEPTCON != 0;
+            Some(Socket {
+                protocol: SocketProtocol::Unix,
+                local: None,
+                remote: None,
+                state: if listening { "LISTEN" } else if state == 3 { "ESTABLISHED" } else { "UNCONN" },
+                listening,
+                inode,
+                path: fields.get(7).map(|path| path.to_string()),
+            })
+        })
+        .collect()
+}
+
+pub struct SocketInspector {
+    root: FsRoot,
+    by_pid: HashMap<u32, Vec<Socket>>,
+}
+
+impl SocketInspector {
+    pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    pub fn with_root(root: FsRoot) -> Self {
+        Self {
+            root,
+            by_pid: HashMap::new(),
+        }
+    }
+
+    /// Re-read the socket tables and every process's file descriptors
+    pub fn refresh(&mut self) {
+        let tables = [
+            ("/proc/net/tcp", SocketProtocol::Tcp),
+            ("/proc/net/tcp6", SocketProtocol::Tcp6),
+            ("/proc/net/udp", SocketProtocol::Udp),
+            ("/proc/net/udp6", SocketProtocol::Udp6),
+        ];
+        let mut by_inode: HashMap<u64, Socket> = HashMap::new();
+        for (path, protocol) in tables {
+            if let Ok(content) = fs::read_to_string(self.root.path(path)) {
+                by_inode.extend(parse_inet(&content, protocol).into_iter().map(|s| (s.inode, s)));
+            }
+        }
+        if let Ok(content) = fs::read_to_string(self.root.path("/proc/net/unix")) {
+            by_inode.extend(parse_unix(&content).into_iter().map(|s| (s.inode, s)));
+        }
+
+        let mut by_pid: HashMap<u32, Vec<Socket>> = HashMap::new();
+        let Ok(entries) = fs::read_dir(self.root.path("/proc")) else {
+            self.by_pid = by_pid;
+            return;
+        };
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else { continue };
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else { continue };
+            for fd in fds.flatten() {
+                let Ok(target) = fs::read_link(fd.path()) else { continue };
+                let inode = target.to_str()
+                    .and_then(|t| t.strip_prefix("socket:["))
+                    .and_then(|t| t.strip_suffix(']'))
+                    .and_then(|t| t.parse::<u64>().ok());
+                if let Some(socket) = inode.and_then(|inode| by_inode.get(&inode)) {
+                    by_pid.entry(pid).or_default().push(socket.clone());
+                }
+            }
+        }
+        // Listening sockets first, then by protocol
+        for sockets in by_pid.values_mut() {
+            sockets.sort_by_key(|s| (!s.listening, s.protocol as u8, s.local_port()));
+        }
+        self.by_pid = by_pid;
+    }
+
+    /// Sockets of a process as of the last refresh
+    pub fn sockets(&self, pid: u32) -> &[Socket] {
+        self.by_pid.get(&pid).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Processes listening on the local port over TCP or UDP; connections
+    /// that merely use the port as their local end do not count
+    pub fn pids_on_port(&self, port: u16) -> HashSet<u32> {
+        self.by_pid.iter()
+            .filter(|(_, sockets)| sockets.iter().any(|s| s.listening && s.local_port() == Some(port)))
+            .map(|(pid, _)| *pid)
+            .collect()
+    }
+}
+
+impl Default for SocketInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fsroot::TestRoot;
+
+    #[test]
+    fn test_sockets_per_process() {
+        let root = TestRoot::new("sockets");
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n";
+        root.write_file("/proc/net/tcp", format!("{}\
+               0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 111 1 0 100 0 0 10 0\n\
+               1: 0100007F:D431 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 222 1 0 20 4 30 10 -1\n\
+               2: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 555 1 0 20 4 30 10 -1\n",
+            header)).unwrap();
+        root.write_file("/proc/net/udp6", format!("{}\
+               0: 00000000000000000000000001000000:0035 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 333 2 0 0\n",
+            header)).unwrap();
+        root.write_file("/proc/net/unix", "Num       RefCount Protocol Flags    Type St Inode Path\n\
+            0000000000000000: 00000002 00000000 00010000 0001 01 444 /run/app.sock\n").unwrap();
+        std::fs::create_dir_all(root.path("/proc/100/fd")).unwrap();
+        std::fs::create_dir_all(root.path("/proc/200/fd")).unwrap();
+        std::fs::create_dir_all(root.path("/proc/300/fd")).unwrap();
+        std::os::unix::fs::symlink("socket:[111]", root.path("/proc/100/fd/3")).unwrap();
+        std::os::unix::fs::symlink("socket:[444]", root.path("/proc/100/fd/4")).unwrap();
+        std::os::unix::fs::symlink("/dev/null", root.path("/proc/100/fd/0")).unwrap();
+        std::os::unix::fs::symlink("socket:[222]", root.path("/proc/200/fd/5")).unwrap();
+        std::os::unix::fs::symlink("socket:[333]", root.path("/proc/200/fd/6")).unwrap();
+        // A worker holding an accepted connection whose local end is 8080
+        std::os::unix::fs::symlink("socket:[555]", root.path("/proc/300/fd/7")).unwrap();
+
+        let mut inspector = SocketInspector::with_root(root.clone());
+        inspector.refresh();
+        let server = inspector.sockets(100);
+        assert_eq!(server.len(), 2);
+        assert_eq!(server[0].describe(), "tcp LISTEN 0.0.0.0:8080");
+        assert_eq!(server[1].path.as_deref(), Some("/run/app.sock"));
+
+        let client = inspector.sockets(200);
+        assert_eq!(client[0].describe(), "udp6 UNCONN [::1]:53");
+        assert_eq!(client[1].describe(), "tcp ESTABLISHED 127.0.0.1:54321 → 127.0.0.1:8080");
+        assert_eq!(inspector.pids_on_port(8080), HashSet::from([100]));
+        assert_eq!(inspector.pids_on_port(53), HashSet::from([200]));
+        assert_eq!(inspector.sockets(300)[0].local_port(), Some(8080));
+        assert!(inspector.pids_on_port(54321).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::fsroot::FsRoot;
 use crate::metrics::system::ProcessInfo;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -10,18 +11,48 @@ pub struct ProcessNode {
     pub children: Vec<u32>,
 }
 
+/// Memory of a process and its descendants in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SubtreeMemory {
+    pub bytes: u64,
+    pub processes: usize,
+    /// Processes counted with their PSS; the others count with their RSS
+    pub with_pss: usize,
+}
+
+impl SubtreeMemory {
+    /// What the total is made of: "PSS", "RSS", or "PSS (partial)" when
+    /// smaps was only read for some of the processes
+    pub fn label(&self) -> &'static str {
+        if self.with_pss == 0 {
+            "RSS"
+        } else if self.with_pss == self.processes {
+            "PSS"
+        } else {
+            "PSS (partial)"
+        }
+    }
+}
+
 pub struct ProcessTree {
     nodes: HashMap<u32, ProcessNode>,
     root_pid: u32,
     render_order: Vec<(u32, usize)>, // (pid, depth)
+    fs_root: FsRoot,
 }
 
 impl ProcessTree {
     pub fn new() -> Self {
+        Self::with_root(FsRoot::global())
+    }
+
+    /// Tree reading parent PIDs from the procfs under `fs_root`
+    pub fn with_root(fs_root: FsRoot) -> Self {
         Self {
             nodes: HashMap::new(),
             root_pid: 1,
             render_order: Vec::new(),
+            fs_root,
         }
     }
 
@@ -31,7 +62,7 @@ impl ProcessTree {
         
         // First pass: create all nodes
         for proc in processes {
-            let ppid = Self::read_ppid(proc.pid).unwrap_or(0);
+            let ppid = Self::read_ppid(&self.fs_root, proc.pid).unwrap_or(0);
             let node = ProcessNode {
                 pid: proc.pid,
                 ppid,
@@ -61,8 +92,8 @@ impl ProcessTree {
     }
 
     /// Read parent PID from /proc/[pid]/stat
-    fn read_ppid(pid: u32) -> Option<u32> {
-        let stat_path = format!("/proc/{}/stat", pid);
+    fn read_ppid(fs_root: &FsRoot, pid: u32) -> Option<u32> {
+        let stat_path = fs_root.path(format!("/proc/{}/stat", pid));
         let content = fs::read_to_string(stat_path).ok()?;
         
         // Format: pid (comm) state ppid ...
@@ -156,13 +187,42 @@ impl ProcessTree {
         total
     }
 
-    /// Get aggregated memory usage for a subtree
-    pub fn get_aggregated_memory(&self, pid: u32) -> u64 {
-        let mut total = 0u64;
+    /// Get aggregated disk (read, write) bytes per second for a subtree;
+    /// processes with unreadable I/O counters add nothing
+    pub fn get_aggregated_io(&self, pid: u32) -> (f64, f64) {
+        let mut total = (0.0, 0.0);
         if let Some(node) = self.nodes.get(&pid) {
-            total += node.info.memory_kb;
+            if let Some(io) = node.info.io {
+                total = (io.read_bytes_per_sec, io.write_bytes_per_sec);
+            }
             for &child_pid in &node.children {
-                total += self.get_aggregated_memory(child_pid);
+                let (read, write) = self.get_aggregated_io(child_pid);
+                total.0 += read;
+                total.1 += write;
+            }
+        }
+        total
+    }
+
+    /// Get aggregated memory usage for a subtree. PSS is used where it was
+    /// read, so pages shared between parent and children count once; RSS
+    /// otherwise, which the label of the total tells apart.
+    pub fn get_aggregated_memory(&self, pid: u32) -> SubtreeMemory {
+        let mut total = SubtreeMemory::default();
+        if let Some(node) = self.nodes.get(&pid) {
+            total.processes = 1;
+            match node.info.memory_detail {
+                Some(memory) => {
+                    total.bytes = memory.pss;
+                    total.with_pss = 1;
+                }
+                None => total.bytes = node.info.memory_kb,
+            }
+            for &child_pid in &node.children {
+                let child = self.get_aggregated_memory(child_pid);
+                total.bytes += child.bytes;
+                total.processes += child.processes;
+                total.with_pss += child.with_pss;
             }
         }
         total
@@ -178,35 +238,15 @@ impl Default for ProcessTree {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fsroot::TestRoot;
 
     #[test]
     fn test_tree_building() {
         let mut tree = ProcessTree::new();
         let processes = vec![
-            ProcessInfo {
-                pid: 1,
-                name: "init".to_string(),
-                cmd: "init".to_string(),
-                cpu_usage: 0.0,
-                memory_kb: 1000,
-                user: "root".to_string(),
-            },
-            ProcessInfo {
-                pid: 100,
-                name: "parent".to_string(),
-                cmd: "parent".to_string(),
-                cpu_usage: 10.0,
-                memory_kb: 2000,
-                user: "root".to_string(),
-            },
-            ProcessInfo {
-                pid: 200,
-                name: "child".to_string(),
-                cmd: "child".to_string(),
-                cpu_usage: 5.0,
-                memory_kb: 1500,
-                user: "root".to_string(),
-            },
+            ProcessInfo { memory_kb: 1000, ..ProcessInfo::test(1, "init") },
+            ProcessInfo { cpu_usage: 10.0, memory_kb: 2000, ..ProcessInfo::test(100, "parent") },
+            ProcessInfo { cpu_usage: 5.0, memory_kb: 1500, ..ProcessInfo::test(200, "child") },
         ];
 
         tree.build_from_processes(processes);
@@ -222,4 +262,66 @@ mod tests {
         tree.calculate_render_order(&collapsed);
         // If parent (100) is collapsed, its children shouldn't appear in render order
     }
+
+    #[test]
+    fn test_ppid_from_fixture_root() {
+        let root = TestRoot::new("tree");
+        root.write_file("/proc/100/stat", "100 (parent) S 1 100 100 0").unwrap();
+        // Process names may contain spaces and parentheses
+        root.write_file("/proc/200/stat", "200 (odd (name) x) R 100 200 100 0").unwrap();
+
+        let mut tree = ProcessTree::with_root(root.clone());
+        tree.build_from_processes(vec![ProcessInfo::test(100, "parent"), ProcessInfo::test(200, "odd (name) x")]);
+
+        assert_eq!(tree.get_node(200).map(|n| n.ppid), Some(100));
+        assert!(tree.has_children(100));
+    }
+
+    #[test]
+    fn test_aggregated_io() {
+        let root = TestRoot::new("tree-io");
+        root.write_file("/proc/100/stat", "100 (make) S 1 100 100 0").unwrap();
+        root.write_file("/proc/200/stat", "200 (cc) R 100 200 100 0").unwrap();
+        root.write_file("/proc/300/stat", "300 (ld) R 100 300 100 0").unwrap();
+
+        let process = |pid, io| ProcessInfo { io, ..ProcessInfo::test(pid, "make") };
+        let io = |read, write| Some(crate::metrics::ProcessIo {
+            read_bytes_per_sec: read,
+            write_bytes_per_sec: write,
+            ..Default::default()
+        });
+
+        let mut tree = ProcessTree::with_root(root.clone());
+        // The parent's counters are unreadable and add nothing
+        tree.build_from_processes(vec![process(100, None), process(200, io(4096.0, 0.0)), process(300, io(1024.0, 8192.0))]);
+        assert_eq!(tree.get_aggregated_io(100), (5120.0, 8192.0));
+        assert_eq!(tree.get_aggregated_io(300), (1024.0, 8192.0));
+        assert_eq!(tree.get_aggregated_io(999), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_aggregated_memory_prefers_pss() {
+        let root = TestRoot::new("tree-pss");
+        root.write_file("/proc/100/stat", "100 (server) S 1 100 100 0").unwrap();
+        root.write_file("/proc/200/stat", "200 (worker) S 100 200 100 0").unwrap();
+
+        const MIB: u64 = 1024 * 1024;
+        let process = |pid, memory_detail| ProcessInfo {
+            memory_kb: 100 * MIB,
+            memory_detail,
+            ..ProcessInfo::test(pid, "server")
+        };
+        let pss = crate::metrics::MemoryBreakdown { rss: 100 * MIB, pss: 40 * MIB, ..Default::default() };
+
+        let mut tree = ProcessTree::with_root(root.clone());
+        tree.build_from_processes(vec![process(100, Some(pss)), process(200, None)]);
+        let total = tree.get_aggregated_memory(100);
+        assert_eq!(total.bytes, 140 * MIB);
+        assert_eq!(total.label(), "PSS (partial)");
+        assert_eq!(tree.get_aggregated_memory(200).label(), "RSS");
+
+        tree.build_from_processes(vec![process(100, Some(pss)), process(200, Some(pss))]);
+        let total = tree.get_aggregated_memory(100);
+        assert_eq!((total.bytes, total.label()), (80 * MIB, "PSS"));
+    }
 }
@@ -1,4 +1,4 @@
-use super::types::{TrendDirection, TrendType, TrendSeverity, MetricTrend};
+use super::types::{GpuRef, TrendDirection, TrendType, TrendSeverity, MetricTrend};
 use crate::history::{CircularBuffer, DataPoint, MetricsHistory};
 use crate::config::{TrendConfig, AlertsConfig};
 
@@ -57,41 +57,42 @@ impl TrendAnalyzer {
             }
         }
 
-        // Analyze GPU temperatures
-        for (i, gpu_temp) in history.gpu_temp.iter().enumerate() {
-            if let Some(trend) = self.analyze_metric(
-                gpu_temp,
-                TrendType::GpuTemp(i),
-                thresholds.gpu_temp_threshold,
-            ) {
+        // Analyze CPU steal and iowait
+        let cpu_times = [
+            (&history.cpu_steal, TrendType::CpuSteal, thresholds.cpu_steal_threshold),
+            (&history.cpu_iowait, TrendType::CpuIowait, thresholds.cpu_iowait_threshold),
+        ];
+        for (buffer, trend_type, threshold) in cpu_times {
+            if let Some(trend) = self.analyze_metric(buffer, trend_type, threshold) {
                 if trend.confidence >= self.config.min_confidence {
                     trends.push(trend);
                 }
             }
         }
 
-        // Analyze GPU utilization
-        for (i, gpu_util) in history.gpu_util.iter().enumerate() {
-            if let Some(trend) = self.analyze_metric(
-                gpu_util,
-                TrendType::GpuUtil(i),
-                thresholds.gpu_util_threshold,
-            ) {
-                if trend.confidence >= self.config.min_confidence {
-                    trends.push(trend);
-                }
-            }
-        }
-
-        // Analyze GPU memory
-        for (i, gpu_mem) in history.gpu_mem.iter().enumerate() {
-            if let Some(trend) = self.analyze_metric(
-                gpu_mem,
-                TrendType::GpuMemory(i),
-                thresholds.gpu_mem_threshold,
-            ) {
-                if trend.confidence >= self.config.min_confidence {
-                    trends.push(trend);
+        // Analyze GPUs that are currently attached; removed GPUs keep
+        // their history but would otherwise report a frozen trend
+        for gpu in history.gpus.iter().filter(|gpu| gpu.present) {
+            let gpu_ref = GpuRef { key: gpu.key.clone(), name: gpu.name.clone() };
+            let metrics = [
+                (&gpu.temp, TrendType::GpuTemp(gpu_ref.clone()), thresholds.gpu_temp_limit(None)),
+                (&gpu.util, TrendType::GpuUtil(gpu_ref.clone()), thresholds.gpu_util_threshold),
+                (&gpu.mem, TrendType::GpuMemory(gpu_ref.clone()), thresholds.gpu_mem_threshold),
+                (&gpu.gtt, TrendType::GpuGtt(gpu_ref.clone()), thresholds.gpu_gtt_threshold),
+                // Power heads for the board limit; the others have no
+                // meaningful threshold, so only the trend itself is reported
+                (&gpu.power, TrendType::GpuPower(gpu_ref.clone()), gpu.power_limit.unwrap_or(f32::MAX)),
+                (&gpu.clock, TrendType::GpuClock(gpu_ref.clone()), f32::MAX),
+                (&gpu.mem_clock, TrendType::GpuMemoryClock(gpu_ref.clone()), f32::MAX),
+                (&gpu.fan, TrendType::GpuFan(gpu_ref.clone()), f32::MAX),
+                (&gpu.fan_rpm, TrendType::GpuFanRpm(gpu_ref.clone()), f32::MAX),
+            ];
+
+            for (buffer, trend_type, threshold) in metrics {
+                if let Some(trend) = self.analyze_metric(buffer, trend_type, threshold) {
+                    if trend.confidence >= self.config.min_confidence {
+                        trends.push(trend);
+                    }
                 }
             }
         }
@@ -178,7 +179,7 @@ impl TrendAnalyzer {
             } else {
                 TrendSeverity::Info
             }
-        } else if rate_per_minute.abs() > 5.0 {
+        } else if metric_type.warning_rate().is_some_and(|rate| rate_per_minute.abs() > rate) {
             TrendSeverity::Warning
         } else {
             TrendSeverity::Info
@@ -242,3 +243,33 @@ impl TrendAnalyzer {
         (slope, intercept, r_squared)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn rising(step: f32) -> CircularBuffer<f32> {
+        let mut buffer = CircularBuffer::new(10);
+        for i in 0..10 {
+            buffer.push(1000.0 + step * i as f32, i);
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_warning_rate_per_metric() {
+        let analyzer = TrendAnalyzer::new(Config::default().trends);
+        let gpu = GpuRef { key: "0000:03:00.0".to_string(), name: "Radeon".to_string() };
+        let severity = |buffer: &CircularBuffer<f32>, metric| {
+            analyzer.analyze_metric(buffer, metric, f32::MAX).map(|trend| trend.severity)
+        };
+
+        // 10 MHz or RPM per sample is ordinary; 10 % per sample is not
+        let buffer = rising(10.0);
+        assert_eq!(severity(&buffer, TrendType::GpuClock(gpu.clone())), Some(TrendSeverity::Info));
+        assert_eq!(severity(&buffer, TrendType::GpuFanRpm(gpu.clone())), Some(TrendSeverity::Info));
+        assert_eq!(severity(&buffer, TrendType::GpuPower(gpu)), Some(TrendSeverity::Info));
+        assert_eq!(severity(&buffer, TrendType::Cpu), Some(TrendSeverity::Warning));
+    }
+}
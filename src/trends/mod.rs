@@ -2,4 +2,4 @@ pub mod analyzer;
 pub mod types;
 
 pub use analyzer::TrendAnalyzer;
-pub use types::{Trend, TrendDirection, TrendType, TrendSeverity, MetricTrend};
+pub use types::{GpuRef, Trend, TrendDirection, TrendType, TrendSeverity, MetricTrend};
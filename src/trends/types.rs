@@ -8,14 +8,60 @@ pub enum TrendDirection {
     Volatile,     // Erratic/unpredictable
 }
 
+/// GPU a trend belongs to. Trends are told apart by the device key, so
+/// two cards of the same model keep separate trends; the name is shown.
 #[derive(Debug, Clone)]
+pub struct GpuRef {
+    pub key: String,
+    pub name: String,
+}
+
+impl PartialEq for GpuRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum TrendType {
     Cpu,
     Memory,
     Swap,
-    GpuTemp(usize),
-    GpuUtil(usize),
-    GpuMemory(usize),
+    CpuSteal,
+    CpuIowait,
+    GpuTemp(GpuRef),
+    GpuUtil(GpuRef),
+    GpuMemory(GpuRef),
+    GpuGtt(GpuRef),
+    GpuPower(GpuRef),
+    GpuClock(GpuRef),
+    GpuMemoryClock(GpuRef),
+    GpuFan(GpuRef),
+    GpuFanRpm(GpuRef),
+}
+
+impl TrendType {
+    /// Unit of the metric's values, appended to rates and predictions
+    pub fn unit(&self) -> &'static str {
+        match self {
+            TrendType::GpuTemp(_) => "°C",
+            TrendType::GpuPower(_) => " W",
+            TrendType::GpuClock(_) | TrendType::GpuMemoryClock(_) => " MHz",
+            TrendType::GpuFanRpm(_) => " RPM",
+            _ => "%",
+        }
+    }
+
+    /// Change per minute above which a trend without a threshold in
+    /// sight is a Warning. `None` for metrics that swing widely in normal
+    /// use (clocks, fan speed, power), which only warn on a threshold.
+    pub fn warning_rate(&self) -> Option<f32> {
+        match self {
+            TrendType::GpuPower(_) | TrendType::GpuClock(_) | TrendType::GpuMemoryClock(_)
+                | TrendType::GpuFan(_) | TrendType::GpuFanRpm(_) => None,
+            _ => Some(5.0),
+        }
+    }
 }
 
 impl fmt::Display for TrendType {
@@ -24,9 +70,16 @@ impl fmt::Display for TrendType {
             TrendType::Cpu => write!(f, "CPU"),
             TrendType::Memory => write!(f, "Memory"),
             TrendType::Swap => write!(f, "SWAP"),
-            TrendType::GpuTemp(id) => write!(f, "GPU {} Temp", id),
-            TrendType::GpuUtil(id) => write!(f, "GPU {} Usage", id),
-            TrendType::GpuMemory(id) => write!(f, "GPU {} Memory", id),
+            TrendType::CpuSteal => write!(f, "CPU Steal"),
+            TrendType::CpuIowait => write!(f, "CPU iowait"),
+            TrendType::GpuTemp(gpu) => write!(f, "{} Temp", gpu.name),
+            TrendType::GpuUtil(gpu) => write!(f, "{} Usage", gpu.name),
+            TrendType::GpuMemory(gpu) => write!(f, "{} Memory", gpu.name),
+            TrendType::GpuGtt(gpu) => write!(f, "{} GTT", gpu.name),
+            TrendType::GpuPower(gpu) => write!(f, "{} Power", gpu.name),
+            TrendType::GpuClock(gpu) => write!(f, "{} Core Clock", gpu.name),
+            TrendType::GpuMemoryClock(gpu) => write!(f, "{} Memory Clock", gpu.name),
+            TrendType::GpuFan(gpu) | TrendType::GpuFanRpm(gpu) => write!(f, "{} Fan", gpu.name),
         }
     }
 }
@@ -55,18 +55,49 @@ pub fn draw_confirm_dialog(f: &mut Frame, title: &str, message: &str, app: &App)
 }
 
 pub fn draw_info_dialog(f: &mut Frame, app: &App) {
-    let area = centered_rect(70, 50, f.area());
-    
-    let processes = match app.process_sort {
-        crate::app::ProcessSortMode::Cpu => app.metrics.top_processes_by_cpu(30),
-        crate::app::ProcessSortMode::Memory => app.metrics.top_processes_by_memory(30),
-        _ => app.metrics.top_processes_by_cpu(30),
-    };
+    let area = centered_rect(70, 70, f.area());
     
-    let selected = processes.get(app.selected_process_index);
+    let processes = app.visible_processes();
+    let selected = app.get_selected_pid()
+        .and_then(|pid| processes.iter().find(|p| p.pid == pid));
     
     let text = if let Some(proc) = selected {
-        vec![
+        let container = app.containers.get(proc.pid)
+            .map(|c| format!("{} ({}, {})", c.name, c.runtime.name(), c.id.chars().take(12).collect::<String>()))
+            .unwrap_or_else(|| "-".to_string());
+        let disk = proc.io
+            .map(|io| format!("{:.1} KB/s read, {:.1} KB/s written ({:.0} / {:.0} syscalls/s, {:.1} KB/s cancelled)",
+                io.read_bytes_per_sec / 1024.0, io.write_bytes_per_sec / 1024.0,
+                io.read_syscalls_per_sec, io.write_syscalls_per_sec,
+                io.cancelled_write_bytes_per_sec / 1024.0))
+            .unwrap_or_else(|| "not permitted".to_string());
+        let mb = |bytes: u64| format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0);
+        let memory_detail = proc.memory_detail
+            .map(|m| format!("PSS {} · USS {} · shared {} · swap {} (SwapPss {})",
+                mb(m.pss), mb(m.uss), mb(m.shared), mb(m.swap), mb(m.swap_pss)))
+            .unwrap_or_else(|| "PSS/USS not readable".to_string());
+        let details = proc.stat
+            .map(|s| format!("user {} · PPID {} · state {} · {} threads · nice {} · CPU time {}{}",
+                proc.user, s.ppid, s.state, s.threads, s.nice,
+                super::ui::format_duration(s.cpu_time),
+                s.elapsed().map(|e| format!(" · up {}", super::ui::format_duration(e))).unwrap_or_default()))
+            .unwrap_or_else(|| format!("user {}", proc.user));
+        let sockets = app.sockets.sockets(proc.pid);
+        let listening: Vec<String> = sockets.iter()
+            .filter(|s| s.listening)
+            .map(|s| match s.local_port() {
+                Some(port) => format!("{}/{}", port, s.protocol.name()),
+                None => s.path.clone().unwrap_or_else(|| "unix".to_string()),
+            })
+            .collect();
+        let connections: Vec<String> = sockets.iter()
+            .filter(|s| !s.listening && s.protocol != crate::process::SocketProtocol::Unix)
+            .map(|s| s.describe())
+            .collect();
+        let unix_connected = sockets.iter()
+            .filter(|s| !s.listening && s.protocol == crate::process::SocketProtocol::Unix)
+            .count();
+        let mut lines = vec![
             Line::from(""),
             Line::from(vec![
                 Span::styled("Name: ", Style::default()
@@ -81,6 +112,7 @@ pub fn draw_info_dialog(f: &mut Frame, app: &App) {
                     .add_modifier(Modifier::BOLD)),
                 Span::styled(format!("{}", proc.pid), Style::default().fg(Theme::TEXT)),
             ]),
+            Line::from(Span::styled(details, Style::default().fg(Theme::SUBTEXT0))),
             Line::from(""),
             Line::from(vec![
                 Span::styled("CPU: ", Style::default()
@@ -94,16 +126,56 @@ pub fn draw_info_dialog(f: &mut Frame, app: &App) {
                     .fg(Theme::SUBTEXT1)
                     .add_modifier(Modifier::BOLD)),
                 Span::styled(
-                    format!("{:.2} MB", proc.memory() as f64 / 1024.0 / 1024.0),
+                    format!("{:.2} MB RSS", proc.memory() as f64 / 1024.0 / 1024.0),
                     Style::default().fg(Theme::TEAL)
                 ),
             ]),
+            Line::from(Span::styled(memory_detail, Style::default().fg(Theme::TEAL))),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Disk I/O: ", Style::default()
+                    .fg(Theme::SUBTEXT1)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(disk, Style::default().fg(Theme::PEACH)),
+            ]),
             Line::from(""),
+            Line::from(vec![
+                Span::styled("Container: ", Style::default()
+                    .fg(Theme::SUBTEXT1)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(container, Style::default().fg(Theme::TEXT)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Listening: ", Style::default()
+                    .fg(Theme::SUBTEXT1)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(if listening.is_empty() { "-".to_string() } else { listening.join(", ") },
+                    Style::default().fg(Theme::GREEN)),
+            ]),
+            Line::from(vec![
+                Span::styled("Connections: ", Style::default()
+                    .fg(Theme::SUBTEXT1)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{} inet, {} unix", connections.len(), unix_connected),
+                    Style::default().fg(Theme::TEXT)),
+            ]),
+        ];
+        // As many connections as fit; the rest is summarised
+        const MAX_CONNECTIONS: usize = 6;
+        lines.extend(connections.iter().take(MAX_CONNECTIONS)
+            .map(|c| Line::from(Span::styled(c.clone(), Style::default().fg(Theme::SUBTEXT0)))));
+        if connections.len() > MAX_CONNECTIONS {
+            lines.push(Line::from(Span::styled(format!("… {} more", connections.len() - MAX_CONNECTIONS),
+                Style::default().fg(Theme::SUBTEXT0))));
+        }
+        lines.extend([
             Line::from(""),
             Line::from(Span::styled("[ESC] Close", Style::default()
                 .fg(Theme::SUBTEXT1)
                 .add_modifier(Modifier::ITALIC))),
-        ]
+        ]);
+        lines
     } else {
         vec![
             Line::from(""),
@@ -131,6 +203,155 @@ pub fn draw_info_dialog(f: &mut Frame, app: &App) {
     f.render_widget(paragraph, area);
 }
 
+pub fn draw_gpu_control_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, f.area());
+    let Some(dialog) = &app.gpu_control else { return };
+
+    let key_style = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(&dialog.gpu_name, Style::default()
+            .fg(Theme::TEXT)
+            .add_modifier(Modifier::BOLD))),
+        Line::from(""),
+    ];
+
+    for field in dialog.fields() {
+        let selected = field == dialog.field;
+        let marker = if selected { "▶ " } else { "  " };
+        let value_style = if selected {
+            Style::default().fg(Theme::PEACH).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Theme::TEXT)
+        };
+        let mut spans = vec![
+            Span::styled(marker, Style::default().fg(Theme::PEACH)),
+            Span::styled(format!("{}: ", field.label()), Style::default().fg(Theme::SUBTEXT1)),
+            Span::styled(format!("◀ {} ▶", dialog.value_label(field)), value_style),
+        ];
+        if let (crate::app::ControlField::PowerCap, Some(range)) = (field, dialog.capabilities.power_cap) {
+            spans.push(Span::styled(format!("  ({:.0}-{:.0} W)", range.min, range.max),
+                Style::default().fg(Theme::SUBTEXT0)));
+        }
+        text.push(Line::from(spans));
+    }
+
+    text.push(Line::from(""));
+    match &dialog.preview {
+        Some(Ok(changes)) => {
+            text.push(Line::from(Span::styled("Dry run OK, will write:", Style::default().fg(Theme::GREEN))));
+            for change in changes {
+                text.push(Line::from(Span::styled(change.clone(), Style::default().fg(Theme::SUBTEXT1))));
+            }
+            text.push(Line::from(""));
+            text.push(Line::from(vec![
+                Span::styled("[Y]", key_style(Theme::GREEN)),
+                Span::styled(" Apply", Style::default().fg(Theme::TEXT)),
+            ]));
+        }
+        Some(Err(e)) => {
+            text.push(Line::from(Span::styled(e.clone(), Style::default().fg(Theme::RED))));
+        }
+        None => {
+            text.push(Line::from(Span::styled("Press Enter to check the change before applying it",
+                Style::default().fg(Theme::SUBTEXT0).add_modifier(Modifier::ITALIC))));
+        }
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("[↑↓]", key_style(Theme::LAVENDER)),
+        Span::styled(" Setting  ", Style::default().fg(Theme::TEXT)),
+        Span::styled("[←→]", key_style(Theme::LAVENDER)),
+        Span::styled(" Value  ", Style::default().fg(Theme::TEXT)),
+        Span::styled("[Enter]", key_style(Theme::BLUE)),
+        Span::styled(" Dry run  ", Style::default().fg(Theme::TEXT)),
+        Span::styled("[r]", key_style(Theme::YELLOW)),
+        Span::styled(" Restore  ", Style::default().fg(Theme::TEXT)),
+        Span::styled("[g]", key_style(Theme::MAUVE)),
+        Span::styled(" Next GPU  ", Style::default().fg(Theme::TEXT)),
+        Span::styled("[ESC]", key_style(Theme::RED)),
+        Span::styled(" Close", Style::default().fg(Theme::TEXT)),
+    ]));
+
+    let block = Block::default()
+        .title(vec![
+            Span::styled("⚙ ", Style::default().fg(Theme::PEACH)),
+            Span::styled("GPU Control", Style::default()
+                .fg(Theme::PEACH)
+                .add_modifier(Modifier::BOLD)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::PEACH))
+        .style(Style::default().bg(Theme::CRUST));
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn draw_gpu_details_dialog(f: &mut Frame, app: &App) {
+    let area = centered_rect(70, 50, f.area());
+    let (Some(key), Some(gpu_manager)) = (&app.gpu_details, &app.gpu) else { return };
+
+    let name = app.gpu_info_cache.iter()
+        .find(|gpu| gpu.key == *key)
+        .map(|gpu| format!("{} - {}", gpu.vendor, gpu.name))
+        .unwrap_or_else(|| key.clone());
+    let key_style = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(name, Style::default()
+            .fg(Theme::TEXT)
+            .add_modifier(Modifier::BOLD))),
+        Line::from(""),
+    ];
+
+    let entries = gpu_manager.properties(key).map(|p| p.entries()).unwrap_or_default();
+    if entries.is_empty() {
+        text.push(Line::from(Span::styled("The driver reports no device details",
+            Style::default().fg(Theme::SUBTEXT0).add_modifier(Modifier::ITALIC))));
+    }
+    for (label, value) in entries {
+        text.push(Line::from(vec![
+            Span::styled(format!("{}: ", label), Style::default().fg(Theme::SUBTEXT1)),
+            Span::styled(value, Style::default().fg(Theme::TEXT)),
+        ]));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("[v]", key_style(Theme::SAPPHIRE)),
+        Span::styled(" Next GPU  ", Style::default().fg(Theme::TEXT)),
+        Span::styled("[ESC]", key_style(Theme::RED)),
+        Span::styled(" Close", Style::default().fg(Theme::TEXT)),
+    ]));
+
+    let block = Block::default()
+        .title(vec![
+            Span::styled("ℹ ", Style::default().fg(Theme::SAPPHIRE)),
+            Span::styled("GPU Details", Style::default()
+                .fg(Theme::SAPPHIRE)
+                .add_modifier(Modifier::BOLD)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::SAPPHIRE))
+        .style(Style::default().bg(Theme::CRUST));
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -103,3 +103,23 @@ pub fn is_arrow_right(key: &KeyEvent) -> bool {
 pub fn should_enter_history(key: &KeyEvent) -> bool {
     matches!(key.code, KeyCode::Char('h') | KeyCode::Char('H'))
 }
+
+pub fn should_show_gpu_control(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('g') | KeyCode::Char('G'))
+}
+
+pub fn should_show_gpu_details(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('v') | KeyCode::Char('V'))
+}
+
+pub fn should_toggle_cgroups(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C'))
+}
+
+pub fn should_group_by_container(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('b') | KeyCode::Char('B'))
+}
+
+pub fn is_restore(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('r') | KeyCode::Char('R'))
+}
@@ -91,6 +91,38 @@ fn run_app<B: ratatui::backend::Backend>(
                         }
                         continue;
                     }
+                    DialogMode::GpuControl => {
+                        if events::is_escape(&key) {
+                            app.close_dialog();
+                        } else if events::is_enter(&key) {
+                            app.preview_gpu_control();
+                        } else if events::is_yes(&key) {
+                            app.apply_gpu_control();
+                        } else if events::is_restore(&key) {
+                            app.restore_gpu_controls();
+                        } else if events::should_show_gpu_control(&key) || events::should_toggle_view(&key) {
+                            app.show_gpu_control_dialog();
+                        } else if let Some(dialog) = app.gpu_control.as_mut() {
+                            if events::is_arrow_up(&key) {
+                                dialog.move_field(-1);
+                            } else if events::is_arrow_down(&key) {
+                                dialog.move_field(1);
+                            } else if events::is_arrow_left(&key) {
+                                dialog.adjust(-1);
+                            } else if events::is_arrow_right(&key) {
+                                dialog.adjust(1);
+                            }
+                        }
+                        continue;
+                    }
+                    DialogMode::GpuDetails => {
+                        if events::is_escape(&key) || events::is_enter(&key) {
+                            app.close_dialog();
+                        } else if events::should_show_gpu_details(&key) || events::should_toggle_view(&key) {
+                            app.show_gpu_details_dialog();
+                        }
+                        continue;
+                    }
                     DialogMode::None => {}
                 }
                 
@@ -108,6 +140,14 @@ fn run_app<B: ratatui::backend::Backend>(
                     } else {
                         app.enter_history_mode();
                     }
+                } else if events::should_toggle_cgroups(&key) {
+                    app.toggle_cgroup_view();
+                } else if app.view_mode == crate::app::ViewMode::Cgroups
+                    && (events::is_arrow_up(&key) || events::is_arrow_down(&key)) {
+                    app.move_cgroup_selection(if events::is_arrow_up(&key) { -1 } else { 1 });
+                } else if app.view_mode == crate::app::ViewMode::Cgroups
+                    && (events::is_arrow_left(&key) || events::is_arrow_right(&key)) {
+                    app.set_selected_cgroup_expanded(events::is_arrow_right(&key));
                 } else if events::is_arrow_left(&key) {
                     if app.view_mode == crate::app::ViewMode::History {
                         app.playback_step_backward();
@@ -126,10 +166,14 @@ fn run_app<B: ratatui::backend::Backend>(
                     app.enter_filter_mode();
                 } else if events::should_cycle_sort(&key) {
                     app.cycle_sort();
+                } else if events::should_group_by_container(&key) {
+                    app.toggle_container_grouping();
+                } else if events::is_enter(&key) && app.group_by_container {
+                    app.filter_selected_container();
                 } else if events::is_arrow_up(&key) {
                     app.move_selection_up();
                 } else if events::is_arrow_down(&key) {
-                    let max = 30; // Top 30 processes
+                    let max = app.process_row_count();
                     app.move_selection_down(max);
                 } else if events::should_show_kill_dialog(&key) {
                     app.show_kill_dialog();
@@ -137,6 +181,10 @@ fn run_app<B: ratatui::backend::Backend>(
                     app.show_terminate_dialog();
                 } else if events::should_show_info(&key) {
                     app.show_info_dialog();
+                } else if events::should_show_gpu_control(&key) {
+                    app.show_gpu_control_dialog();
+                } else if events::should_show_gpu_details(&key) {
+                    app.show_gpu_details_dialog();
                 }
             }
             events::AppEvent::Tick => {
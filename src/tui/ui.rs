@@ -7,7 +7,9 @@ use ratatui::{
     Frame,
 };
 use crate::app::{App, ViewMode, ProcessSortMode, DialogMode};
+use crate::config::ProcessColumn;
 use crate::alerts::AlertLevel;
+use crate::gpu::{PcieLink, ThrottleReason};
 use super::theme::CatppuccinTheme as Theme;
 
 pub fn draw(f: &mut Frame, app: &App) {
@@ -15,6 +17,7 @@ pub fn draw(f: &mut Frame, app: &App) {
         ViewMode::Dashboard => draw_dashboard(f, app),
         ViewMode::Processes => draw_processes_view(f, app),
         ViewMode::History => draw_history_view(f, app),
+        ViewMode::Cgroups => draw_cgroups_view(f, app),
     }
     
     // Draw dialogs on top
@@ -22,6 +25,8 @@ pub fn draw(f: &mut Frame, app: &App) {
         DialogMode::ConfirmKill => draw_confirm_dialog(f, "Kill Process", "Send SIGKILL?", app),
         DialogMode::ConfirmTerminate => draw_confirm_dialog(f, "Terminate Process", "Send SIGTERM?", app),
         DialogMode::ProcessInfo => draw_info_dialog(f, app),
+        DialogMode::GpuControl => draw_gpu_control_dialog(f, app),
+        DialogMode::GpuDetails => draw_gpu_details_dialog(f, app),
         DialogMode::None => {}
     }
 }
@@ -94,11 +99,12 @@ fn draw_header(f: &mut Frame, area: Rect, _app: &App) {
 }
 
 fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
-    let has_gpu = app.gpu.is_some();
+    let has_gpu = app.has_gpu();
     // Always show trends panel if enabled, even when empty
     let show_trends = app.config.trends.enabled;
+    let show_pressure = app.pressure.available();
     
-    let constraints = if has_gpu && show_trends {
+    let mut constraints = if has_gpu && show_trends {
         vec![
             Constraint::Percentage(22),  // CPU
             Constraint::Percentage(20),  // Memory
@@ -126,16 +132,41 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
         ]
     };
     
+    if show_pressure {
+        // Below GPU + Info, one line per resource
+        constraints.insert(3, Constraint::Length(6));
+    }
+    
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
         .split(area);
 
     let mut idx = 0;
-    draw_cpu_panel(f, chunks[idx], app);
+    if app.network.interfaces().iter().any(|iface| !iface.loopback) {
+        let cpu_network_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[idx]);
+
+        draw_cpu_panel(f, cpu_network_chunks[0], app);
+        draw_network_panel(f, cpu_network_chunks[1], app);
+    } else {
+        draw_cpu_panel(f, chunks[idx], app);
+    }
     idx += 1;
     
-    draw_memory_panel(f, chunks[idx], app);
+    if app.disk_io.devices().is_empty() {
+        draw_memory_panel(f, chunks[idx], app);
+    } else {
+        let memory_disk_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[idx]);
+
+        draw_memory_panel(f, memory_disk_chunks[0], app);
+        draw_disk_panel(f, memory_disk_chunks[1], app);
+    }
     idx += 1;
     
     if has_gpu {
@@ -146,10 +177,15 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
             .split(chunks[idx]);
         
         draw_gpu_panel(f, gpu_info_chunks[0], app);
-        draw_info_panel(f, gpu_info_chunks[1], app);
+        draw_info_and_sensors(f, gpu_info_chunks[1], app);
         idx += 1;
     } else {
-        draw_info_panel(f, chunks[idx], app);
+        draw_info_and_sensors(f, chunks[idx], app);
+        idx += 1;
+    }
+
+    if show_pressure {
+        draw_pressure_panel(f, chunks[idx], app);
         idx += 1;
     }
     
@@ -158,28 +194,128 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// System-wide pressure on the left, the most stalled cgroups on the right
+fn draw_pressure_panel(f: &mut Frame, area: Rect, app: &App) {
+    let pressure_color = |avg10: f32| if avg10 >= 20.0 {
+        Theme::status_critical()
+    } else if avg10 >= 5.0 {
+        Theme::status_warning()
+    } else {
+        Theme::status_ok()
+    };
+    let averages = |line: Option<crate::metrics::PressureLine>| match line {
+        Some(line) => Span::styled(
+            format!("{:>6.2} {:>6.2} {:>6.2}", line.avg10, line.avg60, line.avg300),
+            Style::default().fg(pressure_color(line.avg10)),
+        ),
+        None => Span::styled(format!("{:>20}", "-"), Style::default().fg(Theme::OVERLAY0)),
+    };
+
+    let system: Vec<Line> = app.pressure.system().iter()
+        .map(|(resource, pressure)| Line::from(vec![
+            Span::styled(format!("{:<7}", resource.name()), Style::default().fg(Theme::TEXT).add_modifier(Modifier::BOLD)),
+            Span::styled("some ", Style::default().fg(Theme::SUBTEXT0)),
+            averages(pressure.some),
+            Span::styled("  full ", Style::default().fg(Theme::SUBTEXT0)),
+            averages(pressure.full),
+        ]))
+        .collect();
+
+    let mut cgroups: Vec<_> = app.pressure.cgroups().iter()
+        .filter(|cgroup| cgroup.worst_some() > 0.0)
+        .collect();
+    cgroups.sort_by(|a, b| b.worst_some().total_cmp(&a.worst_some()));
+    let cgroup_lines: Vec<Line> = cgroups.iter()
+        .take(4)
+        .map(|cgroup| {
+            let mut spans = vec![Span::styled(format!("{:<36}", cgroup.path), Style::default().fg(Theme::SUBTEXT1))];
+            for (resource, pressure) in &cgroup.resources {
+                if let Some(some) = pressure.some {
+                    spans.push(Span::styled(
+                        format!(" {} {:.1}", resource.name(), some.avg10),
+                        Style::default().fg(pressure_color(some.avg10)),
+                    ));
+                }
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let block = Block::default()
+        .title(vec![
+            Span::styled("╭─ ", Style::default().fg(Theme::FLAMINGO)),
+            Span::styled("Pressure (avg10 avg60 avg300 %)", Style::default()
+                .fg(Theme::FLAMINGO)
+                .add_modifier(Modifier::BOLD)),
+            Span::styled(" ─╮", Style::default().fg(Theme::FLAMINGO)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::FLAMINGO))
+        .style(Style::default().bg(Theme::MANTLE));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    f.render_widget(Paragraph::new(system), chunks[0]);
+    f.render_widget(Paragraph::new(cgroup_lines), chunks[1]);
+}
+
+/// System info, with the Sensors panel beside it when the machine has any
+fn draw_info_and_sensors(f: &mut Frame, area: Rect, app: &App) {
+    if app.sensors.readings().is_empty() {
+        draw_info_panel(f, area, app);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    draw_info_panel(f, chunks[0], app);
+    draw_sensors_panel(f, chunks[1], app);
+}
+
 fn draw_cpu_panel(f: &mut Frame, area: Rect, app: &App) {
     use ratatui::widgets::Sparkline;
     
     let cpu_usage = app.metrics.global_cpu_usage();
     let cpu_color = Theme::cpu_color(cpu_usage);
+    let breakdown = app.cpu_times.total();
     
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(area);
+    let chunks = if breakdown.is_some() {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Percentage(30)])
+            .split(area)
+    } else {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area)
+    };
+    let history_chunk = chunks[chunks.len() - 1];
     
     // Gauge
     let label = format!("{:.1}%", cpu_usage);
+    let mut title = vec![
+        Span::styled("╭─ ", Style::default().fg(Theme::BLUE)),
+        Span::styled("CPU", Style::default()
+            .fg(Theme::BLUE)
+            .add_modifier(Modifier::BOLD)),
+    ];
+    if let Some(temp) = app.sensors.cpu_temperature() {
+        title.push(Span::styled(format!(" {:.0}°C", temp), Style::default()
+            .fg(Theme::gpu_temp_color(temp))
+            .add_modifier(Modifier::BOLD)));
+    }
+    title.push(Span::styled(" ─╮", Style::default().fg(Theme::BLUE)));
     let gauge = Gauge::default()
         .block(Block::default()
-            .title(vec![
-                Span::styled("╭─ ", Style::default().fg(Theme::BLUE)),
-                Span::styled("CPU", Style::default()
-                    .fg(Theme::BLUE)
-                    .add_modifier(Modifier::BOLD)),
-                Span::styled(" ─╮", Style::default().fg(Theme::BLUE)),
-            ])
+            .title(title)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(Theme::BLUE))
@@ -191,6 +327,10 @@ fn draw_cpu_panel(f: &mut Frame, area: Rect, app: &App) {
         .percent(cpu_usage.min(100.0) as u16);
     
     f.render_widget(gauge, chunks[0]);
+
+    if let Some(total) = breakdown {
+        draw_cpu_breakdown(f, chunks[1], total, app.cpu_times.cores());
+    }
     
     // Sparkline
     let history_data: Vec<u64> = app.history.cpu_usage.get_values()
@@ -209,10 +349,89 @@ fn draw_cpu_panel(f: &mut Frame, area: Rect, app: &App) {
             .data(&history_data)
             .style(Style::default().fg(Theme::BLUE));
         
-        f.render_widget(sparkline, chunks[1]);
+        f.render_widget(sparkline, history_chunk);
     }
 }
 
+/// States of the CPU time breakdown with their legend label and color, in
+/// stacking order
+fn cpu_time_segments(cpu: &crate::metrics::CpuBreakdown) -> [(&'static str, f32, ratatui::style::Color); 8] {
+    [
+        ("usr", cpu.user, Theme::BLUE),
+        ("nice", cpu.nice, Theme::TEAL),
+        ("sys", cpu.system, Theme::RED),
+        ("irq", cpu.irq, Theme::PEACH),
+        ("sirq", cpu.softirq, Theme::MAROON),
+        ("iow", cpu.iowait, Theme::YELLOW),
+        ("steal", cpu.steal, Theme::MAUVE),
+        ("guest", cpu.guest, Theme::GREEN),
+    ]
+}
+
+/// Stacked bar of the CPU time states; the rest of the width is idle
+fn cpu_stacked_bar(cpu: &crate::metrics::CpuBreakdown, width: usize) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut cumulative = 0.0;
+    let mut drawn = 0;
+    for (_, percent, color) in cpu_time_segments(cpu) {
+        // Round the running total so segments never drift past the width
+        cumulative += percent;
+        let end = ((cumulative.clamp(0.0, 100.0) / 100.0) * width as f32).round() as usize;
+        if end > drawn {
+            spans.push(Span::styled("█".repeat(end - drawn), Style::default().fg(color)));
+            drawn = end;
+        }
+    }
+    spans.push(Span::styled("░".repeat(width.saturating_sub(drawn)), Style::default().fg(Theme::SURFACE1)));
+    spans
+}
+
+/// Legend with the total shares, the total bar, then one bar per core.
+/// Cores are laid out in as many columns as needed to fit.
+fn draw_cpu_breakdown(f: &mut Frame, area: Rect, total: &crate::metrics::CpuBreakdown, cores: &[crate::metrics::CpuBreakdown]) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::BLUE))
+        .style(Style::default().bg(Theme::MANTLE));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height == 0 || inner.width < 8 {
+        return;
+    }
+
+    let legend: Vec<Span> = cpu_time_segments(total).into_iter()
+        .filter(|(label, percent, _)| *percent >= 0.05 || *label == "steal" || *label == "iow")
+        .map(|(label, percent, color)| Span::styled(format!("{} {:.1}% ", label, percent), Style::default().fg(color)))
+        .collect();
+    let mut lines = vec![Line::from(legend)];
+
+    let label_width = 6;
+    let mut total_bar = vec![Span::styled(format!("{:<label_width$}", "all"), Style::default().fg(Theme::SUBTEXT0))];
+    total_bar.extend(cpu_stacked_bar(total, inner.width as usize - label_width));
+    lines.push(Line::from(total_bar));
+
+    let rows = (inner.height as usize).saturating_sub(lines.len());
+    if rows > 0 && !cores.is_empty() {
+        let columns = cores.len().div_ceil(rows);
+        let column_width = inner.width as usize / columns;
+        if column_width > label_width + 2 {
+            for row in 0..rows.min(cores.len()) {
+                let mut spans = Vec::new();
+                for core in cores.iter().skip(row).step_by(rows) {
+                    let label = core.name.trim_start_matches("cpu");
+                    spans.push(Span::styled(format!("{:<label_width$}", label), Style::default().fg(Theme::SUBTEXT0)));
+                    spans.extend(cpu_stacked_bar(core, column_width - label_width - 1));
+                    spans.push(Span::raw(" "));
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
 fn draw_memory_panel(f: &mut Frame, area: Rect, app: &App) {
     use ratatui::widgets::Sparkline;
     let mem_percent = app.metrics.memory_usage_percent();
@@ -312,8 +531,215 @@ fn draw_memory_panel(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+fn draw_disk_panel(f: &mut Frame, area: Rect, app: &App) {
+    use ratatui::widgets::Sparkline;
+
+    let block = Block::default()
+        .title(vec![
+            Span::styled("╭─ ", Style::default().fg(Theme::PEACH)),
+            Span::styled("Disk I/O", Style::default()
+                .fg(Theme::PEACH)
+                .add_modifier(Modifier::BOLD)),
+            Span::styled(" ─╮", Style::default().fg(Theme::PEACH)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::PEACH))
+        .style(Style::default().bg(Theme::MANTLE));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Two rows per device: the numbers, then a read+write sparkline
+    let devices = app.disk_io.devices();
+    let shown = devices.len().min(inner.height as usize / 2);
+    if shown == 0 {
+        return;
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(2); shown])
+        .split(inner);
+
+    for (disk, row) in devices.iter().zip(rows.iter()) {
+        let util_color = Theme::cpu_color(disk.utilization);
+        let mounts = if disk.mount_points.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", disk.mount_points.join(","))
+        };
+        let latency = disk.avg_latency_ms
+            .map(|ms| format!("{:.1}ms", ms))
+            .unwrap_or_else(|| "-".to_string());
+
+        let line = Line::from(vec![
+            Span::styled(disk.name.clone(), Style::default().fg(Theme::TEXT).add_modifier(Modifier::BOLD)),
+            Span::styled(mounts, Style::default().fg(Theme::OVERLAY1)),
+            Span::styled(format!(" R {}", format_rate(disk.read_bytes_per_sec)), Style::default().fg(Theme::GREEN)),
+            Span::styled(format!(" W {}", format_rate(disk.write_bytes_per_sec)), Style::default().fg(Theme::MAUVE)),
+            Span::styled(
+                format!(" {:.0}/{:.0} IOPS", disk.read_iops, disk.write_iops),
+                Style::default().fg(Theme::SUBTEXT0),
+            ),
+            Span::styled(format!(" {:.0}%", disk.utilization), Style::default().fg(util_color)),
+            Span::styled(
+                format!(" {} q{}", latency, disk.in_flight),
+                Style::default().fg(Theme::SUBTEXT0),
+            ),
+        ]);
+
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(*row);
+        f.render_widget(Paragraph::new(line), parts[0]);
+
+        if let Some(history) = app.history.disk(&disk.name) {
+            let data: Vec<u64> = history.read.get_values().iter()
+                .zip(history.write.get_values())
+                .map(|(read, write)| (*read + write) as u64)
+                .collect();
+            let sparkline = Sparkline::default()
+                .data(&data)
+                .style(Style::default().fg(Theme::PEACH));
+            f.render_widget(sparkline, parts[1]);
+        }
+    }
+}
+
+fn draw_network_panel(f: &mut Frame, area: Rect, app: &App) {
+    use ratatui::widgets::Sparkline;
+
+    let block = Block::default()
+        .title(vec![
+            Span::styled("╭─ ", Style::default().fg(Theme::LAVENDER)),
+            Span::styled("Network", Style::default()
+                .fg(Theme::LAVENDER)
+                .add_modifier(Modifier::BOLD)),
+            Span::styled(" ─╮", Style::default().fg(Theme::LAVENDER)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::LAVENDER))
+        .style(Style::default().bg(Theme::MANTLE));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Links that are up come first; loopback is left out
+    let mut interfaces: Vec<_> = app.network.interfaces().iter()
+        .filter(|iface| !iface.loopback)
+        .collect();
+    interfaces.sort_by_key(|iface| !iface.is_up());
+
+    // Three rows per interface: link details, rates, then a rx+tx sparkline
+    let shown = interfaces.len().min(inner.height as usize / 3);
+    if shown == 0 {
+        return;
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3); shown])
+        .split(inner);
+
+    for (iface, row) in interfaces.iter().zip(rows.iter()) {
+        let state_color = if iface.is_up() { Theme::GREEN } else { Theme::OVERLAY1 };
+        let mut link = vec![
+            Span::styled(iface.name.clone(), Style::default().fg(Theme::TEXT).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(" {}", iface.operstate), Style::default().fg(state_color)),
+        ];
+        if let Some(speed) = iface.speed_mbps {
+            link.push(Span::styled(format!(" {} Mb/s", speed), Style::default().fg(Theme::SUBTEXT0)));
+        }
+        if let Some(mtu) = iface.mtu {
+            link.push(Span::styled(format!(" MTU {}", mtu), Style::default().fg(Theme::SUBTEXT0)));
+        }
+        if let Some(mac) = &iface.mac {
+            link.push(Span::styled(format!(" {}", mac), Style::default().fg(Theme::OVERLAY1)));
+        }
+        if !iface.addresses.is_empty() {
+            link.push(Span::styled(format!(" {}", iface.addresses.join(" ")), Style::default().fg(Theme::SKY)));
+        }
+
+        let mut rates = vec![
+            Span::styled(format!("↓ {}", format_rate(iface.rx_bytes_per_sec)), Style::default().fg(Theme::GREEN)),
+            Span::styled(format!("  ↑ {}", format_rate(iface.tx_bytes_per_sec)), Style::default().fg(Theme::MAUVE)),
+            Span::styled(
+                format!("  {:.0}/{:.0} pkt/s", iface.rx_packets_per_sec, iface.tx_packets_per_sec),
+                Style::default().fg(Theme::SUBTEXT0),
+            ),
+        ];
+        let errors = iface.rx_errors_per_sec + iface.tx_errors_per_sec;
+        let drops = iface.rx_drops_per_sec + iface.tx_drops_per_sec;
+        if errors > 0.0 || drops > 0.0 {
+            rates.push(Span::styled(
+                format!("  err {:.0}/s drop {:.0}/s", errors, drops),
+                Style::default().fg(Theme::status_warning()),
+            ));
+        }
+
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+            .split(*row);
+        f.render_widget(Paragraph::new(Line::from(link)), parts[0]);
+        f.render_widget(Paragraph::new(Line::from(rates)), parts[1]);
+
+        if let Some(history) = app.history.network(&iface.name) {
+            let data: Vec<u64> = history.rx.get_values().iter()
+                .zip(history.tx.get_values())
+                .map(|(rx, tx)| (*rx + tx) as u64)
+                .collect();
+            let sparkline = Sparkline::default()
+                .data(&data)
+                .style(Style::default().fg(Theme::LAVENDER));
+            f.render_widget(sparkline, parts[2]);
+        }
+    }
+}
+
+fn draw_sensors_panel(f: &mut Frame, area: Rect, app: &App) {
+    use crate::metrics::SensorKind;
+
+    let lines: Vec<Line> = app.sensors.readings().iter()
+        .map(|reading| {
+            let (value, color) = match reading.kind {
+                SensorKind::Temperature => (
+                    format!("{:.1}°C", reading.value),
+                    Theme::gpu_temp_color(reading.value),
+                ),
+                SensorKind::Fan => (format!("{:.0} RPM", reading.value), Theme::SKY),
+                SensorKind::Voltage => (format!("{:.3} V", reading.value), Theme::YELLOW),
+            };
+            let mut spans = vec![
+                Span::styled(format!("{:<12}", reading.chip), Style::default().fg(Theme::OVERLAY1)),
+                Span::styled(format!("{:<14}", reading.label), Style::default().fg(Theme::SUBTEXT1)),
+                Span::styled(format!("{:>10}", value), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            ];
+            if let Some(critical) = reading.critical {
+                spans.push(Span::styled(format!("  crit {:.0}°C", critical), Style::default().fg(Theme::OVERLAY0)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default()
+            .title(vec![
+                Span::styled("╭─ ", Style::default().fg(Theme::MAROON)),
+                Span::styled("Sensors", Style::default()
+                    .fg(Theme::MAROON)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" ─╮", Style::default().fg(Theme::MAROON)),
+            ])
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Theme::MAROON))
+            .style(Style::default().bg(Theme::MANTLE)));
+
+    f.render_widget(paragraph, area);
+}
+
 fn draw_gpu_panel(f: &mut Frame, area: Rect, app: &App) {
-    if app.gpu.is_some() {
+    if app.has_gpu() {
         let gpu_info = &app.gpu_info_cache; // Use cached data!
         
         if gpu_info.is_empty() {
@@ -354,6 +780,7 @@ fn draw_gpu_panel(f: &mut Frame, area: Rect, app: &App) {
                     used / 1024 / 1024, 
                     total / 1024 / 1024,
                     mem_percent),
+                (Some(used), None) => format!("{} MB", used / 1024 / 1024),
                 _ => "N/A".to_string(),
             };
 
@@ -380,6 +807,44 @@ fn draw_gpu_panel(f: &mut Frame, area: Rect, app: &App) {
                     Span::styled(mem_str, Style::default().fg(Theme::PINK)),
                 ]),
             ];
+
+            // AMD memory pools: VRAM, GTT (system RAM mapped for the GPU)
+            // and the CPU-visible part of VRAM
+            if gpu.gtt_total.is_some() || gpu.vis_vram_total.is_some() {
+                let mut pool_parts = vec![Span::raw("   ├─ ")];
+                let pools = [
+                    ("VRAM", gpu.memory_usage_percent(), Theme::PINK),
+                    ("GTT", gpu.gtt_usage_percent(), Theme::PEACH),
+                    ("Visible", gpu.vis_vram_usage_percent(), Theme::FLAMINGO),
+                ];
+                for (label, percent, color) in pools {
+                    let Some(percent) = percent else { continue };
+                    pool_parts.push(Span::styled(format!("{} ", label), Style::default().fg(Theme::SUBTEXT0)));
+                    pool_parts.push(Span::styled(text_bar(percent, 10), Style::default().fg(color)));
+                    pool_parts.push(Span::styled(format!(" {:.0}%  ", percent), Style::default().fg(Theme::SUBTEXT1)));
+                }
+                lines.push(Line::from(pool_parts));
+            }
+            
+            // Individual sensors, when there is more than the primary one
+            if gpu.sensors.len() > 1 {
+                let mut sensor_parts = vec![
+                    Span::raw("   ├─ "),
+                    Span::styled("Sensors: ", Style::default().fg(Theme::SUBTEXT0)),
+                ];
+                for sensor in &gpu.sensors {
+                    sensor_parts.push(Span::styled(format!("{} ", sensor.label),
+                        Style::default().fg(Theme::SUBTEXT1)));
+                    sensor_parts.push(Span::styled(format!("{:.0}°C", sensor.temperature),
+                        Style::default().fg(Theme::gpu_temp_color(sensor.temperature))));
+                    if let Some(critical) = sensor.critical {
+                        sensor_parts.push(Span::styled(format!("/{:.0}", critical),
+                            Style::default().fg(Theme::SUBTEXT1)));
+                    }
+                    sensor_parts.push(Span::raw("  "));
+                }
+                lines.push(Line::from(sensor_parts));
+            }
             
             // Add power info if available
             if gpu.power_draw.is_some() || gpu.power_limit.is_some() {
@@ -409,7 +874,8 @@ fn draw_gpu_panel(f: &mut Frame, area: Rect, app: &App) {
             }
             
             // Add clocks and fan
-            let has_clocks = gpu.clock_speed.is_some() || gpu.memory_clock.is_some() || gpu.fan_speed.is_some();
+            let has_clocks = gpu.clock_speed.is_some() || gpu.memory_clock.is_some()
+                || gpu.fan_speed.is_some() || gpu.fan_rpm.is_some();
             if has_clocks {
                 let mut clock_parts = vec![
                     Span::raw("   ├─ "),
@@ -429,22 +895,93 @@ fn draw_gpu_panel(f: &mut Frame, area: Rect, app: &App) {
                     clock_parts.push(Span::raw("  "));
                 }
                 
-                if let Some(fan) = gpu.fan_speed {
+                let fan = gpu.fan_speed.map(|duty| format!("{}%", duty))
+                    .or_else(|| gpu.fan_rpm.map(|rpm| format!("{} RPM", rpm)));
+                if let Some(fan) = fan {
                     clock_parts.push(Span::styled("Fan: ", Style::default().fg(Theme::SUBTEXT0)));
-                    clock_parts.push(Span::styled(format!("{}%", fan), 
+                    clock_parts.push(Span::styled(fan, 
                         Style::default().fg(Theme::SKY)));
                 }
                 
                 lines.push(Line::from(clock_parts));
             }
             
+            // Video engines and PCIe link
+            let mut link_parts = vec![Span::raw("   ├─ ")];
+            let video = match (gpu.encoder_utilization, gpu.decoder_utilization, gpu.media_utilization) {
+                (None, None, Some(vcn)) => Some(format!("VCN {:.0}%", vcn)),
+                (None, None, None) => None,
+                (enc, dec, _) => Some(format!("ENC {} DEC {}",
+                    enc.map(|u| format!("{:.0}%", u)).unwrap_or_else(|| "N/A".to_string()),
+                    dec.map(|u| format!("{:.0}%", u)).unwrap_or_else(|| "N/A".to_string()))),
+            };
+            if let Some(video) = video {
+                link_parts.push(Span::styled("Video: ", Style::default().fg(Theme::SUBTEXT0)));
+                link_parts.push(Span::styled(video, Style::default().fg(Theme::MAUVE)));
+                link_parts.push(Span::raw("  "));
+            }
+            if let Some(link) = gpu.pcie_link {
+                let link_color = if link.is_degraded() { Theme::YELLOW } else { Theme::TEAL };
+                link_parts.push(Span::styled("PCIe: ", Style::default().fg(Theme::SUBTEXT0)));
+                link_parts.push(Span::styled(PcieLink::format(link.current_gen, link.current_width),
+                    Style::default().fg(link_color)));
+                if link.max_gen.is_some() || link.max_width.is_some() {
+                    link_parts.push(Span::styled(format!("/{}", PcieLink::format(link.max_gen, link.max_width)),
+                        Style::default().fg(Theme::SUBTEXT1)));
+                }
+            }
+            if let Some(throughput) = gpu.pcie_throughput {
+                link_parts.push(Span::styled(format!("  ↑{:.1} ↓{:.1} MB/s",
+                    throughput.tx_kbps as f32 / 1024.0,
+                    throughput.rx_kbps as f32 / 1024.0),
+                    Style::default().fg(Theme::SUBTEXT1)));
+            }
+            if link_parts.len() > 1 {
+                lines.push(Line::from(link_parts));
+            }
+            
+            // Only shown while clocks are actually held back
+            if let Some(reasons) = gpu.throttle_reasons.as_ref().filter(|r| !r.is_empty()) {
+                let labels: Vec<&str> = reasons.iter().map(|r| r.label()).collect();
+                let color = if reasons.contains(&ThrottleReason::Thermal) {
+                    Theme::RED
+                } else {
+                    Theme::YELLOW
+                };
+                lines.push(Line::from(vec![
+                    Span::raw("   ├─ "),
+                    Span::styled("Throttled: ", Style::default().fg(Theme::SUBTEXT0)),
+                    Span::styled(labels.join(", "), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                ]));
+            }
+            
             // Add process count
             if !gpu.processes.is_empty() {
-                lines.push(Line::from(vec![
+                let mut process_parts = vec![
                     Span::raw("   └─ "),
                     Span::styled(format!("Processes: {} active", gpu.processes.len()), 
                         Style::default().fg(Theme::LAVENDER).add_modifier(Modifier::BOLD)),
-                ]));
+                ];
+                
+                // Show the heaviest client so the user knows who holds the card
+                if let Some(top) = gpu.processes.iter().max_by_key(|p| p.memory_used) {
+                    let mem_str = top.memory_used
+                        .map(|m| format!("{} MB", m / 1024 / 1024))
+                        .unwrap_or_else(|| "N/A".to_string());
+                    let mut top_str = format!("  top: {} ({}", top.name, mem_str);
+                    if let Some(client_type) = top.client_type {
+                        top_str.push_str(&format!(" {}", client_type.short_label()));
+                    }
+                    // "-" when the driver has no sample for the process yet
+                    let util_str = top.utilization
+                        .map(|util| format!("{:.0}%", util))
+                        .unwrap_or_else(|| "-".to_string());
+                    top_str.push_str(&format!(", {}", util_str));
+                    top_str.push(')');
+                    process_parts.push(Span::styled(top_str, Style::default().fg(Theme::SUBTEXT1)));
+                }
+                
+                lines.push(Line::from(process_parts));
             } else {
                 lines.push(Line::from(vec![
                     Span::raw("   └─ "),
@@ -679,10 +1216,11 @@ fn draw_trends_panel(f: &mut Frame, area: Rect, app: &App) {
         };
 
         let metric_name = format!("{}", trend.metric);
+        let unit = trend.metric.unit();
         let rate_str = if trend.rate_per_minute >= 0.0 {
-            format!("+{:.1}%/min", trend.rate_per_minute)
+            format!("+{:.1}{}/min", trend.rate_per_minute, unit)
         } else {
-            format!("{:.1}%/min", trend.rate_per_minute)
+            format!("{:.1}{}/min", trend.rate_per_minute, unit)
         };
 
         lines.push(Line::from(vec![
@@ -713,7 +1251,7 @@ fn draw_trends_panel(f: &mut Frame, area: Rect, app: &App) {
         lines.push(Line::from(vec![
             Span::raw("    "),
             Span::styled("🔮 ", Style::default().fg(Theme::MAUVE)),
-            Span::styled(format!("5min: {:.1}%", trend.predicted_value_5min),
+            Span::styled(format!("5min: {:.1}{}", trend.predicted_value_5min, unit),
                 Style::default().fg(Theme::SUBTEXT1)),
         ]));
     }
@@ -748,20 +1286,27 @@ fn draw_processes_view(f: &mut Frame, app: &App) {
         .split(f.area());
 
     draw_header(f, chunks[0], app);
+
+    if app.group_by_container {
+        draw_container_groups(f, chunks[1], app);
+        draw_footer(f, chunks[2], app);
+        return;
+    }
     
-    // Process table
-    let sort_indicator = match app.process_sort {
-        ProcessSortMode::Cpu => "CPU ▼",
-        ProcessSortMode::Memory => "MEM ▼",
-        ProcessSortMode::Name => "NAME ▼",
-        ProcessSortMode::Pid => "PID ▼",
+    // Process table, with the columns from the config
+    let columns = &app.config.display.process_columns;
+    let sorted_column = match app.process_sort {
+        ProcessSortMode::Cpu => ProcessColumn::Cpu,
+        ProcessSortMode::Memory => ProcessColumn::Rss,
+        ProcessSortMode::Pss => ProcessColumn::Pss,
+        ProcessSortMode::Uss => ProcessColumn::Uss,
+        ProcessSortMode::DiskRead => ProcessColumn::Read,
+        ProcessSortMode::DiskWrite => ProcessColumn::Write,
+        ProcessSortMode::Name => ProcessColumn::Name,
+        ProcessSortMode::Pid => ProcessColumn::Pid,
     };
     
-    let processes = match app.process_sort {
-        ProcessSortMode::Cpu => app.metrics.top_processes_by_cpu(30),
-        ProcessSortMode::Memory => app.metrics.top_processes_by_memory(30),
-        ProcessSortMode::Name | ProcessSortMode::Pid => app.metrics.top_processes_by_cpu(30),
-    };
+    let processes = app.visible_processes();
     
     let rows: Vec<Row> = processes.iter().enumerate().map(|(idx, p)| {
         let style = if idx == app.selected_process_index {
@@ -773,25 +1318,144 @@ fn draw_processes_view(f: &mut Frame, app: &App) {
             Style::default().fg(Theme::TEXT)
         };
         
+        Row::new(columns.iter().map(|column| Cell::from(process_cell(*column, p, app))))
+            .style(style)
+    }).collect();
+
+    // A Name sort is marked on the command when only that is shown; any
+    // other sort on a hidden column is named in the title
+    let marked_column = if columns.contains(&sorted_column) {
+        Some(sorted_column)
+    } else if sorted_column == ProcessColumn::Name && columns.contains(&ProcessColumn::Command) {
+        Some(ProcessColumn::Command)
+    } else {
+        None
+    };
+    let mut title = if app.filter_input.is_empty() {
+        "PROCESSES".to_string()
+    } else {
+        format!("PROCESSES · filter: {}", app.filter_input)
+    };
+    if marked_column.is_none() {
+        title.push_str(&format!(" · by {} ▼", sorted_column.title()));
+    }
+    let headers: Vec<String> = columns.iter()
+        .map(|column| if Some(*column) == marked_column {
+            format!("{} ▼", column.title())
+        } else {
+            column.title().to_string()
+        })
+        .collect();
+    
+    let table = Table::new(rows, columns.iter().map(|column| process_column_width(*column)))
+    .header(Row::new(headers)
+        .style(Style::default()
+            .fg(Theme::LAVENDER)
+            .add_modifier(Modifier::BOLD))
+        .bottom_margin(1))
+    .block(Block::default()
+        .title(vec![
+            Span::styled("╭─ ", Style::default().fg(Theme::PINK)),
+            Span::styled(title, Style::default()
+                .fg(Theme::PINK)
+                .add_modifier(Modifier::BOLD)),
+            Span::styled(" ─╮", Style::default().fg(Theme::PINK)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::PINK))
+        .style(Style::default().bg(Theme::MANTLE)));
+    
+    f.render_widget(table, chunks[1]);
+    draw_footer(f, chunks[2], app);
+}
+
+fn process_column_width(column: ProcessColumn) -> Constraint {
+    match column {
+        ProcessColumn::Command => Constraint::Min(30),
+        ProcessColumn::Container | ProcessColumn::Name => Constraint::Length(16),
+        ProcessColumn::User => Constraint::Length(10),
+        ProcessColumn::Rss | ProcessColumn::Pss | ProcessColumn::Uss => Constraint::Length(12),
+        ProcessColumn::Read | ProcessColumn::Write => Constraint::Length(11),
+        ProcessColumn::Elapsed | ProcessColumn::CpuTime | ProcessColumn::Cpu => Constraint::Length(10),
+        ProcessColumn::Pid | ProcessColumn::Ppid | ProcessColumn::Start => Constraint::Length(8),
+        ProcessColumn::Threads | ProcessColumn::Fds => Constraint::Length(6),
+        ProcessColumn::Nice | ProcessColumn::Priority => Constraint::Length(4),
+        ProcessColumn::State => Constraint::Length(2),
+    }
+}
+
+/// Text of one process table cell; "-" where the value could not be read
+fn process_cell(column: ProcessColumn, p: &crate::metrics::system::ProcessInfo, app: &App) -> String {
+    let mb = |bytes: u64| format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0);
+    let stat = p.stat.as_ref();
+
+    let text = match column {
+        ProcessColumn::Pid => Some(p.pid.to_string()),
+        ProcessColumn::Ppid => stat.map(|s| s.ppid.to_string()),
+        ProcessColumn::User => Some(p.user.clone()),
+        ProcessColumn::State => stat.map(|s| s.state.to_string()),
+        ProcessColumn::Nice => stat.map(|s| s.nice.to_string()),
+        ProcessColumn::Priority => stat.map(|s| s.priority.to_string()),
+        ProcessColumn::Threads => stat.map(|s| s.threads.to_string()),
+        ProcessColumn::Start => stat.and_then(|s| s.start_time).map(format_start_time),
+        ProcessColumn::Elapsed => stat.and_then(|s| s.elapsed()).map(format_duration),
+        ProcessColumn::CpuTime => stat.map(|s| format_duration(s.cpu_time)),
+        ProcessColumn::Fds => stat.and_then(|s| s.fds).map(|n| n.to_string()),
+        ProcessColumn::Container => Some(app.containers.get(p.pid).map(|c| c.name.clone()).unwrap_or_default()),
+        ProcessColumn::Name => Some(p.name.clone()),
+        ProcessColumn::Command => Some(p.cmd.clone()),
+        ProcessColumn::Cpu => Some(format!("{:.1}%", p.cpu_usage)),
+        ProcessColumn::Rss => Some(mb(p.memory())),
+        ProcessColumn::Pss => p.memory_detail.map(|m| mb(m.pss)),
+        ProcessColumn::Uss => p.memory_detail.map(|m| mb(m.uss)),
+        ProcessColumn::Read => p.io.map(|io| format_rate(io.read_bytes_per_sec)),
+        ProcessColumn::Write => p.io.map(|io| format_rate(io.write_bytes_per_sec)),
+    };
+    text.unwrap_or_else(|| "-".to_string())
+}
+
+/// One row per container with the totals of its processes
+fn draw_container_groups(f: &mut Frame, area: Rect, app: &App) {
+    use ratatui::widgets::{Table, Row, Cell, TableState};
+
+    let mb = |bytes: u64| format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0);
+    let groups = app.container_groups();
+    let rows: Vec<Row> = groups.iter().enumerate().map(|(idx, group)| {
+        let style = if idx == app.selected_process_index {
+            Style::default()
+                .fg(Theme::CRUST)
+                .bg(Theme::PINK)
+                .add_modifier(Modifier::BOLD)
+        } else if group.container.is_none() {
+            Style::default().fg(Theme::SUBTEXT0)
+        } else {
+            Style::default().fg(Theme::TEXT)
+        };
+
         Row::new(vec![
-            Cell::from(format!("{}", p.pid)),
-            Cell::from(p.cmd.clone()),  // Show full command instead of just name
-            Cell::from(format!("{:.1}%", p.cpu_usage)),
-            Cell::from(format!("{:.1} MB", p.memory() as f64 / 1024.0 / 1024.0)),
+            Cell::from(group.label().to_string()),
+            Cell::from(group.container.as_ref().map(|c| c.runtime.name()).unwrap_or("")),
+            Cell::from(format!("{}", group.processes)),
+            Cell::from(format!("{:.1}%", group.cpu_usage)),
+            Cell::from(mb(group.memory)),
+            Cell::from(if group.gpu_memory > 0 { mb(group.gpu_memory) } else { "-".to_string() }),
         ])
         .style(style)
     }).collect();
-    
+
     let table = Table::new(
         rows,
         [
-            ratatui::layout::Constraint::Length(8),
-            ratatui::layout::Constraint::Min(30),
-            ratatui::layout::Constraint::Length(10),
-            ratatui::layout::Constraint::Length(12),
+            Constraint::Min(24),
+            Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(12),
         ]
     )
-    .header(Row::new(vec!["PID", "Command", sort_indicator, "Memory"])
+    .header(Row::new(vec!["Container", "Runtime", "Procs", "CPU", "Memory", "GPU Mem"])
         .style(Style::default()
             .fg(Theme::LAVENDER)
             .add_modifier(Modifier::BOLD))
@@ -799,7 +1463,7 @@ fn draw_processes_view(f: &mut Frame, app: &App) {
     .block(Block::default()
         .title(vec![
             Span::styled("╭─ ", Style::default().fg(Theme::PINK)),
-            Span::styled("PROCESSES", Style::default()
+            Span::styled("CONTAINERS", Style::default()
                 .fg(Theme::PINK)
                 .add_modifier(Modifier::BOLD)),
             Span::styled(" ─╮", Style::default().fg(Theme::PINK)),
@@ -808,9 +1472,138 @@ fn draw_processes_view(f: &mut Frame, app: &App) {
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(Theme::PINK))
         .style(Style::default().bg(Theme::MANTLE)));
-    
-    f.render_widget(table, chunks[1]);
-    draw_footer(f, chunks[2], app);
+
+    let mut state = TableState::default().with_selected(Some(app.selected_process_index));
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+/// cgroup hierarchy with subtree totals, and the processes of the
+/// selected cgroup below it
+fn draw_cgroups_view(f: &mut Frame, app: &App) {
+    use ratatui::widgets::{Table, Row, Cell, TableState};
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),       // Header
+            Constraint::Min(10),         // cgroup tree
+            Constraint::Percentage(30),  // Member processes
+            Constraint::Length(3),       // Footer
+        ])
+        .split(f.area());
+
+    draw_header(f, chunks[0], app);
+
+    let mb = |bytes: u64| format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0);
+    let visible = app.cgroups.visible(&app.collapsed_cgroups);
+    let selected_index = app.selected_cgroup_index();
+    let rows: Vec<Row> = visible.iter().enumerate().map(|(idx, (node, depth))| {
+        let style = if idx == selected_index {
+            Style::default()
+                .fg(Theme::CRUST)
+                .bg(Theme::GREEN)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Theme::TEXT)
+        };
+        let marker = if node.children.is_empty() {
+            " "
+        } else if app.collapsed_cgroups.contains(&node.path) {
+            "▶"
+        } else {
+            "▼"
+        };
+        let memory = match (node.memory_current, node.memory_max) {
+            (Some(current), Some(max)) => format!("{} / {}", mb(current), mb(max)),
+            (Some(current), None) => mb(current),
+            _ => "-".to_string(),
+        };
+
+        Row::new(vec![
+            Cell::from(format!("{}{} {}", "  ".repeat(*depth), marker, node.name)),
+            Cell::from(format!("{:.1}%", node.cpu_percent)),
+            Cell::from(memory),
+            Cell::from(format!("{} / {}",
+                format_rate(node.io_read_bytes_per_sec), format_rate(node.io_write_bytes_per_sec))),
+            Cell::from(node.pids_current.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string())),
+        ])
+        .style(style)
+    }).collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(30),
+            Constraint::Length(8),
+            Constraint::Length(24),
+            Constraint::Length(24),
+            Constraint::Length(6),
+        ]
+    )
+    .header(Row::new(vec!["cgroup", "CPU", "Memory / Max", "IO Read / Write", "Tasks"])
+        .style(Style::default()
+            .fg(Theme::LAVENDER)
+            .add_modifier(Modifier::BOLD))
+        .bottom_margin(1))
+    .block(Block::default()
+        .title(vec![
+            Span::styled("╭─ ", Style::default().fg(Theme::GREEN)),
+            Span::styled("CGROUPS", Style::default()
+                .fg(Theme::GREEN)
+                .add_modifier(Modifier::BOLD)),
+            Span::styled(" ─╮", Style::default().fg(Theme::GREEN)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::GREEN))
+        .style(Style::default().bg(Theme::MANTLE)));
+
+    // Keep the selection in view on long hierarchies
+    let mut state = TableState::default().with_selected(Some(selected_index));
+    f.render_stateful_widget(table, chunks[1], &mut state);
+
+    let selected = app.selected_cgroup();
+    let title = selected
+        .map(|node| if node.path.is_empty() { "/".to_string() } else { node.path.clone() })
+        .unwrap_or_default();
+    let process_rows: Vec<Row> = app.selected_cgroup_processes().iter().map(|p| {
+        Row::new(vec![
+            Cell::from(format!("{}", p.pid)),
+            Cell::from(p.cmd.clone()),
+            Cell::from(format!("{:.1}%", p.cpu_usage)),
+            Cell::from(format!("{:.1} MB", p.memory() as f64 / 1024.0 / 1024.0)),
+        ])
+        .style(Style::default().fg(Theme::TEXT))
+    }).collect();
+
+    let processes = Table::new(
+        process_rows,
+        [
+            Constraint::Length(8),
+            Constraint::Min(30),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ]
+    )
+    .header(Row::new(vec!["PID", "Command", "CPU", "Memory"])
+        .style(Style::default()
+            .fg(Theme::LAVENDER)
+            .add_modifier(Modifier::BOLD)))
+    .block(Block::default()
+        .title(vec![
+            Span::styled("╭─ ", Style::default().fg(Theme::PINK)),
+            Span::styled(format!("Processes in {}", title), Style::default()
+                .fg(Theme::PINK)
+                .add_modifier(Modifier::BOLD)),
+            Span::styled(" ─╮", Style::default().fg(Theme::PINK)),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Theme::PINK))
+        .style(Style::default().bg(Theme::MANTLE)));
+
+    f.render_widget(processes, chunks[2]);
+    draw_footer(f, chunks[3], app);
 }
 
 fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
@@ -830,6 +1623,7 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
         ViewMode::Dashboard => "Processes",
         ViewMode::Processes => "Dashboard",
         ViewMode::History => "Dashboard",
+        ViewMode::Cgroups => "Dashboard",
     };
     
     // Context-aware footer based on current view
@@ -857,6 +1651,21 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
                     .add_modifier(Modifier::BOLD)),
                 Span::styled(" Pause", Style::default().fg(Theme::TEXT)),
                 Span::raw("  │  "),
+                Span::styled("[g]", Style::default()
+                    .fg(Theme::PEACH)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" GPU Control", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
+                Span::styled("[v]", Style::default()
+                    .fg(Theme::SAPPHIRE)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" GPU Details", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
+                Span::styled("[c]", Style::default()
+                    .fg(Theme::GREEN)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" Cgroups", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
                 status_text,
             ]),
         ],
@@ -898,6 +1707,38 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App) {
                     .add_modifier(Modifier::BOLD)),
                 Span::styled(" Info", Style::default().fg(Theme::TEXT)),
                 Span::raw("  │  "),
+                Span::styled("[b]", Style::default()
+                    .fg(Theme::GREEN)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(if app.group_by_container { " Ungroup  [Enter] Filter" } else { " By container" },
+                    Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
+                status_text,
+            ]),
+        ],
+        ViewMode::Cgroups => vec![
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled("[q]", Style::default()
+                    .fg(Theme::MAUVE)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" Quit", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
+                Span::styled("[c]", Style::default()
+                    .fg(Theme::GREEN)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" Dashboard", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
+                Span::styled("[↑↓]", Style::default()
+                    .fg(Theme::LAVENDER)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" Select", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
+                Span::styled("[← →]", Style::default()
+                    .fg(Theme::TEAL)
+                    .add_modifier(Modifier::BOLD)),
+                Span::styled(" Collapse/Expand", Style::default().fg(Theme::TEXT)),
+                Span::raw("  │  "),
                 status_text,
             ]),
         ],
@@ -941,6 +1782,14 @@ fn draw_info_dialog(f: &mut Frame, app: &App) {
     super::dialogs::draw_info_dialog(f, app);
 }
 
+fn draw_gpu_control_dialog(f: &mut Frame, app: &App) {
+    super::dialogs::draw_gpu_control_dialog(f, app);
+}
+
+fn draw_gpu_details_dialog(f: &mut Frame, app: &App) {
+    super::dialogs::draw_gpu_details_dialog(f, app);
+}
+
 fn draw_history_view(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -1033,10 +1882,14 @@ fn draw_timeline(f: &mut Frame, area: Rect, app: &App) {
 }
 
 fn draw_historical_metrics(f: &mut Frame, area: Rect, app: &App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
+        .split(rows[0]);
 
     // CPU at this timestamp
     if let Some(idx) = app.playback_index {
@@ -1079,9 +1932,64 @@ fn draw_historical_metrics(f: &mut Frame, area: Rect, app: &App) {
             
             f.render_widget(gauge, chunks[1]);
         }
+
+        if let Some((timestamp, _)) = app.history.cpu_usage.get_at(idx) {
+            draw_historical_gpus(f, rows[1], app, timestamp);
+        }
     }
 }
 
+/// GPU metrics recorded at `timestamp`, one line per GPU
+fn draw_historical_gpus(f: &mut Frame, area: Rect, app: &App, timestamp: u64) {
+    let mut lines = Vec::new();
+    for gpu in &app.history.gpus {
+        let metrics = [
+            ("Temp", gpu.temp.value_at(timestamp).map(|v| format!("{:.1}°C", v)), Theme::PEACH),
+            ("Usage", gpu.util.value_at(timestamp).map(|v| format!("{:.1}%", v)), Theme::GREEN),
+            ("Mem", gpu.mem.value_at(timestamp).map(|v| format!("{:.1}%", v)), Theme::PINK),
+            ("Power", gpu.power.value_at(timestamp).map(|v| format!("{:.1}W", v)), Theme::YELLOW),
+            ("Core", gpu.clock.value_at(timestamp).map(|v| format!("{:.0}MHz", v)), Theme::BLUE),
+            ("Mem clk", gpu.mem_clock.value_at(timestamp).map(|v| format!("{:.0}MHz", v)), Theme::TEAL),
+            ("Fan", gpu.fan.value_at(timestamp).map(|v| format!("{:.0}%", v))
+                .or_else(|| gpu.fan_rpm.value_at(timestamp).map(|v| format!("{:.0} RPM", v))), Theme::SKY),
+        ];
+        if metrics.iter().all(|(_, value, _)| value.is_none()) {
+            continue;
+        }
+
+        let mut spans = vec![
+            Span::styled(format!(" ◆ {}  ", gpu.name), Style::default()
+                .fg(Theme::MAUVE)
+                .add_modifier(Modifier::BOLD)),
+        ];
+        for (label, value, color) in metrics {
+            let Some(value) = value else { continue };
+            spans.push(Span::styled(format!("{}: ", label), Style::default().fg(Theme::SUBTEXT0)));
+            spans.push(Span::styled(value, Style::default().fg(color)));
+            spans.push(Span::raw("  "));
+        }
+        if let Some(limit) = gpu.power_limit {
+            spans.push(Span::styled(format!("(cap {:.0}W)", limit), Style::default().fg(Theme::SUBTEXT1)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled("No GPU data at this point",
+            Style::default().fg(Theme::SUBTEXT0).add_modifier(Modifier::ITALIC))));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default()
+            .title("GPU (Historical)")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Theme::PEACH))
+            .style(Style::default().bg(Theme::MANTLE)));
+
+    f.render_widget(paragraph, area);
+}
+
 fn draw_history_footer(f: &mut Frame, area: Rect, _app: &App) {
     let footer_text = vec![
         Line::from(vec![
@@ -1113,3 +2021,76 @@ fn draw_history_footer(f: &mut Frame, area: Rect, _app: &App) {
     
     f.render_widget(footer, area);
 }
+
+/// Fixed-width bar for use inside text lines, e.g. "████░░░░░░"
+fn text_bar(percent: f32, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f32).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Byte rate with a binary unit, e.g. "12.3 MB/s"
+fn format_rate(bytes_per_sec: f64) -> String {
+    const UNITS: [&str; 4] = ["B/s", "KB/s", "MB/s", "GB/s"];
+    let mut value = bytes_per_sec;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{:.0} {}", value, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// "2d 03:04:05", "03:04:05" or "04:05"
+pub(super) fn format_duration(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, seconds) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Time of day for processes started today, else the date
+fn format_start_time(timestamp: u64) -> String {
+    use chrono::{Local, TimeZone};
+    let Some(start) = Local.timestamp_opt(timestamp as i64, 0).single() else {
+        return "-".to_string();
+    };
+    if start.date_naive() == Local::now().date_naive() {
+        start.format("%H:%M").to_string()
+    } else {
+        start.format("%b %d").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "00:00");
+        assert_eq!(format_duration(Duration::from_secs(245)), "04:05");
+        assert_eq!(format_duration(Duration::from_secs(3600 + 4 * 60 + 5)), "01:04:05");
+        assert_eq!(format_duration(Duration::from_secs(2 * 86400 + 3 * 3600 + 4 * 60 + 5)), "2d 03:04:05");
+    }
+
+    #[test]
+    fn test_format_start_time() {
+        let now = Local::now().timestamp() as u64;
+        let time_of_day = Local.timestamp_opt(now as i64, 0).unwrap().format("%H:%M").to_string();
+        assert_eq!(format_start_time(now), time_of_day);
+
+        let earlier = Local.with_ymd_and_hms(2020, 1, 2, 3, 4, 5).unwrap().timestamp() as u64;
+        assert_eq!(format_start_time(earlier), "Jan 02");
+    }
+}